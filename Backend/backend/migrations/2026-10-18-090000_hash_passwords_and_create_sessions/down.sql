@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+-- Hashed passwords cannot be turned back into plaintext, so they are left as is.
+DROP TABLE sessions;
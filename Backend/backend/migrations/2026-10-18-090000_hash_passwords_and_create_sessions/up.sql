@@ -0,0 +1,13 @@
+-- Your SQL goes here
+-- Passwords used to be stored in plaintext; hash the existing rows with bcrypt
+-- so they can be verified by the same code path as new signups.
+CREATE EXTENSION IF NOT EXISTS pgcrypto;
+UPDATE users SET password = crypt(password, gen_salt('bf', 12))
+WHERE password NOT LIKE '$2_$%';
+
+CREATE TABLE sessions (
+    token TEXT PRIMARY KEY,
+    email TEXT NOT NULL REFERENCES users(email) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL
+);
@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX transactions_email_date_idx;
+ALTER TABLE transactions ALTER COLUMN transaction_date DROP DEFAULT;
+ALTER TABLE transactions
+    ALTER COLUMN transaction_date TYPE TEXT
+    USING to_char(transaction_date AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS.US') || ' UTC';
@@ -0,0 +1,35 @@
+-- Your SQL goes here
+-- Rows written by `Utc::now().to_string()` look like `2024-12-08 05:07:18.906680 UTC`,
+-- which Postgres reads as a timestamp directly. Any other value stops the migration with
+-- the rows to repair, guessing a date would move them into another budget period.
+CREATE FUNCTION pg_temp.try_timestamptz(raw TEXT) RETURNS TIMESTAMPTZ AS $$
+BEGIN
+    RETURN raw::TIMESTAMPTZ;
+EXCEPTION WHEN others THEN
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DO $$
+DECLARE
+    unreadable TEXT;
+BEGIN
+    SELECT string_agg(format('trans_id %s (%L)', trans_id, transaction_date), ', ' ORDER BY trans_id)
+    INTO unreadable
+    FROM transactions
+    WHERE pg_temp.try_timestamptz(transaction_date) IS NULL;
+    IF unreadable IS NOT NULL THEN
+        RAISE EXCEPTION 'transaction_date is not a timestamp for %, fix these rows and run the migration again', unreadable;
+    END IF;
+END
+$$;
+
+ALTER TABLE transactions
+    ALTER COLUMN transaction_date TYPE TIMESTAMPTZ
+    USING pg_temp.try_timestamptz(transaction_date);
+ALTER TABLE transactions ALTER COLUMN transaction_date SET DEFAULT NOW();
+
+DROP FUNCTION pg_temp.try_timestamptz(TEXT);
+
+-- Reports filter every user's transactions by date
+CREATE INDEX transactions_email_date_idx ON transactions (email, transaction_date);
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE categories ALTER COLUMN budget TYPE FLOAT8 USING budget::FLOAT8;
+ALTER TABLE transactions ALTER COLUMN amount TYPE FLOAT8 USING amount::FLOAT8;
@@ -0,0 +1,4 @@
+-- Your SQL goes here
+-- FLOAT8 -> NUMERIC keeps 15 significant digits, so a stored 12.299999999999999 becomes 12.3
+ALTER TABLE transactions ALTER COLUMN amount TYPE NUMERIC USING amount::NUMERIC;
+ALTER TABLE categories ALTER COLUMN budget TYPE NUMERIC USING budget::NUMERIC;
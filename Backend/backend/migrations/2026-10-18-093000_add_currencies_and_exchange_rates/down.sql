@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE exchange_rates;
+ALTER TABLE transactions DROP COLUMN currency;
+ALTER TABLE accounts DROP COLUMN currency;
@@ -0,0 +1,14 @@
+-- Your SQL goes here
+ALTER TABLE accounts ADD COLUMN currency TEXT NOT NULL DEFAULT 'CAD';
+
+-- NULL means the transaction is in the currency of its account
+ALTER TABLE transactions ADD COLUMN currency TEXT;
+
+-- Shared by all users, `rate` is how many `to_currency` one `from_currency` buys on `rate_date`
+CREATE TABLE exchange_rates (
+    rate_date DATE NOT NULL,
+    from_currency TEXT NOT NULL,
+    to_currency TEXT NOT NULL,
+    rate NUMERIC NOT NULL CHECK (rate > 0),
+    PRIMARY KEY (rate_date, from_currency, to_currency)
+);
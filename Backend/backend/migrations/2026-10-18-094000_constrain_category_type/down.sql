@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE categories DROP CONSTRAINT categories_category_type_check;
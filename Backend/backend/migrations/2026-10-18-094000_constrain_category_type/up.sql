@@ -0,0 +1,9 @@
+-- Your SQL goes here
+-- Free-text types written before the constraint become expense categories
+UPDATE categories SET category_type = LOWER(TRIM(category_type));
+UPDATE categories SET category_type = 'expense'
+WHERE category_type NOT IN ('income', 'expense', 'transfer');
+
+ALTER TABLE categories
+ADD CONSTRAINT categories_category_type_check
+CHECK (category_type IN ('income', 'expense', 'transfer'));
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE users DROP COLUMN period_start_day;
+ALTER TABLE users DROP COLUMN time_zone;
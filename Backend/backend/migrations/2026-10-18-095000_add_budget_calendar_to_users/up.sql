@@ -0,0 +1,6 @@
+-- Your SQL goes here
+-- Budget periods follow the owner's calendar: days, ISO weeks, months and years in their time zone
+ALTER TABLE users ADD COLUMN time_zone TEXT NOT NULL DEFAULT 'UTC';
+-- Day of the month a budget month starts on (e.g. payday), capped at 28 so every month has it
+ALTER TABLE users ADD COLUMN period_start_day INTEGER NOT NULL DEFAULT 1
+CHECK (period_start_day BETWEEN 1 AND 28);
@@ -0,0 +1,4 @@
+-- Your SQL goes here
+-- What the previous budget period leaves to the next one: nothing, its unused budget or its overspend
+ALTER TABLE categories ADD COLUMN rollover TEXT NOT NULL DEFAULT 'none'
+CHECK (rollover IN ('none', 'carry_unused', 'carry_overspend'));
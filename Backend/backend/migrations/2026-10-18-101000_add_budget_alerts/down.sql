@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE notifications;
+ALTER TABLE categories DROP COLUMN alert_thresholds;
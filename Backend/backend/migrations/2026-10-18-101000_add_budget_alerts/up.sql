@@ -0,0 +1,18 @@
+-- Your SQL goes here
+-- Percentages of the budget that raise a notification the first time spending reaches them
+ALTER TABLE categories ADD COLUMN alert_thresholds INTEGER[] NOT NULL DEFAULT '{100}';
+
+CREATE TABLE notifications (
+    notification_id SERIAL PRIMARY KEY,
+    email TEXT NOT NULL REFERENCES users(email) ON DELETE CASCADE,
+    category_id INT NOT NULL REFERENCES categories(category_id) ON DELETE CASCADE,
+    threshold INT NOT NULL,
+    -- start of the budget period the threshold was reached in, one alert per period
+    period_start TIMESTAMPTZ NOT NULL,
+    message TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    read_at TIMESTAMPTZ,
+    UNIQUE (category_id, threshold, period_start)
+);
+
+CREATE INDEX notifications_email_created_at_idx ON notifications (email, created_at);
@@ -0,0 +1,23 @@
+-- Your SQL goes here
+-- Templates posted into `transactions` every time an occurrence comes due
+CREATE TABLE recurring_transactions (
+    recurring_id SERIAL PRIMARY KEY,
+    email TEXT NOT NULL REFERENCES users(email) ON DELETE CASCADE,
+    category_id INT NOT NULL REFERENCES categories(category_id) ON DELETE CASCADE,
+    account_id INT NOT NULL REFERENCES accounts(account_id) ON DELETE CASCADE,
+    amount NUMERIC NOT NULL,
+    notes TEXT,
+    currency TEXT,
+    -- every `interval_count` days, weeks, months or years from `start_date`
+    frequency TEXT NOT NULL CHECK (frequency IN ('daily', 'weekly', 'monthly', 'yearly')),
+    interval_count INT NOT NULL DEFAULT 1 CHECK (interval_count >= 1),
+    start_date DATE NOT NULL,
+    end_date DATE,
+    -- occurrences posted since `start_date`, the next one is due on `next_date`
+    posted_count INT NOT NULL DEFAULT 0,
+    next_date DATE NOT NULL,
+    paused BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE INDEX recurring_transactions_next_date_idx ON recurring_transactions (next_date)
+WHERE NOT paused;
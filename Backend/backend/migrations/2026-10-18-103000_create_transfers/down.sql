@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+DELETE FROM transactions WHERE transfer_id IS NOT NULL;
+ALTER TABLE transactions DROP CONSTRAINT transactions_category_or_transfer;
+ALTER TABLE transactions DROP COLUMN transfer_id;
+ALTER TABLE transactions ALTER COLUMN category_id SET NOT NULL;
+DROP TABLE transfers;
@@ -0,0 +1,16 @@
+-- Your SQL goes here
+-- A transfer is two linked transactions without a category, one leg per account
+CREATE TABLE transfers (
+    transfer_id SERIAL PRIMARY KEY,
+    email TEXT NOT NULL REFERENCES users(email) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE transactions ALTER COLUMN category_id DROP NOT NULL;
+ALTER TABLE transactions
+    ADD COLUMN transfer_id INT REFERENCES transfers(transfer_id) ON DELETE CASCADE;
+-- every transaction is either categorized or a transfer leg
+ALTER TABLE transactions ADD CONSTRAINT transactions_category_or_transfer
+    CHECK ((category_id IS NULL) <> (transfer_id IS NULL));
+
+CREATE INDEX transactions_transfer_id_idx ON transactions (transfer_id);
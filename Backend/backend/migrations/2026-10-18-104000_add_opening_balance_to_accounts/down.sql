@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE accounts DROP COLUMN opening_date;
+ALTER TABLE accounts DROP COLUMN opening_balance;
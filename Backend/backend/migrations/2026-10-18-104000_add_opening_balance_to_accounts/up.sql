@@ -0,0 +1,5 @@
+-- Your SQL goes here
+-- What an account held (or, for a credit account, owed) on its opening date.
+-- Balances count the transactions from that day on, a missing date counts them all.
+ALTER TABLE accounts ADD COLUMN opening_balance NUMERIC NOT NULL DEFAULT 0;
+ALTER TABLE accounts ADD COLUMN opening_date DATE;
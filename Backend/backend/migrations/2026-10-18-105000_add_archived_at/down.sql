@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE categories DROP COLUMN archived_at;
+ALTER TABLE accounts DROP COLUMN archived_at;
@@ -0,0 +1,5 @@
+-- Your SQL goes here
+-- Archived accounts and categories are hidden from pickers and summaries, their
+-- transactions stay. NULL means active.
+ALTER TABLE accounts ADD COLUMN archived_at TIMESTAMPTZ;
+ALTER TABLE categories ADD COLUMN archived_at TIMESTAMPTZ;
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+DELETE FROM transactions WHERE split_id IS NOT NULL;
+ALTER TABLE transactions DROP CONSTRAINT transactions_split_has_category;
+ALTER TABLE transactions DROP COLUMN split_id;
+DROP TABLE splits;
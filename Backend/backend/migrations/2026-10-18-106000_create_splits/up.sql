@@ -0,0 +1,21 @@
+-- Your SQL goes here
+-- A split is one payment from an account spread over several categories,
+-- each category line is a transaction pointing back to it
+CREATE TABLE splits (
+    split_id SERIAL PRIMARY KEY,
+    email TEXT NOT NULL REFERENCES users(email) ON DELETE CASCADE,
+    account_id INT NOT NULL REFERENCES accounts(account_id) ON DELETE CASCADE,
+    amount NUMERIC NOT NULL,
+    notes TEXT,
+    transaction_date TIMESTAMPTZ NOT NULL,
+    currency TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE transactions
+    ADD COLUMN split_id INT REFERENCES splits(split_id) ON DELETE CASCADE;
+-- split lines are categorized transactions, never transfer legs
+ALTER TABLE transactions ADD CONSTRAINT transactions_split_has_category
+    CHECK (split_id IS NULL OR category_id IS NOT NULL);
+
+CREATE INDEX transactions_split_id_idx ON transactions (split_id);
@@ -0,0 +1,16 @@
+-- Your SQL goes here
+-- Free-form tags, shared by every transaction of their owner that uses them
+CREATE TABLE tags (
+    tag_id SERIAL PRIMARY KEY,
+    email TEXT NOT NULL REFERENCES users(email) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    UNIQUE (email, name)
+);
+
+CREATE TABLE transaction_tags (
+    trans_id INT NOT NULL REFERENCES transactions(trans_id) ON DELETE CASCADE,
+    tag_id INT NOT NULL REFERENCES tags(tag_id) ON DELETE CASCADE,
+    PRIMARY KEY (trans_id, tag_id)
+);
+
+CREATE INDEX transaction_tags_tag_id_idx ON transaction_tags (tag_id);
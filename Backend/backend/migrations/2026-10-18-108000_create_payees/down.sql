@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE transactions DROP COLUMN payee_id;
+DROP TABLE payees;
@@ -0,0 +1,15 @@
+-- Your SQL goes here
+-- Payees (merchants, employers, ...) of a user, created the first time a transaction names them
+CREATE TABLE payees (
+    payee_id SERIAL PRIMARY KEY,
+    email TEXT NOT NULL REFERENCES users(email) ON DELETE CASCADE,
+    name TEXT NOT NULL
+);
+
+-- `Starbucks` and `STARBUCKS` are the same payee
+CREATE UNIQUE INDEX payees_email_name_idx ON payees (email, lower(name));
+
+ALTER TABLE transactions
+    ADD COLUMN payee_id INT REFERENCES payees(payee_id) ON DELETE SET NULL;
+
+CREATE INDEX transactions_payee_id_idx ON transactions (payee_id);
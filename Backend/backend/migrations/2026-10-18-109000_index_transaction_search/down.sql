@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX transactions_notes_trgm_idx;
+DROP INDEX transactions_email_amount_id_idx;
+DROP INDEX transactions_email_date_id_idx;
+CREATE INDEX transactions_email_date_idx ON transactions (email, transaction_date);
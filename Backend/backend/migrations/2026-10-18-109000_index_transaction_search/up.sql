@@ -0,0 +1,8 @@
+-- Your SQL goes here
+-- /transactions pages through a user's transactions by date or amount, `trans_id` breaks ties
+DROP INDEX transactions_email_date_idx;
+CREATE INDEX transactions_email_date_id_idx ON transactions (email, transaction_date, trans_id);
+CREATE INDEX transactions_email_amount_id_idx ON transactions (email, amount, trans_id);
+-- `q` matches a substring of the notes anywhere, trigrams let the index serve `ILIKE '%…%'`
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+CREATE INDEX transactions_notes_trgm_idx ON transactions USING GIN (notes gin_trgm_ops);
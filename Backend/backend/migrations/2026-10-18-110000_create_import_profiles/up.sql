@@ -0,0 +1,20 @@
+-- Your SQL goes here
+-- How the CSV statements of an account map to transactions, saved by the last import
+CREATE TABLE import_profiles (
+    profile_id SERIAL PRIMARY KEY,
+    email TEXT NOT NULL REFERENCES users(email) ON DELETE CASCADE,
+    account_id INT NOT NULL UNIQUE REFERENCES accounts(account_id) ON DELETE CASCADE,
+    category_id INT REFERENCES categories(category_id) ON DELETE SET NULL,
+    has_header BOOLEAN NOT NULL DEFAULT TRUE,
+    date_column TEXT NOT NULL,
+    date_format TEXT NOT NULL DEFAULT '%Y-%m-%d',
+    amount_column TEXT,
+    debit_column TEXT,
+    credit_column TEXT,
+    description_column TEXT,
+    payee_column TEXT,
+    sign_convention TEXT NOT NULL DEFAULT 'negative_outflow'
+        CHECK (sign_convention IN ('negative_outflow', 'positive_outflow')),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (amount_column IS NOT NULL OR debit_column IS NOT NULL OR credit_column IS NOT NULL)
+);
@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE import_profiles DROP COLUMN inflow_category_id;
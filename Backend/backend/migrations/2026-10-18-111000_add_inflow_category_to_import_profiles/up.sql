@@ -0,0 +1,4 @@
+-- Your SQL goes here
+-- Rows bringing money in go to this category instead of `category_id`, e.g. refunds or pay
+ALTER TABLE import_profiles
+    ADD COLUMN inflow_category_id INT REFERENCES categories(category_id) ON DELETE SET NULL;
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+-- Accounts are looked up by name, so a user cannot have two with the same one
+ALTER TABLE accounts ADD CONSTRAINT unique_account_name UNIQUE (email, account_name);
@@ -0,0 +1,238 @@
+use crate::schema::exchange_rates::dsl::{
+    exchange_rates, from_currency, rate, rate_date, to_currency,
+};
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use rust_decimal::{Decimal, RoundingStrategy};
+use std::collections::HashMap;
+
+// Currency of accounts and reports that do not name one
+pub const DEFAULT_CURRENCY: &str = "CAD";
+
+// Currency codes are three letters (ISO 4217), stored upper case
+pub fn normalize_currency(code: &str) -> Option<String> {
+    let code = code.trim();
+    if code.len() == 3 && code.chars().all(|c| c.is_ascii_alphabetic()) {
+        Some(code.to_ascii_uppercase())
+    } else {
+        None
+    }
+}
+
+// Dated rates between currency pairs, converting into a single base currency
+pub struct RateTable {
+    base: String,
+    // (from, to) -> (day, units of `to` per unit of `from`), sorted by day
+    quotes: HashMap<(String, String), Vec<(NaiveDate, Decimal)>>,
+}
+
+impl RateTable {
+    // The rates converting into `base` on any of `days`: the pairs involving `base` or
+    // the default currency, quoted within those days or last before or first after them
+    pub fn load(
+        conn: &mut PgConnection,
+        base: &str,
+        days: impl IntoIterator<Item = NaiveDate>,
+    ) -> QueryResult<Self> {
+        let mut days = days.into_iter();
+        let Some(first_day) = days.next() else {
+            return Ok(Self::from_rows(base, vec![]));
+        };
+        let (first_day, last_day) = days.fold((first_day, first_day), |(first, last), day| {
+            (first.min(day), last.max(day))
+        });
+        let pair_currencies = [base, DEFAULT_CURRENCY];
+        let involved = || {
+            from_currency
+                .eq_any(pair_currencies)
+                .or(to_currency.eq_any(pair_currencies))
+        };
+        let mut rows = exchange_rates
+            .filter(involved())
+            .filter(rate_date.between(first_day, last_day))
+            .select((rate_date, from_currency, to_currency, rate))
+            .load::<(NaiveDate, String, String, Decimal)>(conn)?;
+        // a day before the first rate of the range takes the last one published before it
+        rows.extend(
+            exchange_rates
+                .filter(involved())
+                .filter(rate_date.lt(first_day))
+                .distinct_on((from_currency, to_currency))
+                .order_by((from_currency, to_currency, rate_date.desc()))
+                .select((rate_date, from_currency, to_currency, rate))
+                .load::<(NaiveDate, String, String, Decimal)>(conn)?,
+        );
+        // and a pair without any rate up to the last day takes the first one after it
+        rows.extend(
+            exchange_rates
+                .filter(involved())
+                .filter(rate_date.gt(last_day))
+                .distinct_on((from_currency, to_currency))
+                .order_by((from_currency, to_currency, rate_date.asc()))
+                .select((rate_date, from_currency, to_currency, rate))
+                .load::<(NaiveDate, String, String, Decimal)>(conn)?,
+        );
+        Ok(Self::from_rows(base, rows))
+    }
+
+    // Every stored rate is also usable in the opposite direction
+    fn from_rows(base: &str, rows: Vec<(NaiveDate, String, String, Decimal)>) -> Self {
+        let mut quotes: HashMap<(String, String), Vec<(NaiveDate, Decimal)>> = HashMap::new();
+        let mut inverted: Vec<((String, String), NaiveDate, Decimal)> = vec![];
+        for (day, from, to, quoted) in rows {
+            if !quoted.is_zero() {
+                inverted.push(((to.clone(), from.clone()), day, Decimal::ONE / quoted));
+            }
+            quotes.entry((from, to)).or_default().push((day, quoted));
+        }
+        // a quoted rate wins over the inverse of the opposite quote for the same day
+        for (pair, day, derived) in inverted {
+            let dated = quotes.entry(pair).or_default();
+            if !dated.iter().any(|(quoted_day, _)| *quoted_day == day) {
+                dated.push((day, derived));
+            }
+        }
+        for dated in quotes.values_mut() {
+            dated.sort_by_key(|(day, _)| *day);
+        }
+
+        Self {
+            base: base.to_string(),
+            quotes,
+        }
+    }
+
+    pub fn base(&self) -> &str {
+        &self.base
+    }
+
+    // The latest rate published on or before `day`, or the earliest one after it
+    fn rate_on(&self, from: &str, to: &str, day: NaiveDate) -> Option<Decimal> {
+        let dated = self.quotes.get(&(from.to_string(), to.to_string()))?;
+        let published = dated.partition_point(|(rate_day, _)| *rate_day <= day);
+        dated
+            .get(published.saturating_sub(1))
+            .map(|(_, day_rate)| *day_rate)
+    }
+
+    // Pairs without a rate of their own go through the default currency.
+    // Converted amounts are rounded to cents.
+    pub fn convert(&self, amount: Decimal, currency: &str, day: NaiveDate) -> Result<Decimal, String> {
+        if currency == self.base {
+            return Ok(amount);
+        }
+        let day_rate = self.rate_on(currency, &self.base, day).or_else(|| {
+            Some(
+                self.rate_on(currency, DEFAULT_CURRENCY, day)?
+                    * self.rate_on(DEFAULT_CURRENCY, &self.base, day)?,
+            )
+        });
+        match day_rate {
+            Some(day_rate) => Ok((amount * day_rate)
+                .round_dp_with_strategy(2, RoundingStrategy::MidpointAwayFromZero)),
+            None => Err(format!(
+                "No exchange rate from {} to {}",
+                currency, self.base
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RateTable;
+    use chrono::NaiveDate;
+    use rust_decimal::Decimal;
+
+    fn day(text: &str) -> NaiveDate {
+        text.parse().unwrap()
+    }
+
+    fn value(text: &str) -> Result<Decimal, String> {
+        Ok(text.parse().unwrap())
+    }
+
+    fn table(base: &str) -> RateTable {
+        let rows = [
+            ("2026-10-01", "USD", "CAD", "1.40"),
+            ("2026-10-01", "CAD", "EUR", "0.66"),
+            ("2026-10-01", "GBP", "CAD", "1.25"),
+            ("2026-10-15", "USD", "CAD", "1.35"),
+            ("2026-10-15", "CAD", "USD", "0.70"),
+            ("2026-10-01", "CHF", "CAD", "0"),
+        ];
+        let rows = rows
+            .into_iter()
+            .map(|(on, from, to, quoted)| {
+                (
+                    day(on),
+                    from.to_string(),
+                    to.to_string(),
+                    quoted.parse().unwrap(),
+                )
+            })
+            .collect();
+        RateTable::from_rows(base, rows)
+    }
+
+    fn convert(base: &str, amount: &str, currency: &str, on: &str) -> Result<Decimal, String> {
+        table(base).convert(amount.parse().unwrap(), currency, day(on))
+    }
+
+    #[test]
+    fn keeps_amounts_already_in_base() {
+        assert_eq!(
+            convert("CAD", "12.345", "CAD", "2026-10-05"),
+            value("12.345")
+        );
+        assert_eq!(convert("JPY", "100", "JPY", "2026-10-05"), value("100"));
+    }
+
+    #[test]
+    fn uses_the_latest_rate_on_or_before_the_day() {
+        assert_eq!(convert("CAD", "10", "USD", "2026-10-01"), value("14.00"));
+        assert_eq!(convert("CAD", "10", "USD", "2026-10-14"), value("14.00"));
+        assert_eq!(convert("CAD", "10", "USD", "2026-10-15"), value("13.50"));
+        assert_eq!(convert("CAD", "10", "USD", "2026-12-31"), value("13.50"));
+    }
+
+    #[test]
+    fn uses_the_earliest_rate_before_any_is_published() {
+        assert_eq!(convert("CAD", "10", "USD", "2020-01-01"), value("14.00"));
+    }
+
+    #[test]
+    fn inverts_the_opposite_quote() {
+        assert_eq!(convert("USD", "14", "CAD", "2026-10-05"), value("10.00"));
+        assert_eq!(convert("GBP", "10", "CAD", "2026-10-05"), value("8.00"));
+    }
+
+    #[test]
+    fn prefers_a_quoted_rate_over_an_inverted_one() {
+        // 100 / 1.35 would be 74.07
+        assert_eq!(convert("USD", "100", "CAD", "2026-10-15"), value("70.00"));
+    }
+
+    #[test]
+    fn pivots_through_the_default_currency() {
+        // USD to CAD at 1.40, then CAD to EUR at 0.66
+        assert_eq!(convert("EUR", "10", "USD", "2026-10-05"), value("9.24"));
+        // EUR to CAD inverted, then CAD to USD inverted
+        assert_eq!(convert("USD", "9.24", "EUR", "2026-10-05"), value("10.00"));
+    }
+
+    #[test]
+    fn rounds_to_cents_away_from_zero() {
+        assert_eq!(convert("CAD", "0.1", "GBP", "2026-10-05"), value("0.13"));
+        assert_eq!(convert("CAD", "-0.1", "GBP", "2026-10-05"), value("-0.13"));
+    }
+
+    #[test]
+    fn reports_missing_rates() {
+        let missing = Err("No exchange rate from JPY to CAD".to_string());
+        assert_eq!(convert("CAD", "10", "JPY", "2026-10-05"), missing);
+        // a zero rate has no inverse
+        let missing = Err("No exchange rate from CAD to CHF".to_string());
+        assert_eq!(convert("CHF", "10", "CAD", "2026-10-05"), missing);
+    }
+}
@@ -13,3 +13,10 @@ pub fn establish_connection() -> DbPool {
         .build(manager)
         .expect("Failed to create pool")
 }
+
+// `raw` as plain text inside a LIKE pattern, `%` and `_` lose their wildcard meaning
+pub fn escape_like(raw: &str) -> String {
+    raw.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
@@ -0,0 +1,72 @@
+use crate::db::DbPool;
+use crate::models::user::User;
+use crate::schema::sessions::dsl::{email as session_email, expires_at, sessions, token};
+use crate::schema::users::dsl::{email as user_email, users};
+use chrono::Utc;
+use diesel::prelude::*;
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome, Request};
+
+// The caller of a route, resolved from the `Authorization: Bearer <token>` header.
+// Any route taking an `AuthUser` answers 401 when the token is missing, unknown or expired.
+#[derive(Debug, Clone)]
+pub struct AuthUser {
+    pub email: String,
+    pub token: String,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AuthUser {
+    type Error = &'static str;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let bearer = match req
+            .headers()
+            .get_one("Authorization")
+            .and_then(|header| header.strip_prefix("Bearer "))
+        {
+            Some(bearer) if !bearer.trim().is_empty() => bearer.trim().to_string(),
+            _ => return Outcome::Error((Status::Unauthorized, "Missing bearer token")),
+        };
+
+        let pool = match req.rocket().state::<DbPool>() {
+            Some(pool) => pool.clone(),
+            None => return Outcome::Error((Status::InternalServerError, "Database pool missing")),
+        };
+
+        let session_user = tokio::task::spawn_blocking({
+            let bearer = bearer.clone();
+            move || {
+                let mut conn = pool.get().expect("Failed to get database connection");
+                users
+                    .filter(
+                        user_email.eq_any(
+                            sessions
+                                .filter(token.eq(bearer))
+                                .filter(expires_at.gt(Utc::now()))
+                                .select(session_email),
+                        ),
+                    )
+                    .first::<User>(&mut conn)
+                    .optional()
+            }
+        })
+        .await;
+
+        match session_user {
+            Ok(Ok(Some(user))) => Outcome::Success(AuthUser {
+                email: user.email,
+                token: bearer,
+            }),
+            Ok(Ok(None)) => Outcome::Error((Status::Unauthorized, "Invalid or expired token")),
+            Ok(Err(e)) => {
+                eprintln!("Error checking session token: {:?}", e);
+                Outcome::Error((Status::InternalServerError, "Database error"))
+            }
+            Err(e) => {
+                eprintln!("Blocking task failed during session check: {:?}", e);
+                Outcome::Error((Status::InternalServerError, "Internal server error"))
+            }
+        }
+    }
+}
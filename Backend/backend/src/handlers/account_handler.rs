@@ -1,96 +1,365 @@
-use crate::models::account::{NewAccount, Account};
-use crate::models::user::User;
+use crate::models::account::{
+    Account, AccountBalance, AccountChanges, BalanceHistory, BalancePoint, ClientAccount,
+    ClientAccountUpdate, NewAccount,
+};
+use crate::currency::{normalize_currency, RateTable, DEFAULT_CURRENCY};
+use crate::handlers::recurring_handler::parse_day;
+use crate::models::recurring::{normalize_frequency, occurrence};
+use crate::period::BudgetCalendar;
 use crate::schema::accounts::dsl::*;
-use crate::schema::users::dsl::{users, email as user_email}; // For users table
+use crate::schema::categories::dsl::categories;
+use crate::schema::recurring_transactions::dsl::{paused, recurring_transactions};
+use crate::schema::transactions::dsl::transactions;
+use crate::schema::transfers::dsl::transfers;
+use crate::guards::AuthUser;
+use chrono::{DateTime, NaiveDate, Utc};
 use diesel::prelude::*;
 use crate::db::DbPool;
 use rocket::http::Status;
 use rocket::serde::json::Json;
+use rust_decimal::Decimal;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
 
-// DELETE delete account
-pub async fn handle_delete_account(email_str: String, account_name_str: String, pool: DbPool) -> (Status, &'static str) {
-    // Check if email is empty or account_name is empty
-    if email_str.is_empty() || account_name_str.is_empty() {
-        return (Status::BadRequest, "Invalid input");
+// A balance history longer than this needs a longer interval or a shorter range
+const MAX_BALANCE_POINTS: usize = 1000;
+
+// Some helper functions for balances
+
+// What moved into one account each local day, oldest first
+pub type DatedFlows = Vec<(NaiveDate, Decimal)>;
+
+// helper struct for the transactions moving account balances
+#[derive(Debug, Queryable)]
+pub struct FlowEntry {
+    pub account_id: i32,
+    pub amount: Decimal,
+    pub currency: Option<String>,
+    pub transaction_date: DateTime<Utc>,
+    // missing for transfer legs
+    pub category_type: Option<String>,
+}
+
+// Money going into an account is positive. Income comes in and expenses go out, transfer
+// legs and transactions of transfer categories are already signed by their direction.
+fn signed_flow(entry_amount: Decimal, entry_category_type: Option<&str>) -> Decimal {
+    match entry_category_type {
+        Some("expense") => -entry_amount,
+        _ => entry_amount,
+    }
+}
+
+// The flows of each account in its own currency, with the local day they happened on,
+// oldest first. Days before an account's opening date are already in its opening balance.
+fn load_flows(
+    conn: &mut PgConnection,
+    owner: &str,
+    owned_accounts: &[Account],
+) -> QueryResult<Result<HashMap<i32, DatedFlows>, String>> {
+    let calendar = BudgetCalendar::load(conn, owner)?;
+    let acc_ids: Vec<i32> = owned_accounts.iter().map(|acc| acc.account_id).collect();
+    let entries = transactions
+        .left_join(categories)
+        .filter(crate::schema::transactions::dsl::email.eq(owner))
+        .filter(crate::schema::transactions::dsl::account_id.eq_any(acc_ids))
+        .order_by(crate::schema::transactions::dsl::transaction_date)
+        .select((
+            crate::schema::transactions::dsl::account_id,
+            crate::schema::transactions::dsl::amount,
+            crate::schema::transactions::dsl::currency,
+            crate::schema::transactions::dsl::transaction_date,
+            crate::schema::categories::dsl::category_type.nullable(),
+        ))
+        .load::<FlowEntry>(conn)?;
+
+    // entries come by date, so the first and the last span every day converted on
+    let rate_days: Vec<NaiveDate> = entries
+        .first()
+        .into_iter()
+        .chain(entries.last())
+        .map(|entry| calendar.today(entry.transaction_date))
+        .collect();
+    let mut rate_tables: HashMap<String, RateTable> = HashMap::new();
+    let mut flows: HashMap<i32, DatedFlows> = HashMap::new();
+    for entry in entries.into_iter() {
+        let Some(acc) = owned_accounts
+            .iter()
+            .find(|acc| acc.account_id == entry.account_id)
+        else {
+            continue;
+        };
+        let day = calendar.today(entry.transaction_date);
+        if acc.opening_date.is_some_and(|opened| day < opened) {
+            continue;
+        }
+        let rates = match rate_tables.entry(acc.currency.clone()) {
+            Entry::Occupied(loaded) => loaded.into_mut(),
+            Entry::Vacant(missing) => missing.insert(RateTable::load(
+                conn,
+                &acc.currency,
+                rate_days.iter().copied(),
+            )?),
+        };
+        let entry_currency = entry.currency.as_deref().unwrap_or(&acc.currency);
+        let converted = match rates.convert(entry.amount, entry_currency, day) {
+            Ok(converted) => converted,
+            Err(e) => return Ok(Err(e)),
+        };
+        flows
+            .entry(acc.account_id)
+            .or_default()
+            .push((day, signed_flow(converted, entry.category_type.as_deref())));
+    }
+    Ok(Ok(flows))
+}
+
+// What an account holds, or owes for a liability, once `flows` are applied
+fn balance_after(acc: &Account, flows: &[(NaiveDate, Decimal)]) -> Decimal {
+    let moved: Decimal = flows.iter().map(|(_, flow)| *flow).sum();
+    if acc.is_liability() {
+        acc.opening_balance - moved
+    } else {
+        acc.opening_balance + moved
     }
+}
 
-    // Check if user exists
-    let user_exists = tokio::task::spawn_blocking({
+// The balance at the end of `day`, nothing before the account was opened
+pub fn balance_on(acc: &Account, flows: &[(NaiveDate, Decimal)], day: NaiveDate) -> Decimal {
+    if acc.opening_date.is_some_and(|opened| day < opened) {
+        return Decimal::ZERO;
+    }
+    let counted = flows.partition_point(|(flow_day, _)| *flow_day <= day);
+    balance_after(acc, &flows[..counted])
+}
+
+// Every account of `owner` by account name, with its flows
+pub fn load_account_flows(
+    conn: &mut PgConnection,
+    owner: &str,
+) -> QueryResult<Result<Vec<(Account, DatedFlows)>, String>> {
+    let owned_accounts = accounts
+        .filter(email.eq(owner))
+        .order_by(account_name)
+        .load::<Account>(conn)?;
+    let mut flows = match load_flows(conn, owner, &owned_accounts)? {
+        Ok(flows) => flows,
+        Err(e) => return Ok(Err(e)),
+    };
+    Ok(Ok(owned_accounts
+        .into_iter()
+        .map(|acc| {
+            let acc_flows = flows.remove(&acc.account_id).unwrap_or_default();
+            (acc, acc_flows)
+        })
+        .collect()))
+}
+
+// The current balance of every account of `owner`, by account name
+pub fn account_balances(
+    conn: &mut PgConnection,
+    owner: &str,
+) -> QueryResult<Result<Vec<AccountBalance>, String>> {
+    let owned_accounts = match load_account_flows(conn, owner)? {
+        Ok(owned_accounts) => owned_accounts,
+        Err(e) => return Ok(Err(e)),
+    };
+    let balances = owned_accounts
+        .into_iter()
+        .map(|(acc, acc_flows)| {
+            let balance = balance_after(&acc, &acc_flows);
+            let liability = acc.is_liability();
+            AccountBalance {
+                account_name: acc.account_name,
+                account_type: acc.account_type,
+                currency: acc.currency,
+                opening_balance: acc.opening_balance,
+                opening_date: acc.opening_date,
+                liability,
+                archived: acc.archived_at.is_some(),
+                balance,
+                net_value: if liability { -balance } else { balance },
+            }
+        })
+        .collect();
+    Ok(Ok(balances))
+}
+
+// Finds one of the caller's accounts by name, archived or not
+async fn find_owned_account(
+    owner: String,
+    account_name_str: String,
+    pool: DbPool,
+) -> Result<Account, (Status, &'static str)> {
+    // Check if account_name is empty
+    if account_name_str.is_empty() {
+        return Err((Status::BadRequest, "Invalid input"));
+    }
+
+    let account_exists = tokio::task::spawn_blocking({
         let pool = pool.clone();
-        let email_to_check = email_str.clone();
         move || {
             let mut conn = pool.get().expect("Failed to get database connection");
-            users
-                .filter(user_email.eq(email_to_check))
-                .first::<User>(&mut conn)
+            accounts
+                .filter(email.eq(owner))
+                .filter(account_name.eq(account_name_str))
+                .first::<Account>(&mut conn)
                 .optional()
         }
     }).await;
 
-    match user_exists {
+    match account_exists {
+        Ok(Ok(Some(acc))) => Ok(acc),
         Ok(Ok(None)) => {
-            // No user found for this email
-            return (Status::BadRequest, "No user found for the provided email");
-        }
-        Ok(Ok(Some(_))) => {
-            // User found, proceed with account deletion
+            // Account not found for this user
+            Err((Status::BadRequest, "No such account found for the provided email"))
         }
         Ok(Err(e)) => {
-            eprintln!("Error checking user existence: {:?}", e);
-            return (Status::InternalServerError, "Database error");
+            eprintln!("Error checking account existence: {:?}", e);
+            Err((Status::InternalServerError, "Database error"))
         }
         Err(e) => {
-            eprintln!("Blocking task failed during user check: {:?}", e);
-            return (Status::InternalServerError, "Internal server error");
+            eprintln!("Blocking task failed during account existence check: {:?}", e);
+            Err((Status::InternalServerError, "Internal server error"))
         }
     }
+}
 
-    // Check if the account exists for this user
-    let account_exists = tokio::task::spawn_blocking({
+// DELETE delete account
+// Archives the account: it leaves pickers and summaries, its transactions stay in reports
+// and the recurring transactions using it are paused. See /purge_account to really delete it.
+pub async fn handle_delete_account(user: AuthUser, account_name_str: String, pool: DbPool) -> (Status, &'static str) {
+    let found_account = match find_owned_account(user.email.clone(), account_name_str, pool.clone()).await {
+        Ok(acc) => acc,
+        Err(err) => return err,
+    };
+    if found_account.archived_at.is_some() {
+        return (Status::BadRequest, "Account is already archived");
+    }
+
+    // Proceed to archive the found account
+    let archive_result = tokio::task::spawn_blocking({
         let pool = pool.clone();
-        let email_to_check = email_str.clone();
-        let acc_name_to_check = account_name_str.clone();
+        let acc_id_to_archive = found_account.account_id;
         move || {
             let mut conn = pool.get().expect("Failed to get database connection");
-            accounts
-                .filter(email.eq(email_to_check))
-                .filter(account_name.eq(acc_name_to_check))
-                .first::<Account>(&mut conn)
-                .optional()
+            conn.transaction(|conn| {
+                diesel::update(
+                    recurring_transactions
+                        .filter(crate::schema::recurring_transactions::dsl::account_id.eq(acc_id_to_archive)),
+                )
+                .set(paused.eq(true))
+                .execute(conn)?;
+                diesel::update(accounts.filter(account_id.eq(acc_id_to_archive)))
+                    .set(archived_at.eq(Some(Utc::now())))
+                    .execute(conn)
+            })
         }
     }).await;
 
-    let found_account = match account_exists {
-        Ok(Ok(Some(acc))) => acc,
-        Ok(Ok(None)) => {
-            // Account not found for this email
-            return (Status::BadRequest, "No such account found for the provided email");
+    match archive_result {
+        Ok(Ok(rows_archived)) => {
+            if rows_archived > 0 {
+                (Status::Ok, "Account successfully archived")
+            } else {
+                (Status::InternalServerError, "Failed to archive the account")
+            }
         }
         Ok(Err(e)) => {
-            eprintln!("Error checking account existence: {:?}", e);
-            return (Status::InternalServerError, "Database error");
+            eprintln!("Error during archiving: {:?}", e);
+            (Status::InternalServerError, "Database error during archiving")
         }
         Err(e) => {
-            eprintln!("Blocking task failed during account existence check: {:?}", e);
-            return (Status::InternalServerError, "Internal server error");
+            eprintln!("Blocking task failed during archiving: {:?}", e);
+            (Status::InternalServerError, "Internal server error")
         }
+    }
+}
+
+// PATCH /unarchive_account?<account_name>
+// Paused recurring transactions stay paused
+pub async fn handle_unarchive_account(user: AuthUser, account_name_str: String, pool: DbPool) -> (Status, &'static str) {
+    let found_account = match find_owned_account(user.email.clone(), account_name_str, pool.clone()).await {
+        Ok(acc) => acc,
+        Err(err) => return err,
     };
+    if found_account.archived_at.is_none() {
+        return (Status::BadRequest, "Account is not archived");
+    }
 
-    // Proceed to delete the found account
-    let deletion_result = tokio::task::spawn_blocking({
+    let unarchive_result = tokio::task::spawn_blocking({
         let pool = pool.clone();
-        let acc_id_to_delete = found_account.account_id;
+        let acc_id_to_restore = found_account.account_id;
         move || {
             let mut conn = pool.get().expect("Failed to get database connection");
-            diesel::delete(accounts.filter(account_id.eq(acc_id_to_delete)))
+            diesel::update(accounts.filter(account_id.eq(acc_id_to_restore)))
+                .set(archived_at.eq(None::<DateTime<Utc>>))
                 .execute(&mut conn)
         }
     }).await;
 
-    match deletion_result {
+    match unarchive_result {
+        Ok(Ok(rows_restored)) => {
+            if rows_restored > 0 {
+                (Status::Ok, "Account successfully unarchived")
+            } else {
+                (Status::InternalServerError, "Failed to unarchive the account")
+            }
+        }
+        Ok(Err(e)) => {
+            eprintln!("Error during unarchiving: {:?}", e);
+            (Status::InternalServerError, "Database error during unarchiving")
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed during unarchiving: {:?}", e);
+            (Status::InternalServerError, "Internal server error")
+        }
+    }
+}
+
+// DELETE /purge_account?<account_name>&<confirm>
+// Really deletes an archived account with every transaction in it. Transfers touching the
+// account go as a whole, so no leg is left without its counterpart.
+pub async fn handle_purge_account(
+    user: AuthUser,
+    account_name_str: String,
+    confirm: Option<String>,
+    pool: DbPool,
+) -> (Status, &'static str) {
+    let found_account = match find_owned_account(user.email.clone(), account_name_str, pool.clone()).await {
+        Ok(acc) => acc,
+        Err(err) => return err,
+    };
+    if found_account.archived_at.is_none() {
+        return (Status::BadRequest, "Archive the account before purging it");
+    }
+    if confirm.as_deref() != Some(found_account.account_name.as_str()) {
+        return (Status::BadRequest, "Purging deletes every transaction of the account, confirm with its name");
+    }
+
+    let purge_result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        let acc_id_to_purge = found_account.account_id;
+        move || {
+            let mut conn = pool.get().expect("Failed to get database connection");
+            conn.transaction(|conn| {
+                let transfer_ids = transactions
+                    .filter(crate::schema::transactions::dsl::account_id.eq(acc_id_to_purge))
+                    .filter(crate::schema::transactions::dsl::transfer_id.is_not_null())
+                    .select(crate::schema::transactions::dsl::transfer_id)
+                    .load::<Option<i32>>(conn)?;
+                diesel::delete(
+                    transfers.filter(crate::schema::transfers::dsl::transfer_id.nullable().eq_any(transfer_ids)),
+                )
+                .execute(conn)?;
+                diesel::delete(accounts.filter(account_id.eq(acc_id_to_purge))).execute(conn)
+            })
+        }
+    }).await;
+
+    match purge_result {
         Ok(Ok(rows_deleted)) => {
             if rows_deleted > 0 {
-                (Status::Ok, "Account successfully deleted")
+                (Status::Ok, "Account permanently deleted")
             } else {
                 (Status::InternalServerError, "Failed to delete the account")
             }
@@ -106,21 +375,94 @@ pub async fn handle_delete_account(email_str: String, account_name_str: String,
     }
 }
 
-// GET /account_summary?email=<>
-pub async fn handle_account_summary(email_str: String, pool: DbPool) -> (Status, Json<Vec<Account>>) {
-    // If email is empty, return bad request
-    if email_str.is_empty() {
-        return (Status::BadRequest, Json(vec![]));
+// PATCH /account_update?<account_name>
+// Renaming keeps the account id, so its transactions stay with it
+pub async fn handle_account_update(
+    user: AuthUser,
+    account_name_str: String,
+    changes: ClientAccountUpdate,
+    pool: DbPool,
+) -> (Status, &'static str) {
+    // Step 1: validate input
+    let blank = |value: &Option<String>| value.as_deref().is_some_and(|v| v.trim().is_empty());
+    if account_name_str.is_empty() || blank(&changes.account_name) || blank(&changes.account_type) {
+        return (Status::BadRequest, "Invalid input");
+    }
+    let db_changes = AccountChanges {
+        account_name: changes.account_name,
+        account_type: changes.account_type,
+    };
+    if db_changes.is_empty() {
+        return (Status::BadRequest, "No fields to update");
+    }
+
+    // Step 2: find the account and apply the changes, the database refuses a name already
+    // taken by another one of the caller's accounts
+    let update_result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        let owner = user.email.clone();
+        move || {
+            let mut conn = pool.get().expect("Failed to get database connection");
+            conn.transaction(|conn| {
+                let Some(found_account) = accounts
+                    .filter(email.eq(&owner))
+                    .filter(account_name.eq(&account_name_str))
+                    .for_update()
+                    .first::<Account>(conn)
+                    .optional()?
+                else {
+                    return Ok(Err((
+                        Status::BadRequest,
+                        "No such account found for the provided email",
+                    )));
+                };
+                diesel::update(accounts.filter(account_id.eq(found_account.account_id)))
+                    .set(&db_changes)
+                    .execute(conn)
+                    .map(Ok)
+            })
+        }
+    })
+    .await;
+
+    match update_result {
+        Ok(Ok(Ok(rows_updated))) => {
+            if rows_updated > 0 {
+                (Status::Ok, "Account successfully updated")
+            } else {
+                (Status::InternalServerError, "Failed to update the account")
+            }
+        }
+        Ok(Ok(Err(rejected))) => rejected,
+        Ok(Err(diesel::result::Error::DatabaseError(
+            diesel::result::DatabaseErrorKind::UniqueViolation,
+            _,
+        ))) => (Status::Conflict, "Another account already has this name"),
+        Ok(Err(e)) => {
+            eprintln!("Error during account update: {:?}", e);
+            (Status::InternalServerError, "Database error during update")
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed during account update: {:?}", e);
+            (Status::InternalServerError, "Internal server error")
+        }
     }
+}
 
+// GET /account_summary?<include_archived>
+pub async fn handle_account_summary(user: AuthUser, include_archived: bool, pool: DbPool) -> (Status, Json<Vec<Account>>) {
     let accounts_result = tokio::task::spawn_blocking({
         let pool = pool.clone();
-        let email_to_search = email_str.clone();
+        let email_to_search = user.email.clone();
         move || {
             let mut conn = pool.get().expect("Failed to get database connection");
-            accounts
+            let mut query = accounts
                 .filter(email.eq(email_to_search))
-                .load::<Account>(&mut conn)
+                .into_boxed();
+            if !include_archived {
+                query = query.filter(archived_at.is_null());
+            }
+            query.load::<Account>(&mut conn)
         }
     }).await;
 
@@ -140,43 +482,36 @@ pub async fn handle_account_summary(email_str: String, pool: DbPool) -> (Status,
     }
 }
 
-pub async fn handle_account_create(new_acc: NewAccount, pool: DbPool) -> (Status, String) {
+pub async fn handle_account_create(user: AuthUser, client_acc: ClientAccount, pool: DbPool) -> (Status, String) {
     // Step 1: Validate input
-    if new_acc.email.is_empty() || new_acc.account_type.is_empty() || new_acc.account_name.is_empty() {
+    if client_acc.account_type.is_empty() || client_acc.account_name.is_empty() {
         return (Status::BadRequest, "Invalid input".to_string());
     }
 
-    // Step 1.5: Check if the email exists in users table
-    // If not, no account should be created
-    let user_exists = tokio::task::spawn_blocking({
-        let pool = pool.clone();
-        let email_to_check = new_acc.email.clone();
-        move || {
-            let mut conn = pool.get().expect("Failed to get database connection");
-            users
-                .filter(user_email.eq(email_to_check))
-                .first::<User>(&mut conn)
-                .optional()
-        }
-    }).await;
+    let acc_currency = match client_acc.currency.as_deref() {
+        Some(code) => match normalize_currency(code) {
+            Some(valid_code) => valid_code,
+            None => return (Status::BadRequest, "Invalid currency code".to_string()),
+        },
+        None => DEFAULT_CURRENCY.to_string(),
+    };
 
-    match user_exists {
-        Ok(Ok(None)) => {
-            // No user found for this email
-            return (Status::BadRequest, "No user found for the provided email".to_string());
-        }
-        Ok(Ok(Some(_user))) => {
-            // User found, proceed to account name existence check
-        }
-        Ok(Err(e)) => {
-            eprintln!("Error checking user existence: {:?}", e);
-            return (Status::InternalServerError, "Database error".to_string());
-        }
-        Err(e) => {
-            eprintln!("Blocking task failed during user check: {:?}", e);
-            return (Status::InternalServerError, "Internal server error".to_string());
-        }
-    }
+    let acc_opening_date = match client_acc.opening_date.as_deref() {
+        Some(day_str) if !day_str.trim().is_empty() => match parse_day(day_str) {
+            Some(day) => Some(day),
+            None => return (Status::BadRequest, "Invalid opening date".to_string()),
+        },
+        _ => None,
+    };
+
+    let new_acc = NewAccount {
+        email: user.email.clone(),
+        account_type: client_acc.account_type,
+        account_name: client_acc.account_name,
+        currency: acc_currency,
+        opening_balance: client_acc.opening_balance.unwrap_or_default(),
+        opening_date: acc_opening_date,
+    };
 
     // Step 2: Check if the account_name already exists for the given email
     let account_name_exists = tokio::task::spawn_blocking({
@@ -196,7 +531,7 @@ pub async fn handle_account_create(new_acc: NewAccount, pool: DbPool) -> (Status
     match account_name_exists {
         Ok(Ok(Some(_existing_acc))) => {
             // Account name already taken for this email
-            return (Status::BadRequest, "Failed to create new account".to_string());
+            (Status::BadRequest, "Failed to create new account".to_string())
         }
         Ok(Ok(None)) => {
             // Step 3: Proceed to create the new account
@@ -218,6 +553,11 @@ pub async fn handle_account_create(new_acc: NewAccount, pool: DbPool) -> (Status
                     let msg = format!("Successfully created {}", acc_for_message.account_name);
                     (Status::Created, msg)
                 }
+                // taken since the check above
+                Ok(Err(diesel::result::Error::DatabaseError(
+                    diesel::result::DatabaseErrorKind::UniqueViolation,
+                    _,
+                ))) => (Status::BadRequest, "Failed to create new account".to_string()),
                 Ok(Err(e)) => {
                     eprintln!("Database error during insertion: {:?}", e);
                     (Status::InternalServerError, "Database error".to_string())
@@ -238,3 +578,167 @@ pub async fn handle_account_create(new_acc: NewAccount, pool: DbPool) -> (Status
         }
     }
 }
+
+// GET /account_balance?<account_name>&<include_archived>
+// The balance of one account, or of every account when no name is given
+pub async fn handle_account_balance(
+    user: AuthUser,
+    account_name_filter: Option<String>,
+    include_archived: bool,
+    pool: DbPool,
+) -> Result<Json<Vec<AccountBalance>>, (Status, String)> {
+    let balances_result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        let owner = user.email.clone();
+        move || {
+            let mut conn = pool.get().expect("Failed to get database connection");
+            account_balances(&mut conn, &owner)
+        }
+    })
+    .await;
+
+    let balances = match balances_result {
+        Ok(Ok(Ok(balances))) => balances,
+        Ok(Ok(Err(e))) => return Err((Status::UnprocessableEntity, e)),
+        Ok(Err(e)) => {
+            eprintln!("Database error during account balance retrieval: {:?}", e);
+            return Err((Status::InternalServerError, "Database error".to_string()));
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed during account balance retrieval: {:?}", e);
+            return Err((Status::InternalServerError, "Internal server error".to_string()));
+        }
+    };
+
+    match account_name_filter {
+        Some(name) => {
+            let selected: Vec<AccountBalance> = balances
+                .into_iter()
+                .filter(|bal| bal.account_name == name)
+                .collect();
+            if selected.is_empty() {
+                return Err((
+                    Status::BadRequest,
+                    "No such account found for the provided email".to_string(),
+                ));
+            }
+            Ok(Json(selected))
+        }
+        None => Ok(Json(
+            balances
+                .into_iter()
+                .filter(|bal| include_archived || !bal.archived)
+                .collect(),
+        )),
+    }
+}
+
+// GET /account_balance_history?<account_name>&<interval>&<from>&<to>
+// The balance at the end of `from`, then every `interval` after it, up to and including `to`
+pub async fn handle_balance_history(
+    user: AuthUser,
+    acc_name: String,
+    interval: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    pool: DbPool,
+) -> Result<Json<BalanceHistory>, (Status, String)> {
+    // Step 1: validate input
+    let interval = match interval.as_deref() {
+        Some(raw) => normalize_frequency(raw).ok_or((
+            Status::BadRequest,
+            "Invalid interval: expected daily, weekly, monthly or yearly".to_string(),
+        ))?,
+        None => "monthly".to_string(),
+    };
+    let parse_bound = |raw: Option<String>| match raw {
+        Some(day_str) => parse_day(&day_str)
+            .map(Some)
+            .ok_or((Status::BadRequest, "Invalid date: expected YYYY-MM-DD".to_string())),
+        None => Ok(None),
+    };
+    let first_day = parse_bound(from)?;
+    let last_day = parse_bound(to)?;
+
+    // Step 2: get the account and its flows
+    let history_result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        let owner = user.email.clone();
+        move || {
+            let mut conn = pool.get().expect("Failed to get database connection");
+            let Some(acc) = accounts
+                .filter(email.eq(&owner))
+                .filter(account_name.eq(&acc_name))
+                .first::<Account>(&mut conn)
+                .optional()?
+            else {
+                return Ok(Err((
+                    Status::BadRequest,
+                    "No such account found for the provided email".to_string(),
+                )));
+            };
+            let today = BudgetCalendar::load(&mut conn, &owner)?.today(Utc::now());
+            let acc_flows = match load_flows(&mut conn, &owner, std::slice::from_ref(&acc))? {
+                Ok(mut flows) => flows.remove(&acc.account_id).unwrap_or_default(),
+                Err(e) => return Ok(Err((Status::UnprocessableEntity, e))),
+            };
+            Ok::<_, diesel::result::Error>(Ok((acc, acc_flows, today)))
+        }
+    })
+    .await;
+
+    let (acc, acc_flows, today) = match history_result {
+        Ok(Ok(Ok(loaded))) => loaded,
+        Ok(Ok(Err(rejected))) => return Err(rejected),
+        Ok(Err(e)) => {
+            eprintln!("Database error during balance history retrieval: {:?}", e);
+            return Err((Status::InternalServerError, "Database error".to_string()));
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed during balance history retrieval: {:?}", e);
+            return Err((Status::InternalServerError, "Internal server error".to_string()));
+        }
+    };
+
+    // Step 3: pick the days, from the opening date or the first transaction up to today
+    // unless the client chose them
+    let last_day = last_day.unwrap_or(today);
+    let first_day = first_day
+        .or(acc.opening_date)
+        .or(acc_flows.first().map(|(day, _)| *day))
+        .unwrap_or(last_day)
+        .min(last_day);
+    let mut days: Vec<NaiveDate> = vec![];
+    for n in 0.. {
+        match occurrence(first_day, &interval, 1, n) {
+            Some(day) if day <= last_day => days.push(day),
+            _ => break,
+        }
+        if days.len() > MAX_BALANCE_POINTS {
+            return Err((
+                Status::BadRequest,
+                "Too many points: pick a longer interval or a shorter range".to_string(),
+            ));
+        }
+    }
+    if days.last() != Some(&last_day) {
+        days.push(last_day);
+    }
+
+    // Step 4: the balance at the end of each day
+    let points = days
+        .into_iter()
+        .map(|day| BalancePoint {
+            date: day,
+            balance: balance_on(&acc, &acc_flows, day),
+        })
+        .collect();
+
+    Ok(Json(BalanceHistory {
+        liability: acc.is_liability(),
+        account_name: acc.account_name,
+        currency: acc.currency,
+        interval,
+        points,
+    }))
+}
@@ -1,10 +1,24 @@
-use crate::models::user::{NewUser, User};
+use crate::guards::AuthUser;
+use crate::models::session::{LoginResponse, NewSession};
+use crate::models::user::{ClientSettings, LoginUser, NewUser, SettingsChanges, User, UserSettings};
+use crate::period::{parse_time_zone, MAX_PERIOD_START_DAY};
+use crate::schema::sessions::dsl::{expires_at, sessions, token, email as session_email};
 use crate::schema::users::dsl::*;
+use chrono::{Duration, Utc};
 use diesel::prelude::*;
 #[allow(unused_imports)]
 use diesel::result::Error;
 use crate::db::DbPool;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
 use rocket::http::Status;
+use rocket::serde::json::Json;
+
+// How long a session token stays valid after login
+const SESSION_TTL_HOURS: i64 = 24 * 7;
+const SESSION_TOKEN_LEN: usize = 48;
+// bcrypt hash at DEFAULT_COST of a throwaway password, checked when the email is unknown
+const DUMMY_PASSWORD_HASH: &str = "$2a$12$naBvYL4iMorAWqrSevCHQuFAKPCtUtBb/7BST//W3ZWr9Yhi43Emy";
 
 pub async fn handle_signup(user: NewUser, pool: DbPool) -> (Status, &'static str) {
     if user.email.is_empty() || user.password.is_empty() {
@@ -29,19 +43,35 @@ pub async fn handle_signup(user: NewUser, pool: DbPool) -> (Status, &'static str
 
     match email_exists {
         Ok(Ok(Some(existing_user))) => {
-            if existing_user.password == user.password {
-                println!("Email and password match for user: {}", existing_user.username);
-                let message = format!("{} Login successful", existing_user.username); // Include username in the response
-                return (Status::Ok, Box::leak(message.into_boxed_str()));
-            } else {
-                println!("Password mismatch for email: {}", existing_user.email);
-                return (Status::BadRequest, "Invalid password");
-            }
+            println!("Signup attempted for registered email: {}", existing_user.email);
+            (Status::Conflict, "Email already registered")
         }
         Ok(Ok(None)) => {
-            // Email does not exist, create the new user
+            // Email does not exist, hash the password before it reaches the database
+            let hash_result = tokio::task::spawn_blocking({
+                let password_to_hash = user.password.clone();
+                move || bcrypt::hash(password_to_hash, bcrypt::DEFAULT_COST)
+            })
+                .await;
+
+            let hashed_password = match hash_result {
+                Ok(Ok(hashed)) => hashed,
+                Ok(Err(e)) => {
+                    eprintln!("Password hashing failed: {:?}", e);
+                    return (Status::InternalServerError, "Failed to register user");
+                }
+                Err(e) => {
+                    eprintln!("Blocking task failed during password hashing: {:?}", e);
+                    return (Status::InternalServerError, "Internal server error");
+                }
+            };
+
+            // Create the new user
             let result = tokio::task::spawn_blocking({
-                let new_user = user.clone(); // Clone user to avoid move
+                let new_user = NewUser {
+                    password: hashed_password,
+                    ..user.clone() // Clone user to avoid move
+                };
                 let pool = pool.clone();
                 move || {
                     let mut conn = pool.get().expect("Failed to get database connection");
@@ -84,3 +114,211 @@ pub async fn handle_signup(user: NewUser, pool: DbPool) -> (Status, &'static str
         }
     }
 }
+
+// POST /login
+pub async fn handle_login(
+    credentials: LoginUser,
+    pool: DbPool,
+) -> Result<Json<LoginResponse>, (Status, &'static str)> {
+    if credentials.email.is_empty() || credentials.password.is_empty() {
+        return Err((Status::BadRequest, "Invalid input"));
+    }
+
+    // Look up the user and verify the password against the stored hash
+    let verified_user = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        move || {
+            let mut conn = pool.get().expect("Failed to get database connection");
+            users
+                .filter(email.eq(&credentials.email))
+                .first::<User>(&mut conn)
+                .optional()
+                .map(|found| match found {
+                    Some(existing_user) => {
+                        bcrypt::verify(&credentials.password, &existing_user.password)
+                            .unwrap_or(false)
+                            .then_some(existing_user)
+                    }
+                    None => {
+                        // Same work as a wrong password, so timing does not tell which emails exist
+                        let _ = bcrypt::verify(&credentials.password, DUMMY_PASSWORD_HASH);
+                        None
+                    }
+                })
+        }
+    })
+    .await;
+
+    let existing_user = match verified_user {
+        Ok(Ok(Some(existing_user))) => existing_user,
+        Ok(Ok(None)) => {
+            // Unknown email and wrong password get the same answer
+            return Err((Status::Unauthorized, "Invalid email or password"));
+        }
+        Ok(Err(e)) => {
+            eprintln!("Error checking user credentials: {:?}", e);
+            return Err((Status::InternalServerError, "Database error"));
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed during credential check: {:?}", e);
+            return Err((Status::InternalServerError, "Internal server error"));
+        }
+    };
+
+    // Issue a new opaque session token
+    let new_session = NewSession {
+        token: rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(SESSION_TOKEN_LEN)
+            .map(char::from)
+            .collect(),
+        email: existing_user.email.clone(),
+        expires_at: Utc::now() + Duration::hours(SESSION_TTL_HOURS),
+    };
+
+    let result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        let session_to_insert = new_session.clone();
+        move || {
+            let mut conn = pool.get().expect("Failed to get database connection");
+            // Drop this user's expired sessions while we are here
+            diesel::delete(
+                sessions
+                    .filter(session_email.eq(&session_to_insert.email))
+                    .filter(expires_at.lt(Utc::now())),
+            )
+            .execute(&mut conn)?;
+            diesel::insert_into(sessions)
+                .values(&session_to_insert)
+                .execute(&mut conn)
+        }
+    })
+    .await;
+
+    match result {
+        Ok(Ok(_)) => {
+            println!("Login successful for user: {}", existing_user.username);
+            Ok(Json(LoginResponse {
+                token: new_session.token,
+                username: existing_user.username,
+                email: existing_user.email,
+                expires_at: new_session.expires_at,
+            }))
+        }
+        Ok(Err(e)) => {
+            eprintln!("Database error during session creation: {:?}", e);
+            Err((Status::InternalServerError, "Database error"))
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed during session creation: {:?}", e);
+            Err((Status::InternalServerError, "Internal server error"))
+        }
+    }
+}
+
+// DELETE /logout
+pub async fn handle_logout(user: AuthUser, pool: DbPool) -> (Status, &'static str) {
+    let result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        move || {
+            let mut conn = pool.get().expect("Failed to get database connection");
+            diesel::delete(sessions.filter(token.eq(user.token))).execute(&mut conn)
+        }
+    })
+    .await;
+
+    match result {
+        Ok(Ok(_)) => (Status::Ok, "Logged out"),
+        Ok(Err(e)) => {
+            eprintln!("Database error during logout: {:?}", e);
+            (Status::InternalServerError, "Database error")
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed during logout: {:?}", e);
+            (Status::InternalServerError, "Internal server error")
+        }
+    }
+}
+
+// GET /settings
+pub async fn handle_get_settings(
+    user: AuthUser,
+    pool: DbPool,
+) -> Result<Json<UserSettings>, (Status, &'static str)> {
+    let settings_result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        move || {
+            let mut conn = pool.get().expect("Failed to get database connection");
+            users
+                .filter(email.eq(user.email))
+                .select((time_zone, period_start_day))
+                .first::<UserSettings>(&mut conn)
+        }
+    })
+    .await;
+
+    match settings_result {
+        Ok(Ok(settings)) => Ok(Json(settings)),
+        Ok(Err(e)) => {
+            eprintln!("Database error during settings retrieval: {:?}", e);
+            Err((Status::InternalServerError, "Database error"))
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed during settings retrieval: {:?}", e);
+            Err((Status::InternalServerError, "Internal server error"))
+        }
+    }
+}
+
+// PATCH /settings
+pub async fn handle_update_settings(
+    user: AuthUser,
+    changes: ClientSettings,
+    pool: DbPool,
+) -> (Status, &'static str) {
+    // Step 1: validate the fields that were sent
+    let new_time_zone = match changes.time_zone {
+        Some(zone_name) => match parse_time_zone(&zone_name) {
+            Some(zone) => Some(zone.name().to_string()),
+            None => return (Status::BadRequest, "Invalid time zone"),
+        },
+        None => None,
+    };
+    if let Some(start_day) = changes.period_start_day {
+        if !(1..=MAX_PERIOD_START_DAY).contains(&start_day) {
+            return (Status::BadRequest, "Period start day must be between 1 and 28");
+        }
+    }
+    if new_time_zone.is_none() && changes.period_start_day.is_none() {
+        return (Status::BadRequest, "No fields to update");
+    }
+
+    let db_changes = SettingsChanges {
+        time_zone: new_time_zone,
+        period_start_day: changes.period_start_day,
+    };
+
+    // Step 2: apply them to the caller
+    let update_result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        move || {
+            let mut conn = pool.get().expect("Failed to get database connection");
+            diesel::update(users.filter(email.eq(user.email)))
+                .set(&db_changes)
+                .execute(&mut conn)
+        }
+    })
+    .await;
+
+    match update_result {
+        Ok(Ok(_)) => (Status::Ok, "Settings successfully updated"),
+        Ok(Err(e)) => {
+            eprintln!("Database error during settings update: {:?}", e);
+            (Status::InternalServerError, "Database error")
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed during settings update: {:?}", e);
+            (Status::InternalServerError, "Internal server error")
+        }
+    }
+}
@@ -1,60 +1,67 @@
 use crate::db::DbPool;
-use crate::models::category::{Category, NewCategory};
-use crate::models::user::User;
+use crate::guards::AuthUser;
+use crate::models::category::{
+    normalize_alert_thresholds, normalize_category_type, normalize_rollover,
+    parse_alert_thresholds, Category, ClientCategory, NewCategory, DEFAULT_ALERT_THRESHOLDS,
+};
 use crate::schema::categories::dsl::*;
-use crate::schema::users::dsl::{email as user_email, users}; // For users table
+use crate::schema::recurring_transactions::dsl::{paused, recurring_transactions};
+use crate::schema::transactions::dsl::transactions;
+use chrono::{DateTime, Utc};
 use diesel::prelude::*;
+use rust_decimal::Decimal;
 use rocket::http::Status;
 use rocket::serde::json::Json;
 
-pub async fn handle_category_create(new_cat: NewCategory, pool: DbPool) -> (Status, String) {
+const INVALID_CATEGORY_TYPE: &str = "Invalid category type: expected income, expense or transfer";
+const INVALID_ROLLOVER: &str = "Invalid rollover: expected none, carry_unused or carry_overspend";
+const INVALID_ALERT_THRESHOLDS: &str =
+    "Invalid alert thresholds: expected percentages between 1 and 1000";
+const SPLIT_CATEGORY_TYPE: &str =
+    "Categories used by split lines cannot change type, delete those splits first";
+const TRANSFER_CATEGORY_TYPE: &str =
+    "Categories in use cannot change to or from transfer, move their transactions and recurring transactions to another category first";
+
+pub async fn handle_category_create(
+    user: AuthUser,
+    client_cat: ClientCategory,
+    pool: DbPool,
+) -> (Status, String) {
     // Step 1: Validate input
-    if new_cat.email.is_empty()
-        || new_cat.category_type.is_empty()
-        || new_cat.nickname.is_empty()
-        || new_cat.budget_freq.is_empty()
+    if client_cat.category_type.is_empty()
+        || client_cat.nickname.is_empty()
+        || client_cat.budget_freq.is_empty()
     {
         return (Status::BadRequest, "Invalid input".to_string());
     }
 
-    // Step 1.5: Check if the email exists in users table
-    // If not, no category should be created
-    let user_exists = tokio::task::spawn_blocking({
-        let pool = pool.clone();
-        let email_to_check = new_cat.email.clone();
-        move || {
-            let mut conn = pool.get().expect("Failed to get database connection");
-            users
-                .filter(user_email.eq(email_to_check))
-                .first::<User>(&mut conn)
-                .optional()
-        }
-    })
-    .await;
+    let Some(valid_type) = normalize_category_type(&client_cat.category_type) else {
+        return (Status::BadRequest, INVALID_CATEGORY_TYPE.to_string());
+    };
+    let valid_rollover = match client_cat.rollover.as_deref() {
+        Some(policy) => match normalize_rollover(policy) {
+            Some(valid_policy) => valid_policy,
+            None => return (Status::BadRequest, INVALID_ROLLOVER.to_string()),
+        },
+        None => "none".to_string(),
+    };
+    let valid_thresholds = match client_cat.alert_thresholds {
+        Some(thresholds) => match normalize_alert_thresholds(thresholds) {
+            Some(valid_thresholds) => valid_thresholds,
+            None => return (Status::BadRequest, INVALID_ALERT_THRESHOLDS.to_string()),
+        },
+        None => DEFAULT_ALERT_THRESHOLDS.to_vec(),
+    };
 
-    match user_exists {
-        Ok(Ok(None)) => {
-            // No user found for this email
-            return (
-                Status::BadRequest,
-                "No user found for the provided email".to_string(),
-            );
-        }
-        Ok(Ok(Some(_user))) => {
-            // User found, proceed to category name existence check
-        }
-        Ok(Err(e)) => {
-            eprintln!("Error checking user existence: {:?}", e);
-            return (Status::InternalServerError, "Database error".to_string());
-        }
-        Err(e) => {
-            eprintln!("Blocking task failed during user check: {:?}", e);
-            return (
-                Status::InternalServerError,
-                "Internal server error".to_string(),
-            );
-        }
-    }
+    let new_cat = NewCategory {
+        email: user.email.clone(),
+        nickname: client_cat.nickname,
+        category_type: valid_type,
+        budget: client_cat.budget,
+        budget_freq: client_cat.budget_freq,
+        rollover: valid_rollover,
+        alert_thresholds: valid_thresholds,
+    };
 
     // Step 2: Check if the category nickname already exists for the given email
     let nickname_exists = tokio::task::spawn_blocking({
@@ -75,10 +82,10 @@ pub async fn handle_category_create(new_cat: NewCategory, pool: DbPool) -> (Stat
     match nickname_exists {
         Ok(Ok(Some(_existing_nickname))) => {
             // Category nickname already taken for this email
-            return (
+            (
                 Status::BadRequest,
                 "Failed to create new category: duplicate nicknames".to_string(),
-            );
+            )
         }
         Ok(Ok(None)) => {
             // Step 3: Proceed to create the new category
@@ -128,94 +135,344 @@ pub async fn handle_category_create(new_cat: NewCategory, pool: DbPool) -> (Stat
     }
 }
 
+// Finds one of the caller's categories by nickname, archived or not
+async fn find_owned_category(
+    owner: String,
+    category_nickname: String,
+    pool: DbPool,
+) -> Result<Category, (Status, &'static str)> {
+    // Check if category_nickname is empty
+    if category_nickname.is_empty() {
+        return Err((Status::BadRequest, "Invalid input"));
+    }
+
+    let category_exists = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        move || {
+            let mut conn = pool.get().expect("Failed to get database connection");
+            categories
+                .filter(email.eq(owner))
+                .filter(nickname.eq(category_nickname))
+                .first::<Category>(&mut conn)
+                .optional()
+        }
+    })
+    .await;
+
+    match category_exists {
+        Ok(Ok(Some(cat))) => Ok(cat),
+        Ok(Ok(None)) => {
+            // category not found for this email
+            Err((
+                Status::BadRequest,
+                "No such category found for the provided email",
+            ))
+        }
+        Ok(Err(e)) => {
+            eprintln!("Error checking category existence: {:?}", e);
+            Err((Status::InternalServerError, "Database error"))
+        }
+        Err(e) => {
+            eprintln!(
+                "Blocking task failed during category existence check: {:?}",
+                e
+            );
+            Err((Status::InternalServerError, "Internal server error"))
+        }
+    }
+}
+
+// Finds the active category that takes over the transactions of `source`,
+// it has to be of the same type so amounts keep their meaning
+async fn find_reassign_target(
+    owner: String,
+    target_nickname: String,
+    source: &Category,
+    pool: DbPool,
+) -> Result<Category, (Status, &'static str)> {
+    let target = find_owned_category(owner, target_nickname, pool).await?;
+    if target.category_id == source.category_id {
+        return Err((
+            Status::BadRequest,
+            "Transactions cannot be moved to the same category",
+        ));
+    }
+    if target.archived_at.is_some() {
+        return Err((
+            Status::BadRequest,
+            "Transactions cannot be moved to an archived category",
+        ));
+    }
+    if target.category_type != source.category_type {
+        return Err((
+            Status::BadRequest,
+            "Transactions can only be moved to a category of the same type",
+        ));
+    }
+    Ok(target)
+}
+
+// Moves the transactions and recurring transactions of one category to another.
+// Budget alerts are left out, see move_budget_alerts for merges.
+fn move_category_rows(conn: &mut PgConnection, from_id: i32, to_id: i32) -> QueryResult<usize> {
+    use crate::schema::recurring_transactions::dsl as recurring;
+    use crate::schema::transactions::dsl as trans;
+
+    diesel::update(recurring_transactions.filter(recurring::category_id.eq(from_id)))
+        .set(recurring::category_id.eq(to_id))
+        .execute(conn)?;
+    diesel::update(transactions.filter(trans::category_id.eq(from_id)))
+        .set(trans::category_id.eq(to_id))
+        .execute(conn)
+}
+
+// Moves the budget alerts of a merged category to the category it is merged into, so they
+// are not deleted with it. An alert the target already has for the same threshold and
+// period is kept instead of the merged one.
+fn move_budget_alerts(conn: &mut PgConnection, from_id: i32, to_id: i32) -> QueryResult<usize> {
+    use crate::schema::notifications::dsl as alert;
+
+    let taken: Vec<(i32, DateTime<Utc>)> = alert::notifications
+        .filter(alert::category_id.eq(to_id))
+        .select((alert::threshold, alert::period_start))
+        .load(conn)?;
+    let moving: Vec<i32> = alert::notifications
+        .filter(alert::category_id.eq(from_id))
+        .select((
+            alert::notification_id,
+            alert::threshold,
+            alert::period_start,
+        ))
+        .load::<(i32, i32, DateTime<Utc>)>(conn)?
+        .into_iter()
+        .filter(|(_, limit, start)| !taken.contains(&(*limit, *start)))
+        .map(|(alert_id, _, _)| alert_id)
+        .collect();
+    diesel::update(alert::notifications.filter(alert::notification_id.eq_any(moving)))
+        .set(alert::category_id.eq(to_id))
+        .execute(conn)
+}
+
 // DELETE delete category
+// Archives the category: it leaves pickers and summaries, its transactions stay in reports
+// and the recurring transactions using it are paused. See /purge_category to really delete it.
+// With `reassign_to`, its transactions and recurring transactions first move to that category.
 pub async fn handle_delete_category(
-    email_str: String,
+    user: AuthUser,
     category_nickname: String,
+    reassign_to: Option<String>,
     pool: DbPool,
 ) -> (Status, &'static str) {
-    // Check if email is empty or category_nickname is empty
-    if email_str.is_empty() || category_nickname.is_empty() {
-        return (Status::BadRequest, "Invalid input");
+    let found_category =
+        match find_owned_category(user.email.clone(), category_nickname, pool.clone()).await {
+            Ok(cat) => cat,
+            Err(err) => return err,
+        };
+    if found_category.archived_at.is_some() {
+        return (Status::BadRequest, "Category is already archived");
     }
+    let target_id = match reassign_to.filter(|target| !target.is_empty()) {
+        Some(target) => {
+            match find_reassign_target(user.email.clone(), target, &found_category, pool.clone())
+                .await
+            {
+                Ok(target) => Some(target.category_id),
+                Err(err) => return err,
+            }
+        }
+        None => None,
+    };
 
-    // Check if user exists
-    let user_exists = tokio::task::spawn_blocking({
+    // Proceed to archive the found category, after moving its rows when asked to
+    let archive_result = tokio::task::spawn_blocking({
         let pool = pool.clone();
-        let email_to_check = email_str.clone();
+        let to_archive_id = found_category.category_id;
         move || {
             let mut conn = pool.get().expect("Failed to get database connection");
-            users
-                .filter(user_email.eq(email_to_check))
-                .first::<User>(&mut conn)
-                .optional()
+            conn.transaction(|conn| {
+                if let Some(target_id) = target_id {
+                    move_category_rows(conn, to_archive_id, target_id)?;
+                }
+                diesel::update(recurring_transactions.filter(
+                    crate::schema::recurring_transactions::dsl::category_id.eq(to_archive_id),
+                ))
+                .set(paused.eq(true))
+                .execute(conn)?;
+                diesel::update(categories.filter(category_id.eq(to_archive_id)))
+                    .set(archived_at.eq(Some(Utc::now())))
+                    .execute(conn)
+            })
         }
     })
     .await;
 
-    match user_exists {
-        Ok(Ok(None)) => {
-            // No user found for this email
-            return (Status::BadRequest, "No user found for the provided email");
+    match archive_result {
+        Ok(Ok(rows_archived)) => {
+            if rows_archived > 0 {
+                (Status::Ok, "Category successfully archived")
+            } else {
+                (Status::InternalServerError, "Failed to archive the category")
+            }
         }
-        Ok(Ok(Some(_))) => {
-            // User found, proceed with category deletion
+        Ok(Err(e)) => {
+            eprintln!("Error during archiving: {:?}", e);
+            (
+                Status::InternalServerError,
+                "Database error during archiving",
+            )
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed during archiving: {:?}", e);
+            (Status::InternalServerError, "Internal server error")
+        }
+    }
+}
+
+// POST /merge_category?<category_nickname>&<into>
+// Moves every transaction, recurring transaction and budget alert of a category into
+// another one, then deletes the emptied category
+pub async fn handle_merge_category(
+    user: AuthUser,
+    category_nickname: String,
+    into: String,
+    pool: DbPool,
+) -> (Status, &'static str) {
+    let source =
+        match find_owned_category(user.email.clone(), category_nickname, pool.clone()).await {
+            Ok(cat) => cat,
+            Err(err) => return err,
+        };
+    let target = match find_reassign_target(user.email.clone(), into, &source, pool.clone()).await
+    {
+        Ok(cat) => cat,
+        Err(err) => return err,
+    };
+
+    let merge_result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        move || {
+            let mut conn = pool.get().expect("Failed to get database connection");
+            conn.transaction(|conn| {
+                move_category_rows(conn, source.category_id, target.category_id)?;
+                move_budget_alerts(conn, source.category_id, target.category_id)?;
+                diesel::delete(categories.filter(category_id.eq(source.category_id)))
+                    .execute(conn)
+            })
+        }
+    })
+    .await;
+
+    match merge_result {
+        Ok(Ok(rows_deleted)) => {
+            if rows_deleted > 0 {
+                (Status::Ok, "Categories successfully merged")
+            } else {
+                (Status::InternalServerError, "Failed to merge the categories")
+            }
         }
         Ok(Err(e)) => {
-            eprintln!("Error checking user existence: {:?}", e);
-            return (Status::InternalServerError, "Database error");
+            eprintln!("Error during merging: {:?}", e);
+            (Status::InternalServerError, "Database error during merging")
         }
         Err(e) => {
-            eprintln!("Blocking task failed during user check: {:?}", e);
-            return (Status::InternalServerError, "Internal server error");
+            eprintln!("Blocking task failed during merging: {:?}", e);
+            (Status::InternalServerError, "Internal server error")
         }
     }
+}
 
-    // Check if the category nickname exists for this user
-    let category_exists = tokio::task::spawn_blocking({
+// PATCH /unarchive_category?<category_nickname>
+// Paused recurring transactions stay paused
+pub async fn handle_unarchive_category(
+    user: AuthUser,
+    category_nickname: String,
+    pool: DbPool,
+) -> (Status, &'static str) {
+    let found_category =
+        match find_owned_category(user.email.clone(), category_nickname, pool.clone()).await {
+            Ok(cat) => cat,
+            Err(err) => return err,
+        };
+    if found_category.archived_at.is_none() {
+        return (Status::BadRequest, "Category is not archived");
+    }
+
+    let unarchive_result = tokio::task::spawn_blocking({
         let pool = pool.clone();
-        let email_to_check = email_str.clone();
-        let nickname_to_check = category_nickname.clone();
+        let to_restore_id = found_category.category_id;
         move || {
             let mut conn = pool.get().expect("Failed to get database connection");
-            categories
-                .filter(email.eq(email_to_check))
-                .filter(nickname.eq(nickname_to_check))
-                .first::<Category>(&mut conn)
-                .optional()
+            diesel::update(categories.filter(category_id.eq(to_restore_id)))
+                .set(archived_at.eq(None::<DateTime<Utc>>))
+                .execute(&mut conn)
         }
     })
     .await;
 
-    let found_category = match category_exists {
-        Ok(Ok(Some(cat))) => cat,
-        Ok(Ok(None)) => {
-            // category not found for this email
-            return (
-                Status::BadRequest,
-                "No such category found for the provided email",
-            );
+    match unarchive_result {
+        Ok(Ok(rows_restored)) => {
+            if rows_restored > 0 {
+                (Status::Ok, "Category successfully unarchived")
+            } else {
+                (Status::InternalServerError, "Failed to unarchive the category")
+            }
         }
         Ok(Err(e)) => {
-            eprintln!("Error checking category existence: {:?}", e);
-            return (Status::InternalServerError, "Database error");
+            eprintln!("Error during unarchiving: {:?}", e);
+            (
+                Status::InternalServerError,
+                "Database error during unarchiving",
+            )
         }
         Err(e) => {
-            eprintln!(
-                "Blocking task failed during category existence check: {:?}",
-                e
-            );
-            return (Status::InternalServerError, "Internal server error");
+            eprintln!("Blocking task failed during unarchiving: {:?}", e);
+            (Status::InternalServerError, "Internal server error")
         }
-    };
+    }
+}
+
+// DELETE /purge_category?<category_nickname>&<confirm>
+// Really deletes an archived category with every transaction in it. Splits with a line
+// in it are deleted whole, the rest of their lines would no longer add up.
+pub async fn handle_purge_category(
+    user: AuthUser,
+    category_nickname: String,
+    confirm: Option<String>,
+    pool: DbPool,
+) -> (Status, &'static str) {
+    let found_category =
+        match find_owned_category(user.email.clone(), category_nickname, pool.clone()).await {
+            Ok(cat) => cat,
+            Err(err) => return err,
+        };
+    if found_category.archived_at.is_none() {
+        return (Status::BadRequest, "Archive the category before purging it");
+    }
+    if confirm.as_deref() != Some(found_category.nickname.as_str()) {
+        return (
+            Status::BadRequest,
+            "Purging deletes every transaction of the category, confirm with its name",
+        );
+    }
 
-    // Proceed to delete the found category
     let deletion_result = tokio::task::spawn_blocking({
         let pool = pool.clone();
         let to_delete_id = found_category.category_id;
         move || {
             let mut conn = pool.get().expect("Failed to get database connection");
-            diesel::delete(categories.filter(category_id.eq(to_delete_id))).execute(&mut conn)
+            conn.transaction(|conn| {
+                use crate::schema::splits::dsl as split;
+                use crate::schema::transactions::dsl as trans;
+
+                let touched_split_ids = transactions
+                    .filter(trans::category_id.eq(to_delete_id))
+                    .filter(trans::split_id.is_not_null())
+                    .select(trans::split_id)
+                    .load::<Option<i32>>(conn)?;
+                diesel::delete(split::splits.filter(split::split_id.nullable().eq_any(touched_split_ids)))
+                    .execute(conn)?;
+                diesel::delete(categories.filter(category_id.eq(to_delete_id))).execute(conn)
+            })
         }
     })
     .await;
@@ -223,7 +480,7 @@ pub async fn handle_delete_category(
     match deletion_result {
         Ok(Ok(rows_deleted)) => {
             if rows_deleted > 0 {
-                (Status::Ok, "Category successfully deleted")
+                (Status::Ok, "Category permanently deleted")
             } else {
                 (Status::InternalServerError, "Failed to delete the category")
             }
@@ -242,24 +499,25 @@ pub async fn handle_delete_category(
     }
 }
 
-// GET /category_summary?email=<>
+// GET /category_summary?<include_archived>
 pub async fn handle_category_summary(
-    email_str: String,
+    user: AuthUser,
+    include_archived: bool,
     pool: DbPool,
 ) -> (Status, Json<Vec<Category>>) {
-    // If email is empty, return bad request
-    if email_str.is_empty() {
-        return (Status::BadRequest, Json(vec![]));
-    }
-
+    let email_str = user.email;
     let categories_result = tokio::task::spawn_blocking({
         let pool = pool.clone();
         let email_to_search = email_str.clone();
         move || {
             let mut conn = pool.get().expect("Failed to get database connection");
-            categories
+            let mut query = categories
                 .filter(email.eq(email_to_search))
-                .load::<Category>(&mut conn)
+                .into_boxed();
+            if !include_archived {
+                query = query.filter(archived_at.is_null());
+            }
+            query.load::<Category>(&mut conn)
         }
     })
     .await;
@@ -283,7 +541,7 @@ pub async fn handle_category_summary(
     }
 }
 
-// POST /category_update?email=<user_email>&field=<field_to_update>&category_nickname=<nickname>&new_value=<new_value>
+// POST /category_update?field=<field_to_update>&category_nickname=<nickname>&new_value=<new_value>
 use std::str::FromStr;
 #[derive(Debug, PartialEq)]
 enum ValidCategoryFields {
@@ -291,6 +549,8 @@ enum ValidCategoryFields {
     Type,
     Budget,
     Freq,
+    Rollover,
+    AlertThresholds,
 }
 
 impl FromStr for ValidCategoryFields {
@@ -301,13 +561,15 @@ impl FromStr for ValidCategoryFields {
             "category_type" => Ok(ValidCategoryFields::Type),
             "budget" => Ok(ValidCategoryFields::Budget),
             "budget_freq" => Ok(ValidCategoryFields::Freq),
+            "rollover" => Ok(ValidCategoryFields::Rollover),
+            "alert_thresholds" => Ok(ValidCategoryFields::AlertThresholds),
             _ => Err(()),
         }
     }
 }
 
 pub async fn handle_category_update(
-    email_str: String,
+    user: AuthUser,
     field: String,
     category_nickname: String,
     new_value: String,
@@ -320,46 +582,9 @@ pub async fn handle_category_update(
         Ok(field_type) => field_type,
         Err(_) => return (Status::BadRequest, "Invalid field specified.".to_string()),
     };
+    let email_str = user.email;
 
-    // Step 1.2: Check if user exists
-    let user_exists = tokio::task::spawn_blocking({
-        let pool = pool.clone();
-        let email_to_check = email_str.clone();
-        move || {
-            let mut conn = pool.get().expect("Failed to get database connection");
-            users
-                .filter(user_email.eq(email_to_check))
-                .first::<User>(&mut conn)
-                .optional()
-        }
-    })
-    .await;
-
-    match user_exists {
-        Ok(Ok(None)) => {
-            // No user found for this email
-            return (
-                Status::BadRequest,
-                "No user found for the provided email".to_string(),
-            );
-        }
-        Ok(Ok(Some(_user))) => {
-            // User found, proceed to category name existence check
-        }
-        Ok(Err(e)) => {
-            eprintln!("Error checking user existence: {:?}", e);
-            return (Status::InternalServerError, "Database error".to_string());
-        }
-        Err(e) => {
-            eprintln!("Blocking task failed during user check: {:?}", e);
-            return (
-                Status::InternalServerError,
-                "Internal server error".to_string(),
-            );
-        }
-    }
-
-    // Step 1.3: Check if the category_nickname with old value entry exists in category table
+    // Step 1.2: Check if the category_nickname with old value entry exists in category table
     let nickname_exists = tokio::task::spawn_blocking({
         let pool = pool.clone();
         let email_to_check = email_str.clone();
@@ -378,10 +603,10 @@ pub async fn handle_category_update(
     match nickname_exists {
         Ok(Ok(None)) => {
             // Category nickname does not exist, cannot update
-            return (
+            (
                 Status::BadRequest,
                 "Failed to update category: unable to match existing entry".to_string(),
-            );
+            )
         }
         Ok(Ok(Some(_))) => {
             // Step 2: Proceed to update the matching category
@@ -431,6 +656,94 @@ pub async fn handle_category_update(
                     }
                 }
                 ValidCategoryFields::Type => {
+                    let Some(new_type) = normalize_category_type(&value_to_change) else {
+                        return (Status::BadRequest, INVALID_CATEGORY_TYPE.to_string());
+                    };
+                    // the checks and the update run in one database transaction, with the
+                    // category locked so no split line or transaction sneaks in between
+                    let result = tokio::task::spawn_blocking({
+                        let pool = pool.clone();
+                        move || {
+                            use crate::schema::recurring_transactions::dsl as recurring;
+                            use crate::schema::transactions::dsl as trans;
+
+                            let mut conn = pool.get().expect("Failed to get database connection");
+                            conn.transaction(|conn| {
+                                let found = categories
+                                    .filter(nickname.eq(&cat_to_change))
+                                    .filter(email.eq(&email_to_change))
+                                    .for_update()
+                                    .first::<Category>(conn)?;
+                                // split lines all share one type, see /split_create
+                                let split_lines: i64 = transactions
+                                    .filter(trans::category_id.eq(found.category_id))
+                                    .filter(trans::split_id.is_not_null())
+                                    .count()
+                                    .get_result(conn)?;
+                                if split_lines > 0 {
+                                    return Ok(Err(SPLIT_CATEGORY_TYPE));
+                                }
+                                // transfers are left out of cash flow, tag and payee reports
+                                // and budget alerts, so rows would silently change meaning
+                                let crosses_transfer = found.category_type != new_type
+                                    && (found.category_type == "transfer"
+                                        || new_type == "transfer");
+                                if crosses_transfer {
+                                    let used_rows: i64 = transactions
+                                        .filter(trans::category_id.eq(found.category_id))
+                                        .count()
+                                        .get_result(conn)?;
+                                    let used_templates: i64 = recurring_transactions
+                                        .filter(recurring::category_id.eq(found.category_id))
+                                        .count()
+                                        .get_result(conn)?;
+                                    if used_rows + used_templates > 0 {
+                                        return Ok(Err(TRANSFER_CATEGORY_TYPE));
+                                    }
+                                }
+                                diesel::update(
+                                    categories.filter(category_id.eq(found.category_id)),
+                                )
+                                .set(category_type.eq(new_type))
+                                .execute(conn)?;
+                                Ok::<_, diesel::result::Error>(Ok(()))
+                            })
+                        }
+                    })
+                    .await;
+
+                    match result {
+                        Ok(Ok(Err(conflict))) => (Status::Conflict, conflict.to_string()),
+                        Ok(Ok(Ok(()))) => {
+                            // Successfully updated the category field
+                            let msg = format!(
+                                "Successfully updated category {} field {} to {}",
+                                category_nickname,
+                                field,
+                                new_value.clone()
+                            );
+                            (Status::Created, msg)
+                        }
+                        Ok(Err(e)) => {
+                            eprintln!("Database error during insertion: {:?}", e);
+                            (Status::InternalServerError, "Database error".to_string())
+                        }
+                        Err(e) => {
+                            eprintln!("Blocking task failed during insertion: {:?}", e);
+                            (
+                                Status::InternalServerError,
+                                "Internal server error".to_string(),
+                            )
+                        }
+                    }
+                }
+                ValidCategoryFields::Budget => {
+                    let new_budget: Decimal = match value_to_change.trim().parse() {
+                        Ok(parsed_budget) => parsed_budget,
+                        Err(_) => {
+                            return (Status::BadRequest, "Invalid budget value".to_string());
+                        }
+                    };
                     let result = tokio::task::spawn_blocking({
                         let pool = pool.clone();
                         move || {
@@ -440,7 +753,7 @@ pub async fn handle_category_update(
                                     .filter(nickname.eq(cat_to_change))
                                     .filter(email.eq(email_to_change)),
                             )
-                            .set(category_type.eq(value_to_change))
+                            .set(budget.eq(new_budget))
                             .execute(&mut conn)
                         }
                     })
@@ -470,8 +783,7 @@ pub async fn handle_category_update(
                         }
                     }
                 }
-                ValidCategoryFields::Budget => {
-                    let float_budget: f64 = value_to_change.parse().unwrap();
+                ValidCategoryFields::Freq => {
                     let result = tokio::task::spawn_blocking({
                         let pool = pool.clone();
                         move || {
@@ -481,12 +793,11 @@ pub async fn handle_category_update(
                                     .filter(nickname.eq(cat_to_change))
                                     .filter(email.eq(email_to_change)),
                             )
-                            .set(budget.eq(float_budget))
+                            .set(budget_freq.eq(value_to_change))
                             .execute(&mut conn)
                         }
                     })
                     .await;
-
                     match result {
                         Ok(Ok(_)) => {
                             // Successfully updated the category field
@@ -511,7 +822,10 @@ pub async fn handle_category_update(
                         }
                     }
                 }
-                ValidCategoryFields::Freq => {
+                ValidCategoryFields::Rollover => {
+                    let Some(new_rollover) = normalize_rollover(&value_to_change) else {
+                        return (Status::BadRequest, INVALID_ROLLOVER.to_string());
+                    };
                     let result = tokio::task::spawn_blocking({
                         let pool = pool.clone();
                         move || {
@@ -521,7 +835,49 @@ pub async fn handle_category_update(
                                     .filter(nickname.eq(cat_to_change))
                                     .filter(email.eq(email_to_change)),
                             )
-                            .set(budget_freq.eq(value_to_change))
+                            .set(rollover.eq(new_rollover))
+                            .execute(&mut conn)
+                        }
+                    })
+                    .await;
+                    match result {
+                        Ok(Ok(_)) => {
+                            // Successfully updated the category field
+                            let msg = format!(
+                                "Successfully updated category {} field {} to {}",
+                                category_nickname,
+                                field,
+                                new_value.clone()
+                            );
+                            (Status::Created, msg)
+                        }
+                        Ok(Err(e)) => {
+                            eprintln!("Database error during insertion: {:?}", e);
+                            (Status::InternalServerError, "Database error".to_string())
+                        }
+                        Err(e) => {
+                            eprintln!("Blocking task failed during insertion: {:?}", e);
+                            (
+                                Status::InternalServerError,
+                                "Internal server error".to_string(),
+                            )
+                        }
+                    }
+                }
+                ValidCategoryFields::AlertThresholds => {
+                    let Some(new_thresholds) = parse_alert_thresholds(&value_to_change) else {
+                        return (Status::BadRequest, INVALID_ALERT_THRESHOLDS.to_string());
+                    };
+                    let result = tokio::task::spawn_blocking({
+                        let pool = pool.clone();
+                        move || {
+                            let mut conn = pool.get().expect("Failed to get database connection");
+                            diesel::update(
+                                categories
+                                    .filter(nickname.eq(cat_to_change))
+                                    .filter(email.eq(email_to_change)),
+                            )
+                            .set(alert_thresholds.eq(new_thresholds))
                             .execute(&mut conn)
                         }
                     })
@@ -0,0 +1,102 @@
+use crate::currency::normalize_currency;
+use crate::db::DbPool;
+use crate::guards::AuthUser;
+use crate::models::exchange_rate::NewExchangeRate;
+use crate::schema::exchange_rates::dsl::*;
+use diesel::pg::upsert::excluded;
+use diesel::prelude::*;
+use rocket::http::Status;
+use std::env;
+use std::fs::File;
+use std::io::Read;
+
+// Reads `date,from,to,rate` rows and upserts them, a rate for an existing day is replaced.
+// Either every row is imported or none, the error names the first bad line.
+pub fn import_rates_csv<R: Read>(conn: &mut PgConnection, reader: R) -> Result<usize, String> {
+    let mut csv_reader = csv::ReaderBuilder::new().trim(csv::Trim::All).from_reader(reader);
+    let mut new_rates: Vec<NewExchangeRate> = vec![];
+    for (line_idx, record) in csv_reader.deserialize::<NewExchangeRate>().enumerate() {
+        // line 1 is the header
+        let line_no = line_idx + 2;
+        let mut new_rate = record.map_err(|e| format!("Line {}: {}", line_no, e))?;
+        new_rate.from_currency = normalize_currency(&new_rate.from_currency)
+            .ok_or_else(|| format!("Line {}: invalid currency code", line_no))?;
+        new_rate.to_currency = normalize_currency(&new_rate.to_currency)
+            .ok_or_else(|| format!("Line {}: invalid currency code", line_no))?;
+        if new_rate.rate <= rust_decimal::Decimal::ZERO {
+            return Err(format!("Line {}: rate must be positive", line_no));
+        }
+        new_rates.push(new_rate);
+    }
+
+    conn.transaction(|conn| {
+        // one statement per row, a single upsert cannot touch the same key twice
+        for new_rate in new_rates.iter() {
+            diesel::insert_into(exchange_rates)
+                .values(new_rate)
+                .on_conflict((rate_date, from_currency, to_currency))
+                .do_update()
+                .set(rate.eq(excluded(rate)))
+                .execute(conn)?;
+        }
+        Ok::<_, diesel::result::Error>(new_rates.len())
+    })
+    .map_err(|e| format!("Database error: {}", e))
+}
+
+// Startup: load the file named by `EXCHANGE_RATES_CSV`, if any
+pub fn load_rates_from_env(pool: &DbPool) {
+    let Ok(csv_path) = env::var("EXCHANGE_RATES_CSV") else {
+        return;
+    };
+    let csv_file = match File::open(&csv_path) {
+        Ok(csv_file) => csv_file,
+        Err(e) => {
+            eprintln!("Could not open exchange rates file {}: {:?}", csv_path, e);
+            return;
+        }
+    };
+    let mut conn = pool.get().expect("Failed to get database connection");
+    match import_rates_csv(&mut conn, csv_file) {
+        Ok(count) => println!("Loaded {} exchange rates from {}", count, csv_path),
+        Err(e) => eprintln!("Failed to load exchange rates from {}: {}", csv_path, e),
+    }
+}
+
+// Rates are shared by every user, only the emails listed in `RATE_ADMIN_EMAILS`
+// (comma separated) may replace them through the API
+fn is_rate_admin(user_email: &str) -> bool {
+    env::var("RATE_ADMIN_EMAILS").is_ok_and(|admins| {
+        admins
+            .split(',')
+            .any(|admin| admin.trim().eq_ignore_ascii_case(user_email))
+    })
+}
+
+// POST /import_rates
+pub async fn handle_import_rates(user: AuthUser, csv_body: String, pool: DbPool) -> (Status, String) {
+    if !is_rate_admin(&user.email) {
+        return (
+            Status::Forbidden,
+            "Only rate administrators can import exchange rates".to_string(),
+        );
+    }
+
+    let result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        move || {
+            let mut conn = pool.get().expect("Failed to get database connection");
+            import_rates_csv(&mut conn, csv_body.as_bytes())
+        }
+    })
+    .await;
+
+    match result {
+        Ok(Ok(count)) => (Status::Ok, format!("Imported {} exchange rates", count)),
+        Ok(Err(e)) => (Status::BadRequest, e),
+        Err(e) => {
+            eprintln!("Blocking task failed during rate import: {:?}", e);
+            (Status::InternalServerError, "Internal server error".to_string())
+        }
+    }
+}
@@ -0,0 +1,801 @@
+use crate::db::DbPool;
+use crate::guards::AuthUser;
+use crate::handlers::notification_handler::record_budget_alerts;
+use crate::handlers::payee_handler::find_or_create_payee;
+use crate::models::account::Account;
+use crate::models::category::Category;
+use crate::models::import::{
+    normalize_sign_convention, ImportMapping, ImportPreview, ImportProfile, ImportRequest,
+    ImportResult, ImportRow, NewImportProfile, DEFAULT_DATE_FORMAT, DEFAULT_SIGN_CONVENTION,
+};
+use crate::models::payee::{normalize_payee, MAX_PAYEE_LENGTH};
+use crate::models::transaction::NewTransaction;
+use crate::period::BudgetCalendar;
+use crate::schema::import_profiles::dsl::{account_id, import_profiles};
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, NaiveDate, Utc};
+use diesel::prelude::*;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+// Longer statements have to be split, a year of daily card use is well below it
+const MAX_IMPORT_ROWS: usize = 10_000;
+
+// POST /import_preview
+// Reads a statement the way /import_commit would, without writing anything
+pub async fn handle_import_preview(
+    user: AuthUser,
+    request: ImportRequest,
+    pool: DbPool,
+) -> Result<Json<ImportPreview>, (Status, String)> {
+    let preview_result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        move || {
+            let mut conn = pool.get().expect("Failed to get database connection");
+            let prepared = match prepare_import(&mut conn, &user.email, &request)? {
+                Ok(prepared) => prepared,
+                Err(rejected) => return Ok(Err(rejected)),
+            };
+            let count_status = |wanted: &str| {
+                prepared
+                    .rows
+                    .iter()
+                    .filter(|row| row.status == wanted)
+                    .count()
+            };
+            Ok::<_, diesel::result::Error>(Ok(ImportPreview {
+                account_name: prepared.account.account_name.clone(),
+                new_rows: count_status("new"),
+                duplicates: count_status("duplicate"),
+                invalid: count_status("invalid"),
+                mapping: prepared.mapping,
+                rows: prepared.rows,
+            }))
+        }
+    })
+    .await;
+
+    match preview_result {
+        Ok(Ok(Ok(preview))) => Ok(Json(preview)),
+        Ok(Ok(Err(rejected))) => Err(rejected),
+        Ok(Err(e)) => {
+            eprintln!("Database error during import preview: {:?}", e);
+            Err((Status::InternalServerError, "Database error".to_string()))
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed during import preview: {:?}", e);
+            Err((
+                Status::InternalServerError,
+                "Internal server error".to_string(),
+            ))
+        }
+    }
+}
+
+// POST /import_commit
+// Records the new rows of a statement, duplicates and invalid rows are skipped.
+// The mapping is saved as the account's profile for the next statement.
+pub async fn handle_import_commit(
+    user: AuthUser,
+    request: ImportRequest,
+    pool: DbPool,
+) -> Result<Json<ImportResult>, (Status, String)> {
+    let commit_result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        let owner = user.email.clone();
+        move || {
+            let mut conn = pool.get().expect("Failed to get database connection");
+            // Step 1: read the statement exactly like the preview did
+            let prepared = match prepare_import(&mut conn, &owner, &request)? {
+                Ok(prepared) => prepared,
+                Err(rejected) => return Ok(Err(rejected)),
+            };
+
+            // Step 2: write the new rows and the profile at once
+            let mut result = ImportResult {
+                imported: 0,
+                duplicates: 0,
+                invalid: 0,
+            };
+            let mut latest_dates: HashMap<i32, DateTime<Utc>> = HashMap::new();
+            conn.transaction(|conn| {
+                let mut payee_ids: HashMap<String, i32> = HashMap::new();
+                let mut new_transactions = vec![];
+                for row in prepared.rows.iter() {
+                    match row.status.as_str() {
+                        "duplicate" => result.duplicates += 1,
+                        "invalid" => result.invalid += 1,
+                        _ => {
+                            let (Some(day), Some(row_amount)) = (row.date, row.amount) else {
+                                continue;
+                            };
+                            let Some(day_start) = prepared.calendar.local_midnight(day) else {
+                                result.invalid += 1;
+                                continue;
+                            };
+                            let row_payee_id = match row.payee.as_deref() {
+                                Some(payee_name) => match payee_ids.get(payee_name) {
+                                    Some(known_id) => Some(*known_id),
+                                    None => {
+                                        let created_id =
+                                            find_or_create_payee(conn, &owner, payee_name)?;
+                                        payee_ids.insert(payee_name.to_string(), created_id);
+                                        Some(created_id)
+                                    }
+                                },
+                                None => None,
+                            };
+                            let row_cat_id = match &prepared.inflow_category {
+                                Some(inflow)
+                                    if row.category_name.as_deref() == Some(&inflow.nickname) =>
+                                {
+                                    inflow.category_id
+                                }
+                                _ => prepared.category.category_id,
+                            };
+                            let row_date = day_start.with_timezone(&Utc);
+                            let latest_date = latest_dates.entry(row_cat_id).or_insert(row_date);
+                            *latest_date = (*latest_date).max(row_date);
+                            new_transactions.push(NewTransaction {
+                                email: owner.clone(),
+                                category_id: row_cat_id,
+                                amount: row_amount,
+                                notes: row.description.clone(),
+                                account_id: prepared.account.account_id,
+                                transaction_date: row_date,
+                                currency: None,
+                                payee_id: row_payee_id,
+                            });
+                        }
+                    }
+                }
+                result.imported = diesel::insert_into(crate::schema::transactions::table)
+                    .values(&new_transactions)
+                    .execute(conn)?;
+
+                let new_profile = NewImportProfile {
+                    email: owner.clone(),
+                    account_id: prepared.account.account_id,
+                    category_id: Some(prepared.category.category_id),
+                    inflow_category_id: prepared
+                        .inflow_category
+                        .as_ref()
+                        .map(|inflow| inflow.category_id),
+                    has_header: prepared.mapping.has_header.unwrap_or(true),
+                    date_column: prepared.mapping.date_column.clone(),
+                    date_format: prepared
+                        .mapping
+                        .date_format
+                        .clone()
+                        .unwrap_or_else(|| DEFAULT_DATE_FORMAT.to_string()),
+                    amount_column: prepared.mapping.amount_column.clone(),
+                    debit_column: prepared.mapping.debit_column.clone(),
+                    credit_column: prepared.mapping.credit_column.clone(),
+                    description_column: prepared.mapping.description_column.clone(),
+                    payee_column: prepared.mapping.payee_column.clone(),
+                    sign_convention: prepared
+                        .mapping
+                        .sign_convention
+                        .clone()
+                        .unwrap_or_else(|| DEFAULT_SIGN_CONVENTION.to_string()),
+                    updated_at: Utc::now(),
+                };
+                diesel::insert_into(import_profiles)
+                    .values(&new_profile)
+                    .on_conflict(account_id)
+                    .do_update()
+                    .set(&new_profile)
+                    .execute(conn)?;
+                Ok::<_, diesel::result::Error>(())
+            })?;
+
+            // the rows are already saved, a failed alert check does not undo them.
+            // Only the latest period is checked, an old statement raises no stale alerts.
+            for (alert_cat_id, latest_date) in latest_dates {
+                if let Err(e) = record_budget_alerts(&mut conn, &owner, alert_cat_id, latest_date) {
+                    eprintln!("Error recording budget alerts: {:?}", e);
+                }
+            }
+            Ok::<_, diesel::result::Error>(Ok(result))
+        }
+    })
+    .await;
+
+    match commit_result {
+        Ok(Ok(Ok(result))) => Ok(Json(result)),
+        Ok(Ok(Err(rejected))) => Err(rejected),
+        Ok(Err(e)) => {
+            eprintln!("Database error during import commit: {:?}", e);
+            Err((Status::InternalServerError, "Database error".to_string()))
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed during import commit: {:?}", e);
+            Err((
+                Status::InternalServerError,
+                "Internal server error".to_string(),
+            ))
+        }
+    }
+}
+
+// GET /import_profile?<account_name>
+// The mapping the last import into the account used
+pub async fn handle_import_profile(
+    user: AuthUser,
+    acc_name: String,
+    pool: DbPool,
+) -> Result<Json<ImportMapping>, (Status, String)> {
+    let profile_result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        move || {
+            let mut conn = pool.get().expect("Failed to get database connection");
+            let Some(found_account) = find_import_account(&mut conn, &user.email, &acc_name)?
+            else {
+                return Ok(Err((
+                    Status::BadRequest,
+                    "No account found for the provided email".to_string(),
+                )));
+            };
+            match load_profile_mapping(&mut conn, found_account.account_id)? {
+                Some(mapping) => Ok::<_, diesel::result::Error>(Ok(mapping)),
+                None => Ok(Err((
+                    Status::NotFound,
+                    "No import profile saved for this account".to_string(),
+                ))),
+            }
+        }
+    })
+    .await;
+
+    match profile_result {
+        Ok(Ok(Ok(mapping))) => Ok(Json(mapping)),
+        Ok(Ok(Err(rejected))) => Err(rejected),
+        Ok(Err(e)) => {
+            eprintln!("Database error during import profile retrieval: {:?}", e);
+            Err((Status::InternalServerError, "Database error".to_string()))
+        }
+        Err(e) => {
+            eprintln!(
+                "Blocking task failed during import profile retrieval: {:?}",
+                e
+            );
+            Err((
+                Status::InternalServerError,
+                "Internal server error".to_string(),
+            ))
+        }
+    }
+}
+
+// Some helper functions
+
+// A statement read against its account and categories, ready to preview or commit
+struct PreparedImport {
+    account: Account,
+    category: Category,
+    inflow_category: Option<Category>,
+    mapping: ImportMapping,
+    calendar: BudgetCalendar,
+    rows: Vec<ImportRow>,
+}
+
+// Where each mapped field sits in a record, 0-based
+struct ColumnLayout {
+    date: usize,
+    amount: Option<usize>,
+    debit: Option<usize>,
+    credit: Option<usize>,
+    description: Option<usize>,
+    payee: Option<usize>,
+}
+
+// Imports only go to the caller's active accounts
+fn find_import_account(
+    conn: &mut PgConnection,
+    owner: &str,
+    acc_name: &str,
+) -> QueryResult<Option<Account>> {
+    use crate::schema::accounts::dsl as acc;
+
+    acc::accounts
+        .filter(acc::email.eq(owner))
+        .filter(acc::account_name.eq(acc_name))
+        .filter(acc::archived_at.is_null())
+        .first::<Account>(conn)
+        .optional()
+}
+
+// The saved profile of an account in the shape clients send it
+fn load_profile_mapping(
+    conn: &mut PgConnection,
+    profile_account_id: i32,
+) -> QueryResult<Option<ImportMapping>> {
+    use crate::schema::categories::dsl as cat;
+
+    let Some(profile) = import_profiles
+        .filter(account_id.eq(profile_account_id))
+        .first::<ImportProfile>(conn)
+        .optional()?
+    else {
+        return Ok(None);
+    };
+    let mut category_name_of = |profile_cat_id: Option<i32>| match profile_cat_id {
+        Some(profile_cat_id) => cat::categories
+            .filter(cat::category_id.eq(profile_cat_id))
+            .select(cat::nickname)
+            .first::<String>(conn)
+            .optional(),
+        None => Ok(None),
+    };
+    Ok(Some(ImportMapping {
+        category_name: category_name_of(profile.category_id)?,
+        inflow_category_name: category_name_of(profile.inflow_category_id)?,
+        has_header: Some(profile.has_header),
+        date_column: profile.date_column,
+        date_format: Some(profile.date_format),
+        amount_column: profile.amount_column,
+        debit_column: profile.debit_column,
+        credit_column: profile.credit_column,
+        description_column: profile.description_column,
+        payee_column: profile.payee_column,
+        sign_convention: Some(profile.sign_convention),
+    }))
+}
+
+// Fills in the defaults of a mapping and drops blank columns
+fn normalize_mapping(mapping: ImportMapping) -> Result<ImportMapping, &'static str> {
+    let column = |spec: Option<String>| {
+        spec.map(|spec| spec.trim().to_string())
+            .filter(|spec| !spec.is_empty())
+    };
+    let date_column = column(Some(mapping.date_column)).ok_or("Map the date column")?;
+    let date_format = column(mapping.date_format).unwrap_or(DEFAULT_DATE_FORMAT.to_string());
+    if StrftimeItems::new(&date_format).any(|item| matches!(item, Item::Error)) {
+        return Err("Invalid date format, expected a chrono format such as %m/%d/%Y");
+    }
+    let sign_convention = match column(mapping.sign_convention) {
+        Some(raw) => normalize_sign_convention(&raw)
+            .ok_or("Invalid sign convention: expected negative_outflow or positive_outflow")?,
+        None => DEFAULT_SIGN_CONVENTION.to_string(),
+    };
+    let normalized = ImportMapping {
+        category_name: column(mapping.category_name),
+        inflow_category_name: column(mapping.inflow_category_name),
+        has_header: Some(mapping.has_header.unwrap_or(true)),
+        date_column,
+        date_format: Some(date_format),
+        amount_column: column(mapping.amount_column),
+        debit_column: column(mapping.debit_column),
+        credit_column: column(mapping.credit_column),
+        description_column: column(mapping.description_column),
+        payee_column: column(mapping.payee_column),
+        sign_convention: Some(sign_convention),
+    };
+    let maps_amount = normalized.amount_column.is_some()
+        || normalized.debit_column.is_some()
+        || normalized.credit_column.is_some();
+    if !maps_amount {
+        return Err("Map either an amount column or debit and credit columns");
+    }
+    if normalized.amount_column.is_some()
+        && (normalized.debit_column.is_some() || normalized.credit_column.is_some())
+    {
+        return Err("Map either an amount column or debit and credit columns, not both");
+    }
+    Ok(normalized)
+}
+
+// A header name, ignoring case, or a 1-based position
+fn resolve_column(spec: &str, headers: Option<&csv::StringRecord>) -> Result<usize, String> {
+    if let Ok(position) = spec.parse::<usize>() {
+        if position >= 1 {
+            return Ok(position - 1);
+        }
+    }
+    headers
+        .and_then(|headers| {
+            headers
+                .iter()
+                .position(|header| header.trim().eq_ignore_ascii_case(spec))
+        })
+        .ok_or_else(|| format!("Column {} not found in the statement", spec))
+}
+
+// Currency symbols a statement may write next to an amount
+const CURRENCY_SYMBOLS: [&str; 7] = ["US$", "CA$", "C$", "$", "€", "£", "¥"];
+
+// Amounts as banks write them: "1,234.56", "1 234.56", "$12.00", "-$12.00", "(12.00)" or
+// "12.00-" for negatives. Only currency symbols, spaces and thousands separators in groups
+// of three are dropped, anything else ("12,50", "1.234,56", "12.00 CR") is unreadable.
+// `None` for a blank cell, `Some(Err)` for anything unreadable.
+fn parse_statement_amount(raw: &str) -> Option<Result<Decimal, ()>> {
+    let text = raw.trim();
+    if text.is_empty() {
+        return None;
+    }
+    let (mut text, mut negative) = match text.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+        Some(inner) => (inner.trim(), true),
+        None => match text.strip_suffix('-') {
+            Some(inner) => (inner.trim_end(), true),
+            None => (text, false),
+        },
+    };
+
+    // a sign and a currency symbol in front, in either order, or the symbol after
+    let (mut signed, mut symbol) = (negative, false);
+    loop {
+        if let Some(rest) = text.strip_prefix(['-', '+']).filter(|_| !signed) {
+            negative = text.starts_with('-');
+            signed = true;
+            text = rest.trim_start();
+        } else if let Some(rest) = CURRENCY_SYMBOLS
+            .iter()
+            .find_map(|sym| text.strip_prefix(sym))
+            .filter(|_| !symbol)
+        {
+            symbol = true;
+            text = rest.trim_start();
+        } else {
+            break;
+        }
+    }
+    if !symbol {
+        if let Some(rest) = CURRENCY_SYMBOLS
+            .iter()
+            .find_map(|sym| text.strip_suffix(sym))
+        {
+            text = rest.trim_end();
+        }
+    }
+
+    // digits, with the thousands split by commas or spaces, then an optional fraction
+    let (whole, fraction) = match text.split_once('.') {
+        Some((whole, fraction)) => (whole, fraction),
+        None => (text, ""),
+    };
+    let is_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+    let whole: String = match whole
+        .chars()
+        .find(|c| matches!(c, ',' | ' ' | '\u{a0}' | '\u{202f}'))
+    {
+        Some(separator) => {
+            let groups: Vec<&str> = whole.split(separator).collect();
+            if !(1..=3).contains(&groups[0].len()) || groups[1..].iter().any(|g| g.len() != 3) {
+                return Some(Err(()));
+            }
+            groups.concat()
+        }
+        None => whole.to_string(),
+    };
+    if !is_digits(&whole) || !is_digits(fraction) || (whole.is_empty() && fraction.is_empty()) {
+        return Some(Err(()));
+    }
+    let number = match fraction {
+        "" => whole,
+        _ => format!("{}.{}", whole, fraction),
+    };
+    Some(match number.parse::<Decimal>() {
+        Ok(value) if negative => Ok(-value),
+        Ok(value) => Ok(value),
+        Err(_) => Err(()),
+    })
+}
+
+// Reads every record of a statement, `amount` being what left the account (negative for
+// money coming in). Rows that cannot be read are kept as "invalid" with the reason.
+fn read_statement(csv_text: &str, mapping: &ImportMapping) -> Result<Vec<ImportRow>, String> {
+    let has_header = mapping.has_header.unwrap_or(true);
+    let mut csv_reader = csv::ReaderBuilder::new()
+        .has_headers(has_header)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(csv_text.as_bytes());
+    let headers = if has_header {
+        Some(
+            csv_reader
+                .headers()
+                .map_err(|e| format!("Invalid CSV: {}", e))?
+                .clone(),
+        )
+    } else {
+        None
+    };
+    let resolve = |spec: &Option<String>| match spec {
+        Some(spec) => resolve_column(spec, headers.as_ref()).map(Some),
+        None => Ok(None),
+    };
+    let layout = ColumnLayout {
+        date: resolve_column(&mapping.date_column, headers.as_ref())?,
+        amount: resolve(&mapping.amount_column)?,
+        debit: resolve(&mapping.debit_column)?,
+        credit: resolve(&mapping.credit_column)?,
+        description: resolve(&mapping.description_column)?,
+        payee: resolve(&mapping.payee_column)?,
+    };
+    let date_format = mapping
+        .date_format
+        .as_deref()
+        .unwrap_or(DEFAULT_DATE_FORMAT);
+    let outflow_sign = match mapping.sign_convention.as_deref() {
+        Some("positive_outflow") => Decimal::ONE,
+        _ => Decimal::NEGATIVE_ONE,
+    };
+
+    let mut rows = vec![];
+    for record in csv_reader.records() {
+        let record = record.map_err(|e| format!("Invalid CSV: {}", e))?;
+        if record.iter().all(|cell| cell.is_empty()) {
+            continue;
+        }
+        if rows.len() == MAX_IMPORT_ROWS {
+            return Err(format!(
+                "Statements are limited to {} rows, split the file",
+                MAX_IMPORT_ROWS
+            ));
+        }
+        let cell = |idx: Option<usize>| {
+            idx.and_then(|idx| record.get(idx))
+                .filter(|text| !text.is_empty())
+        };
+        let mut row = ImportRow {
+            line: record
+                .position()
+                .map(|pos| pos.line() as usize)
+                .unwrap_or_default(),
+            date: None,
+            category_name: None,
+            amount: None,
+            description: cell(layout.description).map(str::to_string),
+            payee: cell(layout.payee).and_then(|text| {
+                normalize_payee(&text.chars().take(MAX_PAYEE_LENGTH).collect::<String>())
+            }),
+            status: "new".to_string(),
+            error: None,
+        };
+
+        row.date = cell(Some(layout.date))
+            .and_then(|text| NaiveDate::parse_from_str(text, date_format).ok());
+        let outflow = match layout.amount {
+            Some(_) => match parse_statement_amount(cell(layout.amount).unwrap_or_default()) {
+                Some(Ok(value)) => Ok(value * outflow_sign),
+                Some(Err(())) => Err("Invalid amount"),
+                None => Err("Missing amount"),
+            },
+            None => {
+                // banks disagree on the sign of debits, only the column counts
+                let debit = parse_statement_amount(cell(layout.debit).unwrap_or_default());
+                let credit = parse_statement_amount(cell(layout.credit).unwrap_or_default());
+                match (debit, credit) {
+                    (Some(Err(())), _) | (_, Some(Err(()))) => Err("Invalid amount"),
+                    (None, None) => Err("Missing amount"),
+                    (debit, credit) => Ok(debit.and_then(Result::ok).unwrap_or_default().abs()
+                        - credit.and_then(Result::ok).unwrap_or_default().abs()),
+                }
+            }
+        };
+        let error = match (row.date, outflow) {
+            (None, _) if cell(Some(layout.date)).is_none() => Some("Missing date".to_string()),
+            (None, _) => Some(format!("Date does not match {}", date_format)),
+            (_, Err(reason)) => Some(reason.to_string()),
+            (_, Ok(value)) if value.is_zero() => Some("Amount is zero".to_string()),
+            (_, Ok(value)) => {
+                row.amount = Some(value);
+                None
+            }
+        };
+        if let Some(reason) = error {
+            row.status = "invalid".to_string();
+            row.error = Some(reason);
+        }
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+// Everything /import_preview and /import_commit share: find the account, the mapping and
+// its category, read the statement, then flag rows already recorded on the account
+fn prepare_import(
+    conn: &mut PgConnection,
+    owner: &str,
+    request: &ImportRequest,
+) -> QueryResult<Result<PreparedImport, (Status, String)>> {
+    use crate::schema::categories::dsl as cat;
+    use crate::schema::transactions::dsl as trans;
+
+    let bad_request = |message: &str| Ok(Err((Status::BadRequest, message.to_string())));
+
+    // Step 1: the account, and the mapping sent or saved for it
+    let Some(import_account) = find_import_account(conn, owner, &request.account_name)? else {
+        return bad_request("No account found for the provided email");
+    };
+    let raw_mapping = match request.mapping.clone() {
+        Some(mapping) => mapping,
+        None => match load_profile_mapping(conn, import_account.account_id)? {
+            Some(mapping) => mapping,
+            None => return bad_request("No import profile saved for this account, send a mapping"),
+        },
+    };
+    let mapping = match normalize_mapping(raw_mapping) {
+        Ok(mapping) => mapping,
+        Err(message) => return bad_request(message),
+    };
+
+    // Step 2: the category of the rows, and the one of the rows bringing money in if mapped
+    let Some(cat_name) = mapping.category_name.as_deref() else {
+        return bad_request("Choose the category of the imported transactions");
+    };
+    let mut find_category = |name: &str| {
+        cat::categories
+            .filter(cat::email.eq(owner))
+            .filter(cat::nickname.eq(name))
+            .filter(cat::archived_at.is_null())
+            .first::<Category>(conn)
+            .optional()
+    };
+    let Some(import_category) = find_category(cat_name)? else {
+        return bad_request("No category found for the provided email");
+    };
+    let inflow_category = match mapping.inflow_category_name.as_deref() {
+        Some(inflow_name) => match find_category(inflow_name)? {
+            Some(found) => Some(found),
+            None => return bad_request("No inflow category found for the provided email"),
+        },
+        None => None,
+    };
+    let transfer_category = |category: &Category| category.category_type == "transfer";
+    if transfer_category(&import_category)
+        || inflow_category.as_ref().is_some_and(transfer_category)
+    {
+        return bad_request("Transactions cannot be imported into a transfer category");
+    }
+
+    // Step 3: read the statement, then record each row in its category the way /add_trans
+    // would. Without an inflow category, a row that would come out negative (money coming
+    // in on an expense category, going out of an income one) is flagged rather than guessed.
+    let mut rows = match read_statement(&request.csv, &mapping) {
+        Ok(rows) => rows,
+        Err(message) => return bad_request(&message),
+    };
+    for row in rows.iter_mut() {
+        let Some(outflow) = row.amount else {
+            continue;
+        };
+        let row_category = match &inflow_category {
+            Some(inflow) if outflow.is_sign_negative() => inflow,
+            _ => &import_category,
+        };
+        let recorded = match row_category.category_type.as_str() {
+            "income" => -outflow,
+            _ => outflow,
+        };
+        if inflow_category.is_none() && recorded.is_sign_negative() {
+            row.amount = None;
+            row.status = "invalid".to_string();
+            row.error = Some(match import_category.category_type.as_str() {
+                "income" => {
+                    "Money going out of an income category, map an expense category".to_string()
+                }
+                _ => "Money coming in, map an inflow category".to_string(),
+            });
+            continue;
+        }
+        row.amount = Some(recorded);
+        row.category_name = Some(row_category.nickname.clone());
+    }
+
+    // Step 4: a row matching a transaction of the account on the same local day, with the
+    // same amount and notes, is a duplicate. Each recorded transaction matches one row only,
+    // two identical coffees on the same day stay two rows.
+    let calendar = BudgetCalendar::load(conn, owner)?;
+    let row_days: Vec<NaiveDate> = rows.iter().filter_map(|row| row.date).collect();
+    if let (Some(first_day), Some(last_day)) = (row_days.iter().min(), row_days.iter().max()) {
+        let range = (
+            calendar.local_midnight(*first_day),
+            last_day
+                .succ_opt()
+                .and_then(|next_day| calendar.local_midnight(next_day)),
+        );
+        if let (Some(range_start), Some(range_end)) = range {
+            let mut recorded: HashMap<(NaiveDate, Decimal, Option<String>), usize> = HashMap::new();
+            let existing = trans::transactions
+                .filter(trans::account_id.eq(import_account.account_id))
+                .filter(trans::transaction_date.ge(range_start.with_timezone(&Utc)))
+                .filter(trans::transaction_date.lt(range_end.with_timezone(&Utc)))
+                .select((trans::transaction_date, trans::amount, trans::notes))
+                .load::<(DateTime<Utc>, Decimal, Option<String>)>(conn)?;
+            for (recorded_at, recorded_amount, recorded_notes) in existing {
+                *recorded
+                    .entry((calendar.today(recorded_at), recorded_amount, recorded_notes))
+                    .or_default() += 1;
+            }
+            for row in rows.iter_mut().filter(|row| row.status == "new") {
+                let (Some(day), Some(row_amount)) = (row.date, row.amount) else {
+                    continue;
+                };
+                if let Some(left) = recorded.get_mut(&(day, row_amount, row.description.clone())) {
+                    if *left > 0 {
+                        *left -= 1;
+                        row.status = "duplicate".to_string();
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(Ok(PreparedImport {
+        account: import_account,
+        category: import_category,
+        inflow_category,
+        mapping,
+        calendar,
+        rows,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_statement_amount;
+    use rust_decimal::Decimal;
+
+    fn amount(raw: &str) -> Option<Result<Decimal, ()>> {
+        parse_statement_amount(raw)
+    }
+
+    fn value(text: &str) -> Option<Result<Decimal, ()>> {
+        Some(Ok(text.parse().unwrap()))
+    }
+
+    #[test]
+    fn reads_plain_and_signed_amounts() {
+        assert_eq!(amount("12.50"), value("12.50"));
+        assert_eq!(amount("-12.50"), value("-12.50"));
+        assert_eq!(amount("+12.50"), value("12.50"));
+        assert_eq!(amount("12"), value("12"));
+        assert_eq!(amount(".50"), value("0.50"));
+        assert_eq!(amount(" 4.5 "), value("4.5"));
+    }
+
+    #[test]
+    fn reads_negatives_written_by_banks() {
+        assert_eq!(amount("(12.00)"), value("-12.00"));
+        assert_eq!(amount("12.00-"), value("-12.00"));
+        assert_eq!(amount("($1,234.56)"), value("-1234.56"));
+    }
+
+    #[test]
+    fn drops_currency_symbols_and_thousands_separators() {
+        assert_eq!(amount("$12.00"), value("12.00"));
+        assert_eq!(amount("-$12.00"), value("-12.00"));
+        assert_eq!(amount("$-12.00"), value("-12.00"));
+        assert_eq!(amount("C$ 5"), value("5"));
+        assert_eq!(amount("12.00 €"), value("12.00"));
+        assert_eq!(amount("1,234.56"), value("1234.56"));
+        assert_eq!(amount("1,234,567"), value("1234567"));
+        assert_eq!(amount("1 234.56"), value("1234.56"));
+        assert_eq!(amount("1\u{a0}234.56"), value("1234.56"));
+    }
+
+    #[test]
+    fn rejects_decimal_commas_and_unknown_text() {
+        assert_eq!(amount("12,50"), Some(Err(())));
+        assert_eq!(amount("1.234,56"), Some(Err(())));
+        assert_eq!(amount("1,23,456.00"), Some(Err(())));
+        assert_eq!(amount("12.00 CR"), Some(Err(())));
+        assert_eq!(amount("12.00 DR"), Some(Err(())));
+        assert_eq!(amount("USD 12"), Some(Err(())));
+        assert_eq!(amount("(-12.00)"), Some(Err(())));
+        assert_eq!(amount("--12"), Some(Err(())));
+        assert_eq!(amount("1.2.3"), Some(Err(())));
+        assert_eq!(amount("$"), Some(Err(())));
+        assert_eq!(amount("-"), Some(Err(())));
+        assert_eq!(amount("abc"), Some(Err(())));
+    }
+
+    #[test]
+    fn blank_cells_are_missing() {
+        assert_eq!(amount(""), None);
+        assert_eq!(amount("   "), None);
+    }
+}
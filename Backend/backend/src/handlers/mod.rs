@@ -1,5 +1,13 @@
 pub mod account_handler;
 pub mod auth_handler;
 pub mod category_handler;
+pub mod exchange_rate_handler;
+pub mod import_handler;
+pub mod notification_handler;
+pub mod payee_handler;
+pub mod recurring_handler;
 pub mod report_handler;
+pub mod split_handler;
+pub mod tag_handler;
 pub mod transaction_handler;
+pub mod transfer_handler;
@@ -0,0 +1,226 @@
+use crate::currency::{RateTable, DEFAULT_CURRENCY};
+use crate::db::DbPool;
+use crate::guards::AuthUser;
+use crate::handlers::report_handler::carry_over;
+use crate::models::category::Category;
+use crate::models::notification::{NewNotification, Notification};
+use crate::period::{BudgetCalendar, Period};
+use crate::schema::accounts::dsl::{accounts, currency as account_currency};
+use crate::schema::categories::dsl::categories;
+use crate::schema::notifications::dsl::*;
+use crate::schema::transactions::dsl::{amount, currency, transaction_date, transactions};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rust_decimal::{Decimal, RoundingStrategy};
+
+// Some helper functions
+
+// Sum of a category's transactions within `window`, in the rate table's base currency
+fn spent_in(
+    conn: &mut PgConnection,
+    rates: &RateTable,
+    cat_id: i32,
+    window: &Period,
+) -> QueryResult<Result<Decimal, String>> {
+    let entries = transactions
+        .inner_join(accounts)
+        .filter(crate::schema::transactions::dsl::category_id.eq(cat_id))
+        .filter(transaction_date.ge(window.start_utc()))
+        .filter(transaction_date.lt(window.end_utc()))
+        .select((amount, currency, account_currency, transaction_date))
+        .load::<(Decimal, Option<String>, String, DateTime<Utc>)>(conn)?;
+    let mut total = Decimal::ZERO;
+    for (entry_amount, entry_currency, entry_account_currency, entry_date) in entries {
+        let entry_currency = entry_currency.as_deref().unwrap_or(&entry_account_currency);
+        match rates.convert(entry_amount, entry_currency, entry_date.date_naive()) {
+            Ok(converted) => total += converted,
+            Err(e) => return Ok(Err(e)),
+        }
+    }
+    Ok(Ok(total))
+}
+
+// Called after a transaction is written: records a notification for every alert threshold
+// the category's spending has reached in the budget period of `at`.
+// Each threshold is recorded once per period, so later transactions do not repeat it.
+// Budgets are kept in the default currency, like the reports do without a base currency.
+pub fn record_budget_alerts(
+    conn: &mut PgConnection,
+    owner: &str,
+    cat_id: i32,
+    at: DateTime<Utc>,
+) -> QueryResult<usize> {
+    let cat = categories
+        .filter(crate::schema::categories::dsl::category_id.eq(cat_id))
+        .filter(crate::schema::categories::dsl::email.eq(owner))
+        .first::<Category>(conn)?;
+    let thresholds: Vec<i32> = cat.alert_thresholds.iter().flatten().copied().collect();
+    // Without a budget every threshold would be reached at once, so nothing is alerted
+    if cat.category_type != "expense" || thresholds.is_empty() || cat.budget <= Decimal::ZERO {
+        return Ok(0);
+    }
+    let calendar = BudgetCalendar::load(conn, owner)?;
+    let Some(bounds) = calendar.period_containing(&cat.budget_freq, at) else {
+        return Ok(0);
+    };
+    // the rollover reads the period before too
+    let previous = match cat.rollover.as_str() {
+        "none" => None,
+        _ => calendar.period_before(&cat.budget_freq, &bounds),
+    };
+    let rate_days = bounds
+        .days()
+        .into_iter()
+        .chain(previous.iter().flat_map(Period::days));
+    let rates = RateTable::load(conn, DEFAULT_CURRENCY, rate_days)?;
+
+    // Step 1: what was spent in the period, and the budget it is held against
+    let spent = match spent_in(conn, &rates, cat_id, &bounds)? {
+        Ok(spent) => spent,
+        Err(e) => {
+            eprintln!("Skipping budget alerts for {}: {}", cat.nickname, e);
+            return Ok(0);
+        }
+    };
+    let mut effective_budget = cat.budget;
+    if let Some(previous) = previous {
+        match spent_in(conn, &rates, cat_id, &previous)? {
+            Ok(previous_total) => {
+                effective_budget += carry_over(&cat.rollover, cat.budget, previous_total)
+            }
+            Err(e) => {
+                eprintln!("Skipping budget alerts for {}: {}", cat.nickname, e);
+                return Ok(0);
+            }
+        }
+    }
+    // Overspending carried from last period can leave no budget to take a share of
+    if effective_budget <= Decimal::ZERO {
+        return Ok(0);
+    }
+
+    // Step 2: one notification per threshold reached, already recorded ones are skipped
+    let round = |value: Decimal| value.round_dp_with_strategy(2, RoundingStrategy::MidpointAwayFromZero);
+    let new_notifications: Vec<NewNotification> = thresholds
+        .into_iter()
+        .filter(|limit| spent * Decimal::ONE_HUNDRED >= effective_budget * Decimal::from(*limit))
+        .map(|limit| NewNotification {
+            email: owner.to_string(),
+            category_id: cat_id,
+            threshold: limit,
+            period_start: bounds.start_utc(),
+            message: format!(
+                "{} reached {}% of its {} budget: {} of {} {} spent ({} to {})",
+                cat.nickname,
+                limit,
+                cat.budget_freq,
+                round(spent),
+                round(effective_budget),
+                rates.base(),
+                bounds.start.date_naive(),
+                bounds.end.date_naive(),
+            ),
+        })
+        .collect();
+    if new_notifications.is_empty() {
+        return Ok(0);
+    }
+    diesel::insert_into(notifications)
+        .values(&new_notifications)
+        .on_conflict_do_nothing()
+        .execute(conn)
+}
+
+// GET /notifications?<unread>
+pub async fn handle_notifications(
+    user: AuthUser,
+    unread: Option<bool>,
+    pool: DbPool,
+) -> (Status, Json<Vec<Notification>>) {
+    let notification_result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        let email_to_search = user.email.clone();
+        move || {
+            let mut conn = pool.get().expect("Failed to get database connection");
+            let mut query = notifications
+                .filter(email.eq(email_to_search))
+                .select((
+                    notification_id,
+                    category_id,
+                    threshold,
+                    period_start,
+                    message,
+                    created_at,
+                    read_at,
+                ))
+                .order_by(created_at.desc())
+                .into_boxed();
+            if unread.unwrap_or(false) {
+                query = query.filter(read_at.is_null());
+            }
+            query.load::<Notification>(&mut conn)
+        }
+    })
+    .await;
+
+    match notification_result {
+        Ok(Ok(found)) => (Status::Ok, Json(found)),
+        Ok(Err(e)) => {
+            eprintln!("Database error during notification retrieval: {:?}", e);
+            (Status::InternalServerError, Json(vec![]))
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed during notification retrieval: {:?}", e);
+            (Status::InternalServerError, Json(vec![]))
+        }
+    }
+}
+
+// PATCH /read_notifications?<notification_id>
+// Marks one notification as read, or every unread one when no id is given
+pub async fn handle_read_notifications(
+    user: AuthUser,
+    to_read: Option<i32>,
+    pool: DbPool,
+) -> (Status, String) {
+    let read_result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        let email_to_check = user.email.clone();
+        move || {
+            let mut conn = pool.get().expect("Failed to get database connection");
+            let mut query = diesel::update(notifications)
+                .filter(email.eq(email_to_check))
+                .filter(read_at.is_null())
+                .into_boxed();
+            if let Some(id_to_read) = to_read {
+                query = query.filter(notification_id.eq(id_to_read));
+            }
+            query.set(read_at.eq(Utc::now())).execute(&mut conn)
+        }
+    })
+    .await;
+
+    match read_result {
+        Ok(Ok(0)) if to_read.is_some() => (
+            Status::NotFound,
+            "No unread notification found for the provided ID".to_string(),
+        ),
+        Ok(Ok(rows_read)) => (
+            Status::Ok,
+            format!("{} notifications marked as read", rows_read),
+        ),
+        Ok(Err(e)) => {
+            eprintln!("Database error while marking notifications read: {:?}", e);
+            (Status::InternalServerError, "Database error".to_string())
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed while marking notifications read: {:?}", e);
+            (
+                Status::InternalServerError,
+                "Internal server error".to_string(),
+            )
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use crate::db::{escape_like, DbPool};
+use crate::guards::AuthUser;
+use crate::models::payee::{normalize_payee, NewPayee, Payee, PayeeSummary};
+use crate::schema::payees::dsl::*;
+use diesel::dsl::count;
+use diesel::prelude::*;
+use diesel::sql_types::Text;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use std::collections::HashMap;
+
+define_sql_function!(fn lower(x: Text) -> Text);
+
+// GET /payees?<search>
+// The caller's payees with how many transactions name them, most used first.
+// `search` keeps the payees whose name starts with it, ignoring case.
+pub async fn handle_payee_list(
+    user: AuthUser,
+    search: Option<String>,
+    pool: DbPool,
+) -> (Status, Json<Vec<PayeeSummary>>) {
+    let payee_result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        let owner = user.email.clone();
+        move || {
+            use crate::schema::transactions::dsl as trans;
+
+            let mut conn = pool.get().expect("Failed to get database connection");
+            let mut query = payees
+                .left_join(trans::transactions)
+                .filter(email.eq(&owner))
+                .group_by((payee_id, name))
+                .select((payee_id, name, count(trans::trans_id.nullable())))
+                .order_by((count(trans::trans_id.nullable()).desc(), name))
+                .into_boxed();
+            if let Some(prefix) = search.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+                let pattern = format!("{}%", escape_like(&prefix.to_lowercase()));
+                query = query.filter(lower(name).like(pattern));
+            }
+            query.load::<(i32, String, i64)>(&mut conn).map(|rows| {
+                rows.into_iter()
+                    .map(|(found_id, found_name, used)| PayeeSummary {
+                        payee_id: found_id,
+                        name: found_name,
+                        transactions: used,
+                    })
+                    .collect::<Vec<PayeeSummary>>()
+            })
+        }
+    })
+    .await;
+
+    match payee_result {
+        Ok(Ok(payee_list)) => (Status::Ok, Json(payee_list)),
+        Ok(Err(e)) => {
+            eprintln!("Database error during payee retrieval: {:?}", e);
+            (Status::InternalServerError, Json(vec![]))
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed during payee retrieval: {:?}", e);
+            (Status::InternalServerError, Json(vec![]))
+        }
+    }
+}
+
+// POST /merge_payee?<payee_name>&<into>
+// Moves every transaction of a payee to another one, then deletes it
+pub async fn handle_merge_payee(
+    user: AuthUser,
+    payee_name: String,
+    into: String,
+    pool: DbPool,
+) -> (Status, &'static str) {
+    let merge_result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        let owner = user.email.clone();
+        move || {
+            use crate::schema::transactions::dsl as trans;
+
+            let mut conn = pool.get().expect("Failed to get database connection");
+            // Step 1: find both payees among the caller's
+            let (Some(source), Some(target)) = (
+                find_payee(&mut conn, &owner, &payee_name)?,
+                find_payee(&mut conn, &owner, &into)?,
+            ) else {
+                return Ok(Err((
+                    Status::BadRequest,
+                    "No payee found for the provided email",
+                )));
+            };
+            if source.payee_id == target.payee_id {
+                return Ok(Err((
+                    Status::BadRequest,
+                    "A payee cannot be merged into itself",
+                )));
+            }
+
+            // Step 2: move the transactions, then drop the source
+            let rows_deleted = conn.transaction(|conn| {
+                diesel::update(trans::transactions.filter(trans::payee_id.eq(source.payee_id)))
+                    .set(trans::payee_id.eq(target.payee_id))
+                    .execute(conn)?;
+                diesel::delete(payees.filter(payee_id.eq(source.payee_id))).execute(conn)
+            })?;
+            Ok::<_, diesel::result::Error>(Ok(rows_deleted))
+        }
+    })
+    .await;
+
+    match merge_result {
+        Ok(Ok(Ok(rows_deleted))) => {
+            if rows_deleted > 0 {
+                (Status::Ok, "Payees successfully merged")
+            } else {
+                (Status::InternalServerError, "Failed to merge the payees")
+            }
+        }
+        Ok(Ok(Err(rejected))) => rejected,
+        Ok(Err(e)) => {
+            eprintln!("Error during payee merging: {:?}", e);
+            (Status::InternalServerError, "Database error during merging")
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed during payee merging: {:?}", e);
+            (Status::InternalServerError, "Internal server error")
+        }
+    }
+}
+
+// Some helper functions
+
+// The owner's payee called `raw_name`, ignoring case and extra spaces
+pub fn find_payee(
+    conn: &mut PgConnection,
+    owner: &str,
+    raw_name: &str,
+) -> QueryResult<Option<Payee>> {
+    let Some(payee_name) = normalize_payee(raw_name) else {
+        return Ok(None);
+    };
+    payees
+        .filter(email.eq(owner))
+        .filter(lower(name).eq(payee_name.to_lowercase()))
+        .first::<Payee>(conn)
+        .optional()
+}
+
+// Id of the owner's payee `payee_name`, already normalized, created on first use
+pub fn find_or_create_payee(
+    conn: &mut PgConnection,
+    owner: &str,
+    payee_name: &str,
+) -> QueryResult<i32> {
+    if let Some(found) = find_payee(conn, owner, payee_name)? {
+        return Ok(found.payee_id);
+    }
+    let created = diesel::insert_into(payees)
+        .values(&NewPayee {
+            email: owner.to_string(),
+            name: payee_name.to_string(),
+        })
+        .on_conflict_do_nothing()
+        .returning(payee_id)
+        .get_result::<i32>(conn)
+        .optional()?;
+    match created {
+        Some(created_id) => Ok(created_id),
+        // created by a concurrent request in the meantime
+        None => find_payee(conn, owner, payee_name)?
+            .map(|found| found.payee_id)
+            .ok_or(diesel::result::Error::NotFound),
+    }
+}
+
+// The name of each of `payee_ids`
+pub fn load_payee_names(
+    conn: &mut PgConnection,
+    payee_ids: &[i32],
+) -> QueryResult<HashMap<i32, String>> {
+    Ok(payees
+        .filter(payee_id.eq_any(payee_ids))
+        .select((payee_id, name))
+        .load::<(i32, String)>(conn)?
+        .into_iter()
+        .collect())
+}
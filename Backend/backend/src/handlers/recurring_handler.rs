@@ -0,0 +1,594 @@
+use crate::currency::normalize_currency;
+use crate::db::DbPool;
+use crate::guards::AuthUser;
+use crate::handlers::notification_handler::record_budget_alerts;
+use crate::handlers::transaction_handler::{check_account_name, check_category_name};
+use crate::models::recurring::{
+    normalize_frequency, ClientRecurring, ClientRecurringUpdate, NewRecurring, Recurring,
+    RecurringChanges, RecurringSummary,
+};
+use crate::models::transaction::NewTransaction;
+use crate::period::BudgetCalendar;
+use crate::schema::accounts::dsl::{account_name, accounts};
+use crate::schema::categories::dsl::{categories, nickname};
+use crate::schema::recurring_transactions::dsl::*;
+use crate::schema::transactions::dsl::transactions;
+use chrono::{Days, NaiveDate, Utc};
+use diesel::prelude::*;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use std::collections::HashMap;
+use std::env;
+use std::time::Duration;
+
+const INVALID_FREQUENCY: &str = "Invalid frequency: expected daily, weekly, monthly or yearly";
+const INVALID_INTERVAL: &str = "Interval count must be at least 1";
+const INVALID_END_DATE: &str = "End date must not be before the start date";
+
+// How often due occurrences are posted while the server runs, unless
+// `RECURRING_POST_INTERVAL_SECS` says otherwise
+const DEFAULT_POST_INTERVAL_SECS: u64 = 3600;
+// A template started long ago catches up over several runs, so one run stays bounded
+const MAX_OCCURRENCES_PER_RUN: usize = 5000;
+// Rows per INSERT, well below the 65535 bind parameters Postgres accepts in one statement
+const INSERT_CHUNK_ROWS: usize = 1000;
+
+// Some helper functions
+
+// Schedule days are plain YYYY-MM-DD days in the owner's time zone
+pub fn parse_day(raw: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(raw.trim(), "%Y-%m-%d").ok()
+}
+
+// Posts every occurrence that has come due, for one user or for everyone.
+// An occurrence is due from the start of its day in the owner's time zone and becomes
+// a transaction dated at that moment. Returns how many transactions were posted.
+// Nothing is posted into an archived account or category. A template that fails to post
+// is logged and skipped, the others still post.
+pub fn post_due_recurring(conn: &mut PgConnection, owner: Option<&str>) -> QueryResult<usize> {
+    // local days run at most one day ahead of UTC
+    let latest_due = Utc::now().date_naive() + Days::new(1);
+    let active_accounts = accounts
+        .filter(crate::schema::accounts::dsl::archived_at.is_null())
+        .select(crate::schema::accounts::dsl::account_id);
+    let active_categories = categories
+        .filter(crate::schema::categories::dsl::archived_at.is_null())
+        .select(crate::schema::categories::dsl::category_id);
+    let mut query = recurring_transactions
+        .filter(paused.eq(false))
+        .filter(next_date.le(latest_due))
+        .filter(account_id.eq_any(active_accounts))
+        .filter(category_id.eq_any(active_categories))
+        .select(recurring_id)
+        .into_boxed();
+    if let Some(owner) = owner {
+        query = query.filter(email.eq(owner));
+    }
+    let due_ids = query.load::<i32>(conn)?;
+
+    let mut calendars: HashMap<String, BudgetCalendar> = HashMap::new();
+    let mut total_posted = 0;
+    for due_id in due_ids {
+        match post_recurring(conn, due_id, &mut calendars) {
+            Ok(posted) => total_posted += posted,
+            Err(e) => eprintln!("Error posting recurring transaction {}: {:?}", due_id, e),
+        }
+    }
+    Ok(total_posted)
+}
+
+fn post_recurring(
+    conn: &mut PgConnection,
+    due_id: i32,
+    calendars: &mut HashMap<String, BudgetCalendar>,
+) -> QueryResult<usize> {
+    let posted = conn.transaction(|conn| {
+        // lock the template so two runs never post the same occurrence
+        let rec = recurring_transactions
+            .find(due_id)
+            .for_update()
+            .first::<Recurring>(conn)?;
+        if !calendars.contains_key(&rec.email) {
+            let calendar = BudgetCalendar::load(conn, &rec.email)?;
+            calendars.insert(rec.email.clone(), calendar);
+        }
+        let calendar = &calendars[&rec.email];
+        let today = calendar.today(Utc::now());
+
+        let mut new_transactions = vec![];
+        let (mut count, mut day) = (rec.posted_count, rec.next_date);
+        while !rec.paused
+            && day <= today
+            && rec.runs_on(day)
+            && new_transactions.len() < MAX_OCCURRENCES_PER_RUN
+        {
+            let Some(day_start) = calendar.local_midnight(day) else {
+                break;
+            };
+            new_transactions.push(NewTransaction {
+                email: rec.email.clone(),
+                category_id: rec.category_id,
+                amount: rec.amount,
+                notes: rec.notes.clone(),
+                account_id: rec.account_id,
+                transaction_date: day_start.with_timezone(&Utc),
+                currency: rec.currency.clone(),
+                payee_id: None,
+            });
+            count += 1;
+            // a schedule running past the calendar simply stops
+            day = rec.occurrence(count).unwrap_or(NaiveDate::MAX);
+        }
+        if new_transactions.is_empty() {
+            return Ok(None);
+        }
+        for chunk in new_transactions.chunks(INSERT_CHUNK_ROWS) {
+            diesel::insert_into(transactions).values(chunk).execute(conn)?;
+        }
+        diesel::update(recurring_transactions.find(due_id))
+            .set((posted_count.eq(count), next_date.eq(day)))
+            .execute(conn)?;
+        let last_date = new_transactions[new_transactions.len() - 1].transaction_date;
+        Ok::<_, diesel::result::Error>(Some((rec, new_transactions.len(), last_date)))
+    })?;
+
+    match posted {
+        Some((rec, posted_len, last_date)) => {
+            // only the latest period can still be news, earlier ones are history
+            if let Err(e) = record_budget_alerts(conn, &rec.email, rec.category_id, last_date) {
+                eprintln!("Error recording budget alerts: {:?}", e);
+            }
+            Ok(posted_len)
+        }
+        None => Ok(0),
+    }
+}
+
+// Startup and timer: post due occurrences right away, then every interval
+pub async fn post_due_recurring_on_timer(pool: DbPool) {
+    let interval_secs = env::var("RECURRING_POST_INTERVAL_SECS")
+        .ok()
+        .and_then(|secs| secs.trim().parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .unwrap_or(DEFAULT_POST_INTERVAL_SECS);
+    let mut ticker = tokio::time::interval(Duration::from_secs(interval_secs));
+    loop {
+        // the first tick completes immediately
+        ticker.tick().await;
+        let post_result = tokio::task::spawn_blocking({
+            let pool = pool.clone();
+            move || {
+                let mut conn = pool.get().expect("Failed to get database connection");
+                post_due_recurring(&mut conn, None)
+            }
+        })
+        .await;
+        match post_result {
+            Ok(Ok(0)) => {}
+            Ok(Ok(posted)) => println!("Posted {} recurring transactions", posted),
+            Ok(Err(e)) => eprintln!("Database error while posting recurring transactions: {:?}", e),
+            Err(e) => eprintln!("Blocking task failed while posting recurring transactions: {:?}", e),
+        }
+    }
+}
+
+// POST create recurring transaction
+pub async fn handle_recurring_create(
+    user: AuthUser,
+    client_rec: ClientRecurring,
+    pool: DbPool,
+) -> (Status, String) {
+    // Step 1: resolve category and account names the same way add_trans does
+    let (cat_status, curr_cat_id) = check_category_name(
+        user.email.clone(),
+        client_rec.category_name.clone(),
+        false,
+        pool.clone(),
+    )
+    .await;
+    if cat_status != Status::Ok {
+        return (
+            Status::BadRequest,
+            "No category found for the provided email".to_string(),
+        );
+    }
+
+    let (acc_status, curr_acc_id) = check_account_name(
+        user.email.clone(),
+        client_rec.account_name.clone(),
+        false,
+        pool.clone(),
+    )
+    .await;
+    if acc_status != Status::Ok {
+        return (
+            Status::BadRequest,
+            "No account found for the provided email".to_string(),
+        );
+    }
+
+    // Step 1.1: validate the schedule
+    let Some(valid_freq) = normalize_frequency(&client_rec.frequency) else {
+        return (Status::BadRequest, INVALID_FREQUENCY.to_string());
+    };
+    let every = client_rec.interval_count.unwrap_or(1);
+    if every < 1 {
+        return (Status::BadRequest, INVALID_INTERVAL.to_string());
+    }
+    let Some(first_day) = parse_day(&client_rec.start_date) else {
+        return (Status::BadRequest, "Invalid start date".to_string());
+    };
+    let last_day = match client_rec.end_date.as_deref() {
+        Some(day_str) if !day_str.trim().is_empty() => match parse_day(day_str) {
+            Some(parsed_day) => Some(parsed_day),
+            None => return (Status::BadRequest, "Invalid end date".to_string()),
+        },
+        _ => None,
+    };
+    if last_day.is_some_and(|last_day| last_day < first_day) {
+        return (Status::BadRequest, INVALID_END_DATE.to_string());
+    }
+
+    // Step 1.2: a currency other than the account's, if the client sent one
+    let rec_currency = match client_rec.currency.as_deref() {
+        Some(code) if !code.trim().is_empty() => match normalize_currency(code) {
+            Some(valid_code) => Some(valid_code),
+            None => return (Status::BadRequest, "Invalid currency code".to_string()),
+        },
+        _ => None,
+    };
+
+    let new_rec = NewRecurring {
+        email: user.email.clone(),
+        category_id: curr_cat_id,
+        account_id: curr_acc_id,
+        amount: client_rec.amount,
+        notes: client_rec.notes,
+        currency: rec_currency,
+        frequency: valid_freq,
+        interval_count: every,
+        start_date: first_day,
+        end_date: last_day,
+        next_date: first_day,
+    };
+
+    // Step 2: add it, then post the occurrences already due (a start date in the past)
+    let result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        move || {
+            let mut conn = pool.get().expect("Failed to get database connection");
+            let new_id = diesel::insert_into(recurring_transactions)
+                .values(&new_rec)
+                .returning(recurring_id)
+                .get_result::<i32>(&mut conn)?;
+            if let Err(e) = post_due_recurring(&mut conn, Some(&new_rec.email)) {
+                eprintln!("Error posting recurring transactions: {:?}", e);
+            }
+            Ok::<_, diesel::result::Error>(new_id)
+        }
+    })
+    .await;
+
+    match result {
+        Ok(Ok(new_id)) => (Status::Created, new_id.to_string()),
+        Ok(Err(e)) => {
+            eprintln!("Database error during recurring transaction insertion: {:?}", e);
+            (Status::InternalServerError, "Database error".to_string())
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed during recurring transaction insertion: {:?}", e);
+            (
+                Status::InternalServerError,
+                "Internal server error".to_string(),
+            )
+        }
+    }
+}
+
+// GET recurring transactions of the caller, next due first
+pub async fn handle_recurring_summary(
+    user: AuthUser,
+    pool: DbPool,
+) -> (Status, Json<Vec<RecurringSummary>>) {
+    let summary_result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        let email_to_search = user.email.clone();
+        move || {
+            let mut conn = pool.get().expect("Failed to get database connection");
+            recurring_transactions
+                .inner_join(categories)
+                .inner_join(accounts)
+                .filter(email.eq(email_to_search))
+                .select((
+                    recurring_id,
+                    nickname,
+                    account_name,
+                    amount,
+                    notes,
+                    currency,
+                    frequency,
+                    interval_count,
+                    start_date,
+                    end_date,
+                    next_date,
+                    paused,
+                ))
+                .order_by((next_date, recurring_id))
+                .load::<RecurringSummary>(&mut conn)
+        }
+    })
+    .await;
+
+    match summary_result {
+        Ok(Ok(summary)) => (Status::Ok, Json(summary)),
+        Ok(Err(e)) => {
+            eprintln!("Database error during recurring transaction retrieval: {:?}", e);
+            (Status::InternalServerError, Json(vec![]))
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed during recurring transaction retrieval: {:?}", e);
+            (Status::InternalServerError, Json(vec![]))
+        }
+    }
+}
+
+// PATCH update recurring transaction, including pausing and resuming it
+pub async fn handle_recurring_update(
+    user: AuthUser,
+    rec_id: String,
+    changes: ClientRecurringUpdate,
+    pool: DbPool,
+) -> (Status, &'static str) {
+    // Step 1: make sure the recurring transaction belongs to the caller
+    let owned_rec = match check_recurring_owner(user.email.clone(), rec_id, pool.clone()).await {
+        Ok(owned_rec) => owned_rec,
+        Err(err) => return err,
+    };
+
+    // Step 2: resolve names and validate the new schedule
+    let new_cat_id = match changes.category_name {
+        Some(cat_name) => {
+            let (cat_status, curr_cat_id) =
+                check_category_name(user.email.clone(), cat_name, false, pool.clone()).await;
+            if cat_status != Status::Ok {
+                return (Status::BadRequest, "No category found for the provided email");
+            }
+            Some(curr_cat_id)
+        }
+        None => None,
+    };
+
+    let new_acc_id = match changes.account_name {
+        Some(acc_name) => {
+            let (acc_status, curr_acc_id) =
+                check_account_name(user.email.clone(), acc_name, false, pool.clone()).await;
+            if acc_status != Status::Ok {
+                return (Status::BadRequest, "No account found for the provided email");
+            }
+            Some(curr_acc_id)
+        }
+        None => None,
+    };
+
+    let new_freq = match changes.frequency {
+        Some(freq) => match normalize_frequency(&freq) {
+            Some(valid_freq) => Some(valid_freq),
+            None => return (Status::BadRequest, INVALID_FREQUENCY),
+        },
+        None => None,
+    };
+    if changes.interval_count.is_some_and(|every| every < 1) {
+        return (Status::BadRequest, INVALID_INTERVAL);
+    }
+    let new_start = match changes.start_date {
+        Some(day_str) => match parse_day(&day_str) {
+            Some(parsed_day) => Some(parsed_day),
+            None => return (Status::BadRequest, "Invalid start date"),
+        },
+        None => None,
+    };
+    let new_end = match changes.end_date {
+        Some(day_str) if day_str.trim().is_empty() => Some(None),
+        Some(day_str) => match parse_day(&day_str) {
+            Some(parsed_day) => Some(Some(parsed_day)),
+            None => return (Status::BadRequest, "Invalid end date"),
+        },
+        None => None,
+    };
+    let new_currency = match changes.currency {
+        Some(code) => match normalize_currency(&code) {
+            Some(valid_code) => Some(valid_code),
+            None => return (Status::BadRequest, "Invalid currency code"),
+        },
+        None => None,
+    };
+
+    let mut db_changes = RecurringChanges {
+        category_id: new_cat_id,
+        account_id: new_acc_id,
+        amount: changes.amount,
+        notes: changes.notes,
+        currency: new_currency,
+        frequency: new_freq,
+        interval_count: changes.interval_count,
+        start_date: new_start,
+        end_date: new_end,
+        paused: changes.paused,
+        ..Default::default()
+    };
+    if db_changes.is_empty() {
+        return (Status::BadRequest, "No fields to update");
+    }
+
+    // Step 2.1: a new schedule starts over from its start date, or from the next occurrence
+    // of the old one. Its occurrences up to the last day the old one got to are skipped
+    // in Step 3, so days already posted are never posted again
+    let mut last_posted = None;
+    if db_changes.frequency.is_some()
+        || db_changes.interval_count.is_some()
+        || db_changes.start_date.is_some()
+    {
+        let restart_day = db_changes.start_date.unwrap_or(owned_rec.next_date);
+        db_changes.start_date = Some(restart_day);
+        db_changes.posted_count = Some(0);
+        db_changes.next_date = Some(restart_day);
+        if owned_rec.posted_count > 0 {
+            last_posted = owned_rec.occurrence(owned_rec.posted_count - 1);
+        }
+    }
+    let first_day = db_changes.start_date.unwrap_or(owned_rec.start_date);
+    let last_day = db_changes.end_date.unwrap_or(owned_rec.end_date);
+    if last_day.is_some_and(|last_day| last_day < first_day) {
+        return (Status::BadRequest, INVALID_END_DATE);
+    }
+
+    // Step 3: apply the changes. A new schedule skips the days already posted, resuming
+    // skips the occurrences that came due while paused, then whatever is due today is posted
+    let resuming = owned_rec.paused && changes.paused == Some(false);
+    let update_result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        let rec_id_int = owned_rec.recurring_id;
+        let email_to_check = user.email.clone();
+        move || {
+            let mut conn = pool.get().expect("Failed to get database connection");
+            conn.transaction(|conn| {
+                let rec = diesel::update(
+                    recurring_transactions
+                        .filter(recurring_id.eq(rec_id_int))
+                        .filter(email.eq(&email_to_check)),
+                )
+                .set(&db_changes)
+                .get_result::<Recurring>(conn)?;
+                let (mut count, mut day) = (rec.posted_count, rec.next_date);
+                if let Some(last_day) = last_posted {
+                    while day <= last_day {
+                        count += 1;
+                        day = rec.occurrence(count).unwrap_or(NaiveDate::MAX);
+                    }
+                }
+                if resuming {
+                    let today = BudgetCalendar::load(conn, &email_to_check)?.today(Utc::now());
+                    while day < today {
+                        count += 1;
+                        day = rec.occurrence(count).unwrap_or(NaiveDate::MAX);
+                    }
+                }
+                if count != rec.posted_count {
+                    diesel::update(recurring_transactions.find(rec_id_int))
+                        .set((posted_count.eq(count), next_date.eq(day)))
+                        .execute(conn)?;
+                }
+                Ok::<_, diesel::result::Error>(())
+            })?;
+            if let Err(e) = post_due_recurring(&mut conn, Some(&email_to_check)) {
+                eprintln!("Error posting recurring transactions: {:?}", e);
+            }
+            Ok::<_, diesel::result::Error>(())
+        }
+    })
+    .await;
+
+    match update_result {
+        Ok(Ok(())) => (Status::Ok, "Recurring transaction successfully updated"),
+        Ok(Err(e)) => {
+            eprintln!("Error during recurring transaction update: {:?}", e);
+            (Status::InternalServerError, "Database error during update")
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed during recurring transaction update: {:?}", e);
+            (Status::InternalServerError, "Internal server error")
+        }
+    }
+}
+
+// DELETE delete recurring transaction, the transactions it already posted are kept
+pub async fn handle_delete_recurring(
+    user: AuthUser,
+    rec_id: String,
+    pool: DbPool,
+) -> (Status, &'static str) {
+    // Step 1: make sure the recurring transaction belongs to the caller
+    let owned_rec = match check_recurring_owner(user.email.clone(), rec_id, pool.clone()).await {
+        Ok(owned_rec) => owned_rec,
+        Err(err) => return err,
+    };
+
+    // Step 2: delete it, still scoped to the caller
+    let deletion_result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        let rec_id_int = owned_rec.recurring_id;
+        let email_to_check = user.email.clone();
+        move || {
+            let mut conn = pool.get().expect("Failed to get database connection");
+            diesel::delete(
+                recurring_transactions
+                    .filter(recurring_id.eq(rec_id_int))
+                    .filter(email.eq(email_to_check)),
+            )
+            .execute(&mut conn)
+        }
+    })
+    .await;
+
+    match deletion_result {
+        Ok(Ok(rows_deleted)) if rows_deleted > 0 => {
+            (Status::Ok, "Recurring transaction successfully deleted")
+        }
+        Ok(Ok(_)) => (
+            Status::InternalServerError,
+            "Failed to delete the recurring transaction",
+        ),
+        Ok(Err(e)) => {
+            eprintln!("Error during recurring transaction deletion: {:?}", e);
+            (
+                Status::InternalServerError,
+                "Database error during deletion",
+            )
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed during recurring transaction deletion: {:?}", e);
+            (Status::InternalServerError, "Internal server error")
+        }
+    }
+}
+
+// Ids owned by other users are reported exactly like ids that do not exist
+pub async fn check_recurring_owner(
+    email_str: String,
+    rec_id: String,
+    pool: DbPool,
+) -> Result<Recurring, (Status, &'static str)> {
+    let rec_id_int = match rec_id.trim().parse::<i32>() {
+        Ok(rec_id_int) => rec_id_int,
+        Err(_) => return Err((Status::BadRequest, "Invalid recurring transaction ID")),
+    };
+
+    let rec_exists = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        move || {
+            let mut conn = pool.get().expect("Failed to get database connection");
+            recurring_transactions
+                .filter(recurring_id.eq(rec_id_int))
+                .filter(email.eq(email_str))
+                .first::<Recurring>(&mut conn)
+                .optional()
+        }
+    })
+    .await;
+
+    match rec_exists {
+        Ok(Ok(Some(owned_rec))) => Ok(owned_rec),
+        Ok(Ok(None)) => Err((
+            Status::NotFound,
+            "No recurring transaction found for the provided ID",
+        )),
+        Ok(Err(e)) => {
+            eprintln!("Error checking recurring transaction ownership: {:?}", e);
+            Err((Status::InternalServerError, "Database error"))
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed during recurring transaction check: {:?}", e);
+            Err((Status::InternalServerError, "Internal server error"))
+        }
+    }
+}
@@ -1,268 +1,220 @@
+use crate::currency::{normalize_currency, RateTable, DEFAULT_CURRENCY};
 use crate::db::DbPool;
-use crate::models::account::Account;
-use crate::models::category::Category;
-use crate::models::user::User;
+use crate::guards::AuthUser;
+use crate::handlers::account_handler::{account_balances, balance_on, load_account_flows};
+use crate::handlers::recurring_handler::parse_day;
+use crate::period::{BudgetCalendar, Period, PeriodSelector, UNKNOWN_PERIOD};
 use crate::schema::accounts::dsl::*; // For accounts table
 use crate::schema::categories::dsl::*; // For categories table
 use crate::schema::transactions::dsl::*;
-use crate::schema::users::dsl::{email as user_email, users}; // For users table
-use diesel::dsl::sum;
+use chrono::{DateTime, Datelike, Months, NaiveDate, Utc};
 use diesel::prelude::*;
+use rust_decimal::{Decimal, RoundingStrategy};
 use rocket::http::Status;
 use rocket::serde::json::Json;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+
+// A net worth series longer than this needs a later start
+const MAX_NETWORTH_POINTS: usize = 600;
+// Payees listed by /report_payees unless the client asks for another count
+const DEFAULT_TOP_PAYEES: usize = 10;
 
 // Some helper functions
-pub async fn check_email_valid(email_str: String, pool: DbPool) -> (Status, String) {
-    let user_exists = tokio::task::spawn_blocking({
-        let pool = pool.clone();
-        let email_to_check = email_str.clone();
-        move || {
-            let mut conn = pool.get().expect("Failed to get database connection");
-            users
-                .filter(user_email.eq(email_to_check))
-                .first::<User>(&mut conn)
-                .optional()
-        }
-    })
-    .await;
 
-    match user_exists {
-        Ok(Ok(None)) => {
-            // No user found for this email
-            return (
-                Status::BadRequest,
-                "No user found for the provided email".to_string(),
-            );
-        }
-        Ok(Ok(Some(user))) => {
-            // User found, proceed to category name existence check
-            return (Status::Ok, user.email);
-        }
-        Ok(Err(e)) => {
-            eprintln!("Error checking user existence: {:?}", e);
-            return (Status::InternalServerError, "Database error".to_string());
-        }
-        Err(e) => {
-            eprintln!("Blocking task failed during user check: {:?}", e);
-            return (
-                Status::InternalServerError,
-                "Internal server error".to_string(),
-            );
-        }
+// Currency asked for by the client, or the default one
+fn resolve_base_currency(base_currency: Option<String>) -> Option<String> {
+    match base_currency {
+        Some(code) => normalize_currency(&code),
+        None => Some(DEFAULT_CURRENCY.to_string()),
     }
 }
 
-pub async fn check_category_name(email_str: String, cat_id: i32, pool: DbPool) -> (Status, String) {
-    // get category id from category name
-    let category_exists = tokio::task::spawn_blocking({
-        let pool = pool.clone();
-        let cat_to_check = cat_id.clone();
-        let email_to_check = email_str.clone();
-        move || {
-            let mut conn = pool.get().expect("Failed to get database connection");
-            categories
-                .filter(crate::schema::categories::dsl::email.eq(email_to_check))
-                .filter(crate::schema::categories::dsl::category_id.eq(cat_to_check))
-                .first::<Category>(&mut conn)
-                .optional()
-        }
-    })
-    .await;
+// How often each budget frequency repeats in a year, used to scale a budget to another period
+fn periods_per_year(freq: &str) -> Option<i64> {
+    match freq {
+        "daily" => Some(365),
+        "weekly" => Some(52),
+        "monthly" => Some(12),
+        "yearly" => Some(1),
+        _ => None,
+    }
+}
 
-    let _ = match category_exists {
-        Ok(Ok(None)) => {
-            // No category found for this name
-            return (Status::BadRequest, "".to_string());
-        }
-        Ok(Ok(Some(match_category))) => {
-            // User found, proceed to category name existence check
-            return (Status::Ok, match_category.nickname);
-        }
-        Ok(Err(e)) => {
-            eprintln!("Error checking category existence: {:?}", e);
-            return (Status::InternalServerError, "".to_string());
-        }
-        Err(e) => {
-            eprintln!("Blocking task failed during user check: {:?}", e);
-            return (Status::InternalServerError, "".to_string());
+// A category budget stretched or shrunk to a period picked by the client
+fn scale_budget(cat_budget: Decimal, cat_freq: &str, selector: PeriodSelector) -> Decimal {
+    let Some(cat_per_year) = periods_per_year(cat_freq) else {
+        return cat_budget;
+    };
+    let scaled = match selector {
+        PeriodSelector::Range(first, last) => {
+            let days = (last - first).num_days() + 1;
+            cat_budget * Decimal::from(cat_per_year * days) / Decimal::from(365)
         }
+        _ => match selector.per_year() {
+            Some(selected_per_year) => {
+                cat_budget * Decimal::from(cat_per_year) / Decimal::from(selected_per_year)
+            }
+            None => cat_budget,
+        },
     };
+    scaled.round_dp_with_strategy(2, RoundingStrategy::MidpointAwayFromZero)
 }
 
-pub async fn check_account_name(email_str: String, acc_id: i32, pool: DbPool) -> (Status, String) {
-    let account_exists = tokio::task::spawn_blocking({
-        let pool = pool.clone();
-        let email_to_check = email_str.clone();
-        let acc_to_check = acc_id.clone();
-        move || {
-            let mut conn = pool.get().expect("Failed to get database connection");
-            accounts
-                .filter(crate::schema::accounts::dsl::email.eq(email_to_check))
-                .filter(crate::schema::accounts::dsl::account_id.eq(acc_to_check))
-                .first::<Account>(&mut conn)
-                .optional()
-        }
-    })
-    .await;
+// What the previous period leaves to the current one under a rollover policy
+// the previous period is held against the plain budget, carries do not chain
+pub fn carry_over(policy: &str, cat_budget: Decimal, previous_total: Decimal) -> Decimal {
+    let unused = cat_budget - previous_total;
+    match policy {
+        "carry_unused" => unused.max(Decimal::ZERO),
+        "carry_overspend" => unused.min(Decimal::ZERO),
+        _ => Decimal::ZERO,
+    }
+}
 
-    let _ = match account_exists {
-        Ok(Ok(None)) => {
-            return (Status::BadRequest, "".to_string());
-        }
-        Ok(Ok(Some(match_acc))) => {
-            return (Status::Ok, match_acc.account_name);
-        }
-        Ok(Err(e)) => {
-            eprintln!("Error checking account existence: {:?}", e);
-            return (Status::InternalServerError, "".to_string());
-        }
-        Err(e) => {
-            eprintln!("Blocking task failed during user check: {:?}", e);
-            return (Status::InternalServerError, "".to_string());
-        }
-    };
+// helper struct for overview entries
+#[derive(Debug, Queryable)]
+pub struct OverviewEntry {
+    pub nickname: String,
+    pub amount: Decimal,
+    pub currency: Option<String>,
+    pub account_currency: String,
+    pub transaction_date: DateTime<Utc>,
 }
 
-// GET /report_overview?email=<>
+// GET /report_overview?<base_currency>
 pub async fn handle_report_overview(
-    email_str: String,
+    user: AuthUser,
+    base_currency: Option<String>,
     pool: DbPool,
 ) -> (Status, Json<Vec<String>>) {
-    // Step 1: validate email
-    let (email_status, _user_email) = check_email_valid(email_str.clone(), pool.clone()).await;
-
-    if email_status != Status::Ok {
+    let email_str = user.email;
+    let Some(base) = resolve_base_currency(base_currency) else {
         return (Status::BadRequest, Json(vec![]));
-    }
-
-    // Declare summary vector
-    let mut summary: Vec<String> = vec!["Category Summary:".to_string()];
+    };
 
-    // Step 2: get summary of category
-    let category_result = tokio::task::spawn_blocking({
+    // Step 1: get every transaction with its category and account, the balance of every
+    // account, and the rates into base
+    let overview_result = tokio::task::spawn_blocking({
         let pool = pool.clone();
         let email_to_search = email_str.clone();
         move || {
             let mut conn = pool.get().expect("Failed to get database connection");
-            // order matters: https://stackoverflow.com/questions/72670161/how-do-you-use-rust-diesel-to-do-a-group-by-query
-            // nullable types: https://docs.diesel.rs/1.4.x/diesel/sql_types/struct.Nullable.html
-            transactions
-                .filter(crate::schema::transactions::dsl::email.eq(email_to_search))
-                .group_by(crate::schema::transactions::dsl::category_id)
+            let entries = transactions
+                .inner_join(categories)
+                .inner_join(accounts)
+                .filter(crate::schema::transactions::dsl::email.eq(&email_to_search))
                 .select((
-                    crate::schema::transactions::dsl::category_id,
-                    sum(crate::schema::transactions::dsl::amount),
+                    crate::schema::categories::dsl::nickname,
+                    crate::schema::transactions::dsl::amount,
+                    crate::schema::transactions::dsl::currency,
+                    crate::schema::accounts::dsl::currency,
+                    crate::schema::transactions::dsl::transaction_date,
                 ))
-                .load::<(i32, Option<f64>)>(&mut conn)
+                .load::<OverviewEntry>(&mut conn)?;
+            // each transaction converts on its own day, the balances on today
+            let rate_days = entries
+                .iter()
+                .map(|entry| entry.transaction_date.date_naive())
+                .chain([Utc::now().date_naive()]);
+            let rates = RateTable::load(&mut conn, &base, rate_days)?;
+            let balances = account_balances(&mut conn, &email_to_search)?;
+            Ok::<_, diesel::result::Error>((rates, entries, balances))
         }
     })
     .await;
 
-    match category_result {
-        Ok(Ok(trans_list)) => {
-            // Successfully retrieved accounts
-            for &(cat_id, cat_sum) in trans_list.iter() {
-                if let Some(valid_sum) = cat_sum {
-                    // convert category ID to category name
-                    let (cat_status, cat_name) =
-                        check_category_name(email_str.clone(), cat_id.clone(), pool.clone()).await;
-
-                    if cat_status != Status::Ok {
-                        return (Status::BadRequest, Json(vec![]));
-                    }
-                    let cat_line = format!("{} : {}", cat_name, valid_sum);
-                    summary.push(cat_line);
-                }
-            }
-        }
+    let (rates, entries, balances) = match overview_result {
+        Ok(Ok(loaded)) => loaded,
         Ok(Err(e)) => {
-            eprintln!("Database error during category summary retrieval: {:?}", e);
+            eprintln!("Database error during report overview retrieval: {:?}", e);
             return (Status::InternalServerError, Json(vec![]));
         }
         Err(e) => {
-            eprintln!(
-                "Blocking task failed during category summary retrieval: {:?}",
-                e
-            );
+            eprintln!("Blocking task failed during report overview retrieval: {:?}", e);
             return (Status::InternalServerError, Json(vec![]));
         }
+    };
+
+    // Step 2: sum per category in the base currency
+    let mut category_totals: BTreeMap<String, Decimal> = BTreeMap::new();
+    for entry in entries.into_iter() {
+        let entry_currency = entry.currency.as_deref().unwrap_or(&entry.account_currency);
+        let converted = match rates.convert(
+            entry.amount,
+            entry_currency,
+            entry.transaction_date.date_naive(),
+        ) {
+            Ok(converted) => converted,
+            Err(e) => return (Status::UnprocessableEntity, Json(vec![e])),
+        };
+        *category_totals.entry(entry.nickname).or_default() += converted;
     }
+    let balances = match balances {
+        Ok(balances) => balances,
+        Err(e) => return (Status::UnprocessableEntity, Json(vec![e])),
+    };
 
+    // Declare summary vector
+    let mut summary: Vec<String> = vec!["Category Summary:".to_string()];
+    for (cat_name, cat_sum) in category_totals.iter() {
+        summary.push(format!("{} : {} {}", cat_name, cat_sum, rates.base()));
+    }
+    // Step 3: list the current balance of each active account at today's rate,
+    // liabilities as negative amounts
     summary.push("Account Summary:".to_string());
-
-    // Add account summary section
-    let account_result = tokio::task::spawn_blocking({
-        let pool = pool.clone();
-        let email_to_search = email_str.clone();
-        move || {
-            let mut conn = pool.get().expect("Failed to get database connection");
-            // order matters: https://stackoverflow.com/questions/72670161/how-do-you-use-rust-diesel-to-do-a-group-by-query
-            // nullable types: https://docs.diesel.rs/1.4.x/diesel/sql_types/struct.Nullable.html
-            transactions
-                .filter(crate::schema::transactions::dsl::email.eq(email_to_search))
-                .group_by((crate::schema::transactions::dsl::account_id,))
-                .select((
-                    crate::schema::transactions::dsl::account_id,
-                    sum(crate::schema::transactions::dsl::amount),
-                ))
-                .load::<(i32, Option<f64>)>(&mut conn)
-        }
-    })
-    .await;
-
-    match account_result {
-        Ok(Ok(trans_list)) => {
-            // Successfully retrieved accounts
-            for &(acc_id, acc_sum) in trans_list.iter() {
-                if let Some(valid_sum) = acc_sum {
-                    // convert account ID to account name
-                    let (acc_status, acc_name) =
-                        check_account_name(email_str.clone(), acc_id.clone(), pool.clone()).await;
-
-                    if acc_status != Status::Ok {
-                        return (Status::BadRequest, Json(vec![]));
-                    }
-                    let acc_line = format!("{}: {}", acc_name, valid_sum);
-                    summary.push(acc_line);
-                }
-            }
-            return (Status::Ok, Json(summary));
-        }
-        Ok(Err(e)) => {
-            eprintln!("Database error during category summary retrieval: {:?}", e);
-            return (Status::InternalServerError, Json(vec![]));
-        }
-        Err(e) => {
-            eprintln!(
-                "Blocking task failed during category summary retrieval: {:?}",
-                e
-            );
-            return (Status::InternalServerError, Json(vec![]));
-        }
+    let today = Utc::now().date_naive();
+    for bal in balances.iter().filter(|bal| !bal.archived) {
+        let converted = match rates.convert(bal.net_value, &bal.currency, today) {
+            Ok(converted) => converted,
+            Err(e) => return (Status::UnprocessableEntity, Json(vec![e])),
+        };
+        let kind = if bal.liability { " (liability)" } else { "" };
+        summary.push(format!("{}{}: {} {}", bal.account_name, kind, converted, rates.base()));
     }
+    (Status::Ok, Json(summary))
 }
 
 // helper struct for summary entires
 #[derive(Debug, Queryable, Serialize, Deserialize, Clone)]
 pub struct SummaryEntry {
+    pub category_id: i32,
     pub nickname: String,
-    pub budget: f64,
+    pub category_type: String,
+    pub rollover: String,
+    pub budget: Decimal,
     pub budget_freq: String,
-    pub transaction_date: String,
-    pub amount: f64,
+    pub transaction_date: DateTime<Utc>,
+    pub amount: Decimal,
     pub notes: Option<String>,
     pub transaction_id: i32,
+    pub currency: Option<String>,
+    pub account_currency: String,
 }
 
-#[derive(Debug, Queryable, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CategorySummary {
     pub nickname: String,
-    pub budget: f64,
+    pub category_type: String,
+    // the spending limit of an expense category, the target of an income category
+    pub budget: Decimal,
     pub budget_freq: String,
+    // only expense categories go over budget
     pub overbudget: bool,
-    pub total: f64,
+    // only income categories reach a target
+    pub target_reached: bool,
+    // the budget period reported, missing for frequencies without one
+    pub bounds: Option<Period>,
+    // none, carry_unused or carry_overspend
+    pub rollover: String,
+    // left over (positive) or overspent (negative) in the previous period, zero without rollover
+    pub carried: Decimal,
+    // budget + carried, what the total is held against
+    pub effective_budget: Decimal,
+    pub total: Decimal,
+    // currency of `total`, `budget`, `carried` and `effective_budget`. Budgets are stored in
+    // DEFAULT_CURRENCY and converted at the rate of the period's first day.
+    pub base_currency: String,
     // a vector of transaction IDs corresponding to the trans summary below
     // added for easier deletion of transaction through report page
     pub transaction_idz: Vec<i32>,
@@ -270,52 +222,143 @@ pub struct CategorySummary {
     pub cat_trans: Vec<String>,
 }
 
-// GET /report_details?email=<>
+// GET /report_details?<base_currency>&<period>&<from>&<to>
 pub async fn handle_report_details(
-    email_str: String,
+    user: AuthUser,
+    base_currency: Option<String>,
+    period: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
     pool: DbPool,
-) -> (Status, Json<Vec<CategorySummary>>) {
-    // Step 1: validate email
-    let (email_status, _user_email) = check_email_valid(email_str.clone(), pool.clone()).await;
-
-    if email_status != Status::Ok {
-        return (Status::BadRequest, Json(vec![]));
-    }
+) -> Result<Json<Vec<CategorySummary>>, (Status, String)> {
+    let email_str = user.email;
+    let Some(base) = resolve_base_currency(base_currency) else {
+        return Err((Status::BadRequest, "Invalid currency code".to_string()));
+    };
+    // no selector reports every category over its own current period,
+    // a selector reports every category over the same past (or future) period
+    let selector = PeriodSelector::parse(period.as_deref(), from.as_deref(), to.as_deref())
+        .map_err(|e| (Status::BadRequest, e.to_string()))?;
 
-    // Step 2: get summary of category
+    // Step 1: get summary of category
     let category_result = tokio::task::spawn_blocking({
         let pool = pool.clone();
         let email_to_search = email_str.clone();
         move || {
             let mut conn = pool.get().expect("Failed to get database connection");
+            let calendar = BudgetCalendar::load(&mut conn, &email_to_search)?;
+            let now = Utc::now();
+            let selected = match selector {
+                Some(selector) => match calendar.resolve(selector) {
+                    Some(bounds) => Some(bounds),
+                    None => return Ok(Err((Status::BadRequest, UNKNOWN_PERIOD.to_string()))),
+                },
+                None => None,
+            };
             // order matters: https://stackoverflow.com/questions/72670161/how-do-you-use-rust-diesel-to-do-a-group-by-query
             // nullable types: https://docs.diesel.rs/1.4.x/diesel/sql_types/struct.Nullable.html
-            transactions
+            let query = transactions
                 .inner_join(categories)
+                .inner_join(accounts)
                 .filter(crate::schema::transactions::dsl::email.eq(email_to_search))
                 .select((
+                    crate::schema::categories::dsl::category_id,
                     crate::schema::categories::dsl::nickname,
+                    crate::schema::categories::dsl::category_type,
+                    crate::schema::categories::dsl::rollover,
                     crate::schema::categories::dsl::budget,
                     crate::schema::categories::dsl::budget_freq,
                     crate::schema::transactions::dsl::transaction_date,
                     crate::schema::transactions::dsl::amount,
                     crate::schema::transactions::dsl::notes,
                     crate::schema::transactions::dsl::trans_id,
+                    crate::schema::transactions::dsl::currency,
+                    crate::schema::accounts::dsl::currency,
                 ))
                 .order_by((
                     crate::schema::transactions::dsl::category_id,
                     crate::schema::transactions::dsl::transaction_date,
                 ))
-                .load::<SummaryEntry>(&mut conn)
+                .into_boxed();
+            let entries = match selected {
+                Some(bounds) => query
+                    .filter(transaction_date.ge(bounds.start_utc()))
+                    .filter(transaction_date.lt(bounds.end_utc())),
+                None => {
+                    // only keep transactions inside their category's current budget period
+                    // frequencies we do not know have no period and keep everything
+                    let period_of = |freq: &str| {
+                        calendar
+                            .period_containing(freq, now)
+                            .map(|period| (period.start_utc(), period.end_utc()))
+                            .unwrap_or((now, now))
+                    };
+                    let (day_start, day_end) = period_of("daily");
+                    let (week_start, week_end) = period_of("weekly");
+                    let (month_start, month_end) = period_of("monthly");
+                    let (year_start, year_end) = period_of("yearly");
+                    let in_budget_window = budget_freq
+                        .eq("daily")
+                        .and(transaction_date.ge(day_start))
+                        .and(transaction_date.lt(day_end))
+                        .or(budget_freq
+                            .eq("weekly")
+                            .and(transaction_date.ge(week_start))
+                            .and(transaction_date.lt(week_end)))
+                        .or(budget_freq
+                            .eq("monthly")
+                            .and(transaction_date.ge(month_start))
+                            .and(transaction_date.lt(month_end)))
+                        .or(budget_freq
+                            .eq("yearly")
+                            .and(transaction_date.ge(year_start))
+                            .and(transaction_date.lt(year_end)))
+                        .or(budget_freq.ne_all(["daily", "weekly", "monthly", "yearly"]));
+                    query.filter(in_budget_window)
+                }
+            }
+            .load::<SummaryEntry>(&mut conn)?;
+            // budgets convert at the start of their period, and the rollover also reads
+            // the period before
+            let budget_periods: Vec<Period> = match selected {
+                Some(bounds) => [
+                    Some(bounds),
+                    selector
+                        .and_then(|selector| selector.previous())
+                        .and_then(|previous| calendar.resolve(previous)),
+                ]
+                .into_iter()
+                .flatten()
+                .collect(),
+                None => ["daily", "weekly", "monthly", "yearly"]
+                    .iter()
+                    .filter_map(|freq| {
+                        let current = calendar.period_containing(freq, now)?;
+                        Some([Some(current), calendar.period_before(freq, &current)])
+                    })
+                    .flatten()
+                    .flatten()
+                    .collect(),
+            };
+            let rate_days = entries
+                .iter()
+                .map(|entry| entry.transaction_date.date_naive())
+                .chain([now.date_naive()])
+                .chain(budget_periods.iter().flat_map(Period::days));
+            let rates = RateTable::load(&mut conn, &base, rate_days)?;
+            Ok::<_, diesel::result::Error>(Ok((rates, entries, calendar, now, selected)))
         }
     })
     .await;
 
     match category_result {
-        Ok(Ok(trans_list)) => {
+        Ok(Ok(Ok((rates, trans_list, calendar, now, selected)))) => {
             // Declare summary vector
             let mut summary: Vec<CategorySummary> = vec![];
-            let mut cat_summary_dict: HashMap<String, CategorySummary> = HashMap::new();
+            let mut cat_summary_dict: BTreeMap<String, CategorySummary> = BTreeMap::new();
+            let mut cat_ids: HashMap<String, i32> = HashMap::new();
+            // budgets as stored, in DEFAULT_CURRENCY, for the previous period's carry
+            let mut stored_budgets: HashMap<String, Decimal> = HashMap::new();
             // Successfully retrieved category transactions
             for sum_entry in trans_list.into_iter() {
                 let mut curr_cat_sum: CategorySummary =
@@ -323,73 +366,855 @@ pub async fn handle_report_details(
                         true => match cat_summary_dict.get(&sum_entry.nickname) {
                             Some(existing_cat) => existing_cat.clone(),
                             None => {
-                                return (Status::InternalServerError, Json(vec![]));
+                                return Err((
+                                    Status::InternalServerError,
+                                    "Internal server error".to_string(),
+                                ));
                             }
                         },
                         false => {
+                            let (cat_budget, cat_bounds) = match (selector, selected) {
+                                (Some(selector), Some(bounds)) => (
+                                    scale_budget(sum_entry.budget, &sum_entry.budget_freq, selector),
+                                    Some(bounds),
+                                ),
+                                _ => (
+                                    sum_entry.budget,
+                                    calendar.period_containing(&sum_entry.budget_freq, now),
+                                ),
+                            };
+                            let budget_day = cat_bounds
+                                .map(|bounds| bounds.start.date_naive())
+                                .unwrap_or(now.date_naive());
+                            let base_budget =
+                                match rates.convert(cat_budget, DEFAULT_CURRENCY, budget_day) {
+                                    Ok(converted) => converted,
+                                    Err(e) => {
+                                        return Err((Status::UnprocessableEntity, e));
+                                    }
+                                };
                             let new_cat = CategorySummary {
                                 nickname: sum_entry.nickname.clone(),
-                                budget: sum_entry.budget.clone(),
+                                category_type: sum_entry.category_type.clone(),
+                                budget: base_budget,
                                 budget_freq: sum_entry.budget_freq.clone(),
                                 overbudget: false,
-                                total: 0.0,
+                                target_reached: false,
+                                bounds: cat_bounds,
+                                rollover: sum_entry.rollover.clone(),
+                                carried: Decimal::ZERO,
+                                effective_budget: base_budget,
+                                total: Decimal::ZERO,
+                                base_currency: rates.base().to_string(),
                                 transaction_idz: vec![],
                                 cat_trans: vec![],
                             };
                             cat_summary_dict.insert(sum_entry.nickname.clone(), new_cat.clone());
+                            cat_ids.insert(sum_entry.nickname.clone(), sum_entry.category_id);
+                            stored_budgets.insert(sum_entry.nickname.clone(), cat_budget);
                             new_cat
                         }
                     };
-                // check if entry is within the budget frame:
-                let budget_freq_str = curr_cat_sum.budget_freq.as_str();
-                let budget_timeframe = match budget_freq_str {
-                    "weekly" => 7.0 * 24.0 * 60.0,
-                    // add frequency
-                    "daily" => 1.0 * 24.0 * 60.0,
-                    "monthly" => 30.0 * 24.0 * 60.0,
-                    "yearly" => 365.0 * 24.0 * 60.0,
-                    _ => f64::INFINITY,
-                };
-                let dt_str = sum_entry.transaction_date.as_str();
-                let txn_datetime: chrono::DateTime<chrono::Utc> = dt_str.parse().unwrap();
-                let diff = chrono::Utc::now() - txn_datetime;
-                if diff.num_minutes() > budget_timeframe.round() as i64 {
-                    // outside of the budget range concern
-                    continue;
-                }
                 // include the amount of the current entry, and check budget status
-                curr_cat_sum.total += sum_entry.amount;
-                if curr_cat_sum.total > curr_cat_sum.budget {
-                    curr_cat_sum.overbudget = true;
+                let entry_currency = sum_entry
+                    .currency
+                    .as_deref()
+                    .unwrap_or(&sum_entry.account_currency);
+                let converted = match rates.convert(
+                    sum_entry.amount,
+                    entry_currency,
+                    sum_entry.transaction_date.date_naive(),
+                ) {
+                    Ok(converted) => converted,
+                    Err(e) => {
+                        return Err((Status::UnprocessableEntity, e));
+                    }
+                };
+                curr_cat_sum.total += converted;
+                match curr_cat_sum.category_type.as_str() {
+                    "expense" => {
+                        curr_cat_sum.overbudget = curr_cat_sum.total > curr_cat_sum.effective_budget
+                    }
+                    "income" => curr_cat_sum.target_reached = curr_cat_sum.total >= curr_cat_sum.budget,
+                    _ => {}
                 }
+                // amounts in another currency keep their own currency code
+                let amount_str = if entry_currency == rates.base() {
+                    sum_entry.amount.to_string()
+                } else {
+                    format!("{} {}", sum_entry.amount, entry_currency)
+                };
                 let curr_line = match sum_entry.notes {
                     Some(valid_notes) => format!(
                         "{}, {}, {}",
-                        sum_entry.transaction_date, sum_entry.amount, valid_notes
+                        sum_entry.transaction_date, amount_str, valid_notes
                     ),
-                    None => format!("{}, {}, ", sum_entry.transaction_date, sum_entry.amount,),
+                    None => format!("{}, {}, ", sum_entry.transaction_date, amount_str,),
                 };
                 curr_cat_sum.cat_trans.push(curr_line);
                 curr_cat_sum.transaction_idz.push(sum_entry.transaction_id);
                 // refresh the summary copy
                 cat_summary_dict.insert(sum_entry.nickname.clone(), curr_cat_sum.clone());
             }
+            // Step 2: carry the previous period into expense categories with a rollover policy
+            let carry_windows: Vec<(String, i32, Period)> = cat_summary_dict
+                .values()
+                .filter(|cat_sum| cat_sum.category_type == "expense" && cat_sum.rollover != "none")
+                .filter_map(|cat_sum| {
+                    let previous = match selector {
+                        Some(selector) => calendar.resolve(selector.previous()?)?,
+                        None => calendar.period_before(&cat_sum.budget_freq, &cat_sum.bounds?)?,
+                    };
+                    Some((cat_sum.nickname.clone(), *cat_ids.get(&cat_sum.nickname)?, previous))
+                })
+                .collect();
+            let previous_result = tokio::task::spawn_blocking({
+                let pool = pool.clone();
+                move || {
+                    let mut conn = pool.get().expect("Failed to get database connection");
+                    let mut previous_entries = vec![];
+                    for (cat_name, cat_id, previous) in carry_windows {
+                        let entries = transactions
+                            .inner_join(accounts)
+                            .filter(crate::schema::transactions::dsl::category_id.eq(cat_id))
+                            .filter(transaction_date.ge(previous.start_utc()))
+                            .filter(transaction_date.lt(previous.end_utc()))
+                            .select((
+                                crate::schema::transactions::dsl::amount,
+                                crate::schema::transactions::dsl::currency,
+                                crate::schema::accounts::dsl::currency,
+                                crate::schema::transactions::dsl::transaction_date,
+                            ))
+                            .load::<(Decimal, Option<String>, String, DateTime<Utc>)>(&mut conn)?;
+                        previous_entries.push((cat_name, previous, entries));
+                    }
+                    Ok::<_, diesel::result::Error>(previous_entries)
+                }
+            })
+            .await;
+            let previous_entries = match previous_result {
+                Ok(Ok(previous_entries)) => previous_entries,
+                Ok(Err(e)) => {
+                    eprintln!("Database error during rollover retrieval: {:?}", e);
+                    return Err((Status::InternalServerError, "Database error".to_string()));
+                }
+                Err(e) => {
+                    eprintln!("Blocking task failed during rollover retrieval: {:?}", e);
+                    return Err((
+                        Status::InternalServerError,
+                        "Internal server error".to_string(),
+                    ));
+                }
+            };
+            for (cat_name, previous, entries) in previous_entries {
+                let mut previous_total = Decimal::ZERO;
+                for (prev_amount, prev_currency, prev_account_currency, prev_date) in entries {
+                    let prev_entry_currency = prev_currency.as_deref().unwrap_or(&prev_account_currency);
+                    match rates.convert(prev_amount, prev_entry_currency, prev_date.date_naive()) {
+                        Ok(converted) => previous_total += converted,
+                        Err(e) => {
+                            return Err((Status::UnprocessableEntity, e));
+                        }
+                    }
+                }
+                // the previous budget at the previous period's rate, like its spending
+                let stored_budget = stored_budgets.get(&cat_name).copied().unwrap_or_default();
+                let previous_budget = match rates.convert(
+                    stored_budget,
+                    DEFAULT_CURRENCY,
+                    previous.start.date_naive(),
+                ) {
+                    Ok(converted) => converted,
+                    Err(e) => {
+                        return Err((Status::UnprocessableEntity, e));
+                    }
+                };
+                if let Some(cat_sum) = cat_summary_dict.get_mut(&cat_name) {
+                    cat_sum.carried = carry_over(&cat_sum.rollover, previous_budget, previous_total);
+                    cat_sum.effective_budget = cat_sum.budget + cat_sum.carried;
+                    cat_sum.overbudget = cat_sum.total > cat_sum.effective_budget;
+                }
+            }
+
             // populate summary
-            for (_, sum_obj) in &cat_summary_dict {
+            for sum_obj in cat_summary_dict.values() {
                 summary.push(sum_obj.clone());
             }
-            return (Status::Ok, Json(summary));
+            Ok(Json(summary))
         }
+        Ok(Ok(Err(rejected))) => Err(rejected),
         Ok(Err(e)) => {
             eprintln!("Database error during category summary retrieval: {:?}", e);
-            return (Status::InternalServerError, Json(vec![]));
+            Err((Status::InternalServerError, "Database error".to_string()))
         }
         Err(e) => {
             eprintln!(
                 "Blocking task failed during category summary retrieval: {:?}",
                 e
             );
-            return (Status::InternalServerError, Json(vec![]));
+            Err((
+                Status::InternalServerError,
+                "Internal server error".to_string(),
+            ))
+        }
+    }
+}
+
+// helper struct for cash flow entries
+#[derive(Debug, Queryable)]
+pub struct CashFlowEntry {
+    pub category_id: i32,
+    pub category_type: String,
+    pub amount: Decimal,
+    pub currency: Option<String>,
+    pub account_currency: String,
+    pub transaction_date: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IncomeTarget {
+    pub nickname: String,
+    // the category budget scaled to the report period
+    pub target: Decimal,
+    pub received: Decimal,
+    pub reached: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CashFlowReport {
+    pub base_currency: String,
+    pub period: String,
+    // the current calendar period of that frequency
+    pub bounds: Period,
+    pub income: Decimal,
+    pub expense: Decimal,
+    // income minus expense, transfers are left out
+    pub net_cash_flow: Decimal,
+    // share of income not spent, in percent, missing when there was no income
+    pub savings_rate: Option<Decimal>,
+    pub income_targets: Vec<IncomeTarget>,
+}
+
+// GET /report_cashflow?<period>&<base_currency>
+pub async fn handle_report_cashflow(
+    user: AuthUser,
+    period: Option<String>,
+    base_currency: Option<String>,
+    pool: DbPool,
+) -> Result<Json<CashFlowReport>, (Status, String)> {
+    let email_str = user.email;
+    let Some(base) = resolve_base_currency(base_currency) else {
+        return Err((Status::BadRequest, "Invalid currency code".to_string()));
+    };
+    let period = period
+        .map(|p| p.trim().to_ascii_lowercase())
+        .unwrap_or_else(|| "monthly".to_string());
+    let Some(report_per_year) = periods_per_year(&period) else {
+        return Err((
+            Status::BadRequest,
+            "Invalid period: expected daily, weekly, monthly or yearly".to_string(),
+        ));
+    };
+
+    // Step 1: get the income categories and every income/expense transaction of the period
+    let cashflow_result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        let email_to_search = email_str.clone();
+        let period = period.clone();
+        move || {
+            let mut conn = pool.get().expect("Failed to get database connection");
+            let calendar = BudgetCalendar::load(&mut conn, &email_to_search)?;
+            let Some(bounds) = calendar.period_containing(&period, Utc::now()) else {
+                return Ok(Err((Status::BadRequest, UNKNOWN_PERIOD.to_string())));
+            };
+            let rates = RateTable::load(&mut conn, &base, bounds.days())?;
+            let income_cats = categories
+                .filter(crate::schema::categories::dsl::email.eq(&email_to_search))
+                .filter(category_type.eq("income"))
+                .select((
+                    crate::schema::categories::dsl::category_id,
+                    nickname,
+                    budget,
+                    budget_freq,
+                ))
+                .order_by(nickname)
+                .load::<(i32, String, Decimal, String)>(&mut conn)?;
+            let entries = transactions
+                .inner_join(categories)
+                .inner_join(accounts)
+                .filter(crate::schema::transactions::dsl::email.eq(&email_to_search))
+                .filter(category_type.ne("transfer"))
+                .filter(transaction_date.ge(bounds.start_utc()))
+                .filter(transaction_date.lt(bounds.end_utc()))
+                .select((
+                    crate::schema::categories::dsl::category_id,
+                    crate::schema::categories::dsl::category_type,
+                    crate::schema::transactions::dsl::amount,
+                    crate::schema::transactions::dsl::currency,
+                    crate::schema::accounts::dsl::currency,
+                    crate::schema::transactions::dsl::transaction_date,
+                ))
+                .load::<CashFlowEntry>(&mut conn)?;
+            Ok::<_, diesel::result::Error>(Ok((rates, income_cats, entries, bounds)))
+        }
+    })
+    .await;
+
+    let (rates, income_cats, entries, bounds) = match cashflow_result {
+        Ok(Ok(Ok(loaded))) => loaded,
+        Ok(Ok(Err(rejected))) => return Err(rejected),
+        Ok(Err(e)) => {
+            eprintln!("Database error during cash flow report retrieval: {:?}", e);
+            return Err((Status::InternalServerError, "Database error".to_string()));
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed during cash flow report retrieval: {:?}", e);
+            return Err((Status::InternalServerError, "Internal server error".to_string()));
+        }
+    };
+
+    // Step 2: sum income and expense in the base currency
+    let mut income = Decimal::ZERO;
+    let mut expense = Decimal::ZERO;
+    let mut received_per_cat: HashMap<i32, Decimal> = HashMap::new();
+    for entry in entries.into_iter() {
+        let entry_currency = entry.currency.as_deref().unwrap_or(&entry.account_currency);
+        let converted = rates
+            .convert(entry.amount, entry_currency, entry.transaction_date.date_naive())
+            .map_err(|e| (Status::UnprocessableEntity, e))?;
+        if entry.category_type == "income" {
+            income += converted;
+            *received_per_cat.entry(entry.category_id).or_default() += converted;
+        } else {
+            expense += converted;
+        }
+    }
+
+    // Step 3: compare each income category against its target for the period,
+    // targets are stored in DEFAULT_CURRENCY and converted at the period's first day
+    let income_targets = income_cats
+        .into_iter()
+        .map(|(cat_id, cat_name, cat_budget, cat_freq)| {
+            let stored_target = match periods_per_year(&cat_freq) {
+                Some(cat_per_year) => (cat_budget
+                    * Decimal::from(cat_per_year)
+                    / Decimal::from(report_per_year))
+                    .round_dp_with_strategy(2, RoundingStrategy::MidpointAwayFromZero),
+                None => cat_budget,
+            };
+            let target = rates
+                .convert(stored_target, DEFAULT_CURRENCY, bounds.start.date_naive())
+                .map_err(|e| (Status::UnprocessableEntity, e))?;
+            let received = received_per_cat.get(&cat_id).copied().unwrap_or_default();
+            Ok(IncomeTarget {
+                nickname: cat_name,
+                target,
+                received,
+                reached: received >= target,
+            })
+        })
+        .collect::<Result<Vec<IncomeTarget>, (Status, String)>>()?;
+
+    let net_cash_flow = income - expense;
+    let savings_rate = if income.is_zero() {
+        None
+    } else {
+        Some(
+            (net_cash_flow * Decimal::ONE_HUNDRED / income)
+                .round_dp_with_strategy(2, RoundingStrategy::MidpointAwayFromZero),
+        )
+    };
+
+    Ok(Json(CashFlowReport {
+        base_currency: rates.base().to_string(),
+        period,
+        bounds,
+        income,
+        expense,
+        net_cash_flow,
+        savings_rate,
+        income_targets,
+    }))
+}
+
+// helper struct for tag report entries, one per tag of each transaction
+#[derive(Debug, Queryable)]
+pub struct TagEntry {
+    pub tag_name: String,
+    pub category_type: String,
+    pub amount: Decimal,
+    pub currency: Option<String>,
+    pub account_currency: String,
+    pub transaction_date: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TagTotal {
+    pub tag: String,
+    // expenses with the tag
+    pub spent: Decimal,
+    // income with the tag
+    pub received: Decimal,
+    pub transactions: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TagReport {
+    pub base_currency: String,
+    pub bounds: Period,
+    // most spent first
+    pub tags: Vec<TagTotal>,
+}
+
+// GET /report_tags?<period>&<from>&<to>&<base_currency>
+// Totals per tag over a period, the current month when none is given.
+// A transaction with several tags counts fully under each of them.
+pub async fn handle_report_tags(
+    user: AuthUser,
+    period: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    base_currency: Option<String>,
+    pool: DbPool,
+) -> Result<Json<TagReport>, (Status, String)> {
+    let email_str = user.email;
+    let Some(base) = resolve_base_currency(base_currency) else {
+        return Err((Status::BadRequest, "Invalid currency code".to_string()));
+    };
+    let selector = PeriodSelector::parse(period.as_deref(), from.as_deref(), to.as_deref())
+        .map_err(|e| (Status::BadRequest, e.to_string()))?;
+
+    // Step 1: get every tagged income/expense transaction of the period
+    let tags_result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        let email_to_search = email_str.clone();
+        move || {
+            use crate::schema::tags::dsl as tag;
+            use crate::schema::transaction_tags::dsl::transaction_tags;
+
+            let mut conn = pool.get().expect("Failed to get database connection");
+            let calendar = BudgetCalendar::load(&mut conn, &email_to_search)?;
+            let Some(bounds) = (match selector {
+                Some(selector) => calendar.resolve(selector),
+                None => calendar.period_containing("monthly", Utc::now()),
+            }) else {
+                return Ok(Err((Status::BadRequest, UNKNOWN_PERIOD.to_string())));
+            };
+            let rates = RateTable::load(&mut conn, &base, bounds.days())?;
+            let entries = transaction_tags
+                .inner_join(tag::tags)
+                .inner_join(transactions.inner_join(categories).inner_join(accounts))
+                .filter(tag::email.eq(&email_to_search))
+                .filter(category_type.ne("transfer"))
+                .filter(transaction_date.ge(bounds.start_utc()))
+                .filter(transaction_date.lt(bounds.end_utc()))
+                .select((
+                    tag::name,
+                    crate::schema::categories::dsl::category_type,
+                    crate::schema::transactions::dsl::amount,
+                    crate::schema::transactions::dsl::currency,
+                    crate::schema::accounts::dsl::currency,
+                    crate::schema::transactions::dsl::transaction_date,
+                ))
+                .load::<TagEntry>(&mut conn)?;
+            Ok::<_, diesel::result::Error>(Ok((rates, entries, bounds)))
+        }
+    })
+    .await;
+
+    let (rates, entries, bounds) = match tags_result {
+        Ok(Ok(Ok(loaded))) => loaded,
+        Ok(Ok(Err(rejected))) => return Err(rejected),
+        Ok(Err(e)) => {
+            eprintln!("Database error during tag report retrieval: {:?}", e);
+            return Err((Status::InternalServerError, "Database error".to_string()));
         }
+        Err(e) => {
+            eprintln!("Blocking task failed during tag report retrieval: {:?}", e);
+            return Err((Status::InternalServerError, "Internal server error".to_string()));
+        }
+    };
+
+    // Step 2: sum per tag in the base currency
+    let mut tag_totals: BTreeMap<String, TagTotal> = BTreeMap::new();
+    for entry in entries.into_iter() {
+        let entry_currency = entry.currency.as_deref().unwrap_or(&entry.account_currency);
+        let converted = rates
+            .convert(entry.amount, entry_currency, entry.transaction_date.date_naive())
+            .map_err(|e| (Status::UnprocessableEntity, e))?;
+        let total = tag_totals
+            .entry(entry.tag_name.clone())
+            .or_insert_with(|| TagTotal {
+                tag: entry.tag_name,
+                spent: Decimal::ZERO,
+                received: Decimal::ZERO,
+                transactions: 0,
+            });
+        if entry.category_type == "income" {
+            total.received += converted;
+        } else {
+            total.spent += converted;
+        }
+        total.transactions += 1;
+    }
+    let mut tag_list: Vec<TagTotal> = tag_totals.into_values().collect();
+    tag_list.sort_by_key(|total| std::cmp::Reverse(total.spent));
+
+    Ok(Json(TagReport {
+        base_currency: rates.base().to_string(),
+        bounds,
+        tags: tag_list,
+    }))
+}
+
+// helper struct for payee report entries
+#[derive(Debug, Queryable)]
+pub struct PayeeEntry {
+    pub payee_name: String,
+    pub category_type: String,
+    pub amount: Decimal,
+    pub currency: Option<String>,
+    pub account_currency: String,
+    pub transaction_date: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PayeeTotal {
+    pub payee: String,
+    // expenses paid to the payee
+    pub spent: Decimal,
+    // income from the payee
+    pub received: Decimal,
+    pub transactions: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PayeeReport {
+    pub base_currency: String,
+    pub bounds: Period,
+    // most spent first, at most `limit` of them
+    pub payees: Vec<PayeeTotal>,
+}
+
+// GET /report_payees?<period>&<from>&<to>&<base_currency>&<limit>
+// The payees with the most spending over a period, the current month when none is given
+pub async fn handle_report_payees(
+    user: AuthUser,
+    period: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    base_currency: Option<String>,
+    limit: Option<String>,
+    pool: DbPool,
+) -> Result<Json<PayeeReport>, (Status, String)> {
+    let email_str = user.email;
+    let Some(base) = resolve_base_currency(base_currency) else {
+        return Err((Status::BadRequest, "Invalid currency code".to_string()));
+    };
+    let selector = PeriodSelector::parse(period.as_deref(), from.as_deref(), to.as_deref())
+        .map_err(|e| (Status::BadRequest, e.to_string()))?;
+    let top_count = match limit {
+        Some(limit_str) => match limit_str.trim().parse::<usize>() {
+            Ok(top_count) if top_count > 0 => top_count,
+            _ => return Err((Status::BadRequest, "Invalid limit".to_string())),
+        },
+        None => DEFAULT_TOP_PAYEES,
+    };
+
+    // Step 1: get every income/expense transaction of the period that names a payee
+    let payees_result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        let email_to_search = email_str.clone();
+        move || {
+            use crate::schema::payees::dsl as payee;
+
+            let mut conn = pool.get().expect("Failed to get database connection");
+            let calendar = BudgetCalendar::load(&mut conn, &email_to_search)?;
+            let Some(bounds) = (match selector {
+                Some(selector) => calendar.resolve(selector),
+                None => calendar.period_containing("monthly", Utc::now()),
+            }) else {
+                return Ok(Err((Status::BadRequest, UNKNOWN_PERIOD.to_string())));
+            };
+            let rates = RateTable::load(&mut conn, &base, bounds.days())?;
+            let entries = transactions
+                .inner_join(payee::payees)
+                .inner_join(categories)
+                .inner_join(accounts)
+                .filter(payee::email.eq(&email_to_search))
+                .filter(category_type.ne("transfer"))
+                .filter(transaction_date.ge(bounds.start_utc()))
+                .filter(transaction_date.lt(bounds.end_utc()))
+                .select((
+                    payee::name,
+                    crate::schema::categories::dsl::category_type,
+                    crate::schema::transactions::dsl::amount,
+                    crate::schema::transactions::dsl::currency,
+                    crate::schema::accounts::dsl::currency,
+                    crate::schema::transactions::dsl::transaction_date,
+                ))
+                .load::<PayeeEntry>(&mut conn)?;
+            Ok::<_, diesel::result::Error>(Ok((rates, entries, bounds)))
+        }
+    })
+    .await;
+
+    let (rates, entries, bounds) = match payees_result {
+        Ok(Ok(Ok(loaded))) => loaded,
+        Ok(Ok(Err(rejected))) => return Err(rejected),
+        Ok(Err(e)) => {
+            eprintln!("Database error during payee report retrieval: {:?}", e);
+            return Err((Status::InternalServerError, "Database error".to_string()));
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed during payee report retrieval: {:?}", e);
+            return Err((Status::InternalServerError, "Internal server error".to_string()));
+        }
+    };
+
+    // Step 2: sum per payee in the base currency, then keep the top ones
+    let mut payee_totals: BTreeMap<String, PayeeTotal> = BTreeMap::new();
+    for entry in entries.into_iter() {
+        let entry_currency = entry.currency.as_deref().unwrap_or(&entry.account_currency);
+        let converted = rates
+            .convert(entry.amount, entry_currency, entry.transaction_date.date_naive())
+            .map_err(|e| (Status::UnprocessableEntity, e))?;
+        let total = payee_totals
+            .entry(entry.payee_name.clone())
+            .or_insert_with(|| PayeeTotal {
+                payee: entry.payee_name,
+                spent: Decimal::ZERO,
+                received: Decimal::ZERO,
+                transactions: 0,
+            });
+        if entry.category_type == "income" {
+            total.received += converted;
+        } else {
+            total.spent += converted;
+        }
+        total.transactions += 1;
+    }
+    let mut payee_list: Vec<PayeeTotal> = payee_totals.into_values().collect();
+    payee_list.sort_by_key(|total| std::cmp::Reverse(total.spent));
+    payee_list.truncate(top_count);
+
+    Ok(Json(PayeeReport {
+        base_currency: rates.base().to_string(),
+        bounds,
+        payees: payee_list,
+    }))
+}
+
+// Last day of the calendar month of `day`
+fn month_end(day: NaiveDate) -> Option<NaiveDate> {
+    day.with_day(1)?
+        .checked_add_months(Months::new(1))?
+        .pred_opt()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NetWorthAccount {
+    pub account_name: String,
+    pub account_type: String,
+    // "asset" or "liability", from the account type
+    pub class: String,
+    pub currency: String,
+    // in `currency`, what is owed for a liability
+    pub balance: Decimal,
+    // `balance` in the base currency
+    pub converted: Decimal,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NetWorthPoint {
+    pub date: NaiveDate,
+    pub assets: Decimal,
+    // what is owed on liability accounts
+    pub liabilities: Decimal,
+    // assets minus liabilities
+    pub net_worth: Decimal,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NetWorthReport {
+    pub base_currency: String,
+    // the totals and accounts are the balances at the end of this day
+    pub as_of: NaiveDate,
+    pub assets: Decimal,
+    pub liabilities: Decimal,
+    pub net_worth: Decimal,
+    pub accounts: Vec<NetWorthAccount>,
+    // one point per month end from `from` on, the last one being `as_of`
+    pub series: Vec<NetWorthPoint>,
+}
+
+// GET /report_networth?<as_of>&<from>&<base_currency>
+pub async fn handle_report_networth(
+    user: AuthUser,
+    as_of: Option<String>,
+    from: Option<String>,
+    base_currency: Option<String>,
+    pool: DbPool,
+) -> Result<Json<NetWorthReport>, (Status, String)> {
+    let email_str = user.email;
+    let Some(base) = resolve_base_currency(base_currency) else {
+        return Err((Status::BadRequest, "Invalid currency code".to_string()));
+    };
+    let parse_bound = |raw: Option<String>| match raw {
+        Some(day_str) => parse_day(&day_str)
+            .map(Some)
+            .ok_or((Status::BadRequest, "Invalid date: expected YYYY-MM-DD".to_string())),
+        None => Ok(None),
+    };
+    let as_of = parse_bound(as_of)?;
+    let from = parse_bound(from)?;
+
+    // Step 1: get every account with its flows, the series bounds and the rates into base
+    // over them, the series runs over the last twelve month ends by default
+    let networth_result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        let email_to_search = email_str.clone();
+        move || {
+            let mut conn = pool.get().expect("Failed to get database connection");
+            let today = BudgetCalendar::load(&mut conn, &email_to_search)?.today(Utc::now());
+            let as_of = as_of.unwrap_or(today);
+            let from = match from {
+                Some(from) => from,
+                None => as_of.checked_sub_months(Months::new(11)).unwrap_or(as_of),
+            };
+            let rates = RateTable::load(&mut conn, &base, [from, as_of])?;
+            let owned_accounts = load_account_flows(&mut conn, &email_to_search)?;
+            Ok::<_, diesel::result::Error>((as_of, from, rates, owned_accounts))
+        }
+    })
+    .await;
+
+    let (as_of, from, rates, owned_accounts) = match networth_result {
+        Ok(Ok((as_of, from, rates, Ok(owned_accounts)))) => (as_of, from, rates, owned_accounts),
+        Ok(Ok((_, _, _, Err(e)))) => return Err((Status::UnprocessableEntity, e)),
+        Ok(Err(e)) => {
+            eprintln!("Database error during net worth report retrieval: {:?}", e);
+            return Err((Status::InternalServerError, "Database error".to_string()));
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed during net worth report retrieval: {:?}", e);
+            return Err((Status::InternalServerError, "Internal server error".to_string()));
+        }
+    };
+
+    // Step 2: pick the days of the series
+    if from > as_of {
+        return Err((
+            Status::BadRequest,
+            "Start date must not be after the as-of date".to_string(),
+        ));
+    }
+    let mut days: Vec<NaiveDate> = vec![];
+    let mut next_day = month_end(from);
+    while let Some(day) = next_day.filter(|day| *day < as_of) {
+        days.push(day);
+        if days.len() >= MAX_NETWORTH_POINTS {
+            return Err((
+                Status::BadRequest,
+                "Too many points: pick a later start date".to_string(),
+            ));
+        }
+        next_day = day.succ_opt().and_then(month_end);
+    }
+    days.push(as_of);
+
+    // Step 3: convert each balance into base at the rate of the day, liabilities apart
+    let mut accounts_as_of: Vec<NetWorthAccount> = vec![];
+    let mut series: Vec<NetWorthPoint> = vec![];
+    for day in days.into_iter() {
+        let mut assets = Decimal::ZERO;
+        let mut liabilities = Decimal::ZERO;
+        for (acc, acc_flows) in owned_accounts.iter() {
+            let balance = balance_on(acc, acc_flows, day);
+            let converted = rates
+                .convert(balance, &acc.currency, day)
+                .map_err(|e| (Status::UnprocessableEntity, e))?;
+            if acc.is_liability() {
+                liabilities += converted;
+            } else {
+                assets += converted;
+            }
+            if day == as_of {
+                accounts_as_of.push(NetWorthAccount {
+                    account_name: acc.account_name.clone(),
+                    account_type: acc.account_type.clone(),
+                    class: if acc.is_liability() { "liability" } else { "asset" }.to_string(),
+                    currency: acc.currency.clone(),
+                    balance,
+                    converted,
+                });
+            }
+        }
+        series.push(NetWorthPoint {
+            date: day,
+            assets,
+            liabilities,
+            net_worth: assets - liabilities,
+        });
+    }
+
+    let Some(latest) = series.last().cloned() else {
+        return Err((Status::InternalServerError, "Internal server error".to_string()));
+    };
+    Ok(Json(NetWorthReport {
+        base_currency: rates.base().to_string(),
+        as_of,
+        assets: latest.assets,
+        liabilities: latest.liabilities,
+        net_worth: latest.net_worth,
+        accounts: accounts_as_of,
+        series,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::carry_over;
+    use rust_decimal::Decimal;
+
+    fn carried(policy: &str, cat_budget: &str, previous_total: &str) -> Decimal {
+        carry_over(
+            policy,
+            cat_budget.parse().unwrap(),
+            previous_total.parse().unwrap(),
+        )
+    }
+
+    fn value(text: &str) -> Decimal {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn carries_only_what_was_left_unused() {
+        assert_eq!(carried("carry_unused", "100", "60.50"), value("39.50"));
+        assert_eq!(carried("carry_unused", "100", "0"), value("100"));
+        assert_eq!(carried("carry_unused", "100", "100"), Decimal::ZERO);
+        assert_eq!(carried("carry_unused", "100", "130"), Decimal::ZERO);
+    }
+
+    #[test]
+    fn carries_only_the_overspending() {
+        assert_eq!(carried("carry_overspend", "100", "130.25"), value("-30.25"));
+        assert_eq!(carried("carry_overspend", "100", "100"), Decimal::ZERO);
+        assert_eq!(carried("carry_overspend", "100", "60"), Decimal::ZERO);
+    }
+
+    #[test]
+    fn refunds_count_against_the_spending() {
+        // more refunded than spent leaves more than the whole budget
+        assert_eq!(carried("carry_unused", "100", "-20"), value("120"));
+        assert_eq!(carried("carry_overspend", "100", "-20"), Decimal::ZERO);
+    }
+
+    #[test]
+    fn carries_nothing_without_a_policy() {
+        assert_eq!(carried("none", "100", "60"), Decimal::ZERO);
+        assert_eq!(carried("none", "100", "130"), Decimal::ZERO);
+        assert_eq!(carried("unknown", "100", "130"), Decimal::ZERO);
     }
 }
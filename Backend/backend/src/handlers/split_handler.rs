@@ -0,0 +1,397 @@
+use crate::currency::normalize_currency;
+use crate::db::DbPool;
+use crate::guards::AuthUser;
+use crate::handlers::notification_handler::record_budget_alerts;
+use crate::handlers::payee_handler::find_or_create_payee;
+use crate::handlers::transaction_handler::parse_client_date;
+use crate::models::account::Account;
+use crate::models::payee::{normalize_payee, INVALID_PAYEE};
+use crate::models::split::{ClientSplit, NewSplit, NewSplitLine, Split, SplitDetails, SplitLine};
+use crate::schema::splits::dsl::{split_id, splits};
+use crate::schema::transactions::dsl::transactions;
+use chrono::Utc;
+use diesel::prelude::*;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+// every line counts the same way, income lines in an expense split would count backwards
+pub const SPLIT_MIXED_TYPES: &str = "Split lines must all use categories of the same type";
+
+// POST /split_create
+// Records one payment from an account as a split with a transaction per category line,
+// so reports count every line under its own category
+pub async fn handle_create_split(
+    user: AuthUser,
+    client_split: ClientSplit,
+    pool: DbPool,
+) -> (Status, String) {
+    // Step 1: validate input, the lines have to add up to the total
+    if client_split.lines.len() < 2 {
+        return (
+            Status::BadRequest,
+            "A split needs at least two category lines".to_string(),
+        );
+    }
+    if client_split.lines.iter().any(|line| line.amount.is_zero()) {
+        return (
+            Status::BadRequest,
+            "Split line amounts cannot be zero".to_string(),
+        );
+    }
+    let lines_total: Decimal = client_split.lines.iter().map(|line| line.amount).sum();
+    if lines_total != client_split.amount {
+        return (
+            Status::BadRequest,
+            format!(
+                "Split lines add up to {} instead of {}",
+                lines_total, client_split.amount
+            ),
+        );
+    }
+    let trans_date = match client_split.transaction_date.as_deref() {
+        Some(date_str) if !date_str.trim().is_empty() => match parse_client_date(date_str) {
+            Some(parsed_date) => parsed_date,
+            None => return (Status::BadRequest, "Invalid transaction date".to_string()),
+        },
+        _ => Utc::now(),
+    };
+    let trans_currency = match client_split.currency.as_deref() {
+        Some(code) if !code.trim().is_empty() => match normalize_currency(code) {
+            Some(valid_code) => Some(valid_code),
+            None => return (Status::BadRequest, "Invalid currency code".to_string()),
+        },
+        _ => None,
+    };
+    let payee_name = match client_split.payee.as_deref() {
+        Some(raw) if !raw.trim().is_empty() => match normalize_payee(raw) {
+            Some(valid_name) => Some(valid_name),
+            None => return (Status::BadRequest, INVALID_PAYEE.to_string()),
+        },
+        _ => None,
+    };
+
+    // Step 2: find the account and the category of every line among the caller's
+    // active ones, then write the split and its lines at once
+    let result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        let owner = user.email.clone();
+        move || {
+            use crate::schema::accounts::dsl as acc;
+            use crate::schema::categories::dsl as cat;
+
+            let mut conn = pool.get().expect("Failed to get database connection");
+            let Some(split_account) = acc::accounts
+                .filter(acc::email.eq(&owner))
+                .filter(acc::account_name.eq(&client_split.account_name))
+                .filter(acc::archived_at.is_null())
+                .first::<Account>(&mut conn)
+                .optional()?
+            else {
+                return Ok(Err((
+                    Status::BadRequest,
+                    "No account found for the provided email".to_string(),
+                )));
+            };
+            let line_names: Vec<&String> = client_split
+                .lines
+                .iter()
+                .map(|line| &line.category_name)
+                .collect();
+            let cat_ids: HashMap<String, (i32, String)> = cat::categories
+                .filter(cat::email.eq(&owner))
+                .filter(cat::nickname.eq_any(line_names))
+                .filter(cat::archived_at.is_null())
+                .select((cat::nickname, (cat::category_id, cat::category_type)))
+                .load::<(String, (i32, String))>(&mut conn)?
+                .into_iter()
+                .collect();
+            let mut line_cat_ids = Vec::with_capacity(client_split.lines.len());
+            let mut line_types = Vec::with_capacity(client_split.lines.len());
+            for line in client_split.lines.iter() {
+                match cat_ids.get(&line.category_name) {
+                    Some((line_cat_id, line_type)) => {
+                        line_cat_ids.push(*line_cat_id);
+                        line_types.push(line_type);
+                    }
+                    None => {
+                        return Ok(Err((
+                            Status::BadRequest,
+                            format!(
+                                "No category found for the provided email: {}",
+                                line.category_name
+                            ),
+                        )))
+                    }
+                }
+            }
+            if line_types
+                .iter()
+                .any(|line_type| *line_type != line_types[0])
+            {
+                return Ok(Err((Status::BadRequest, SPLIT_MIXED_TYPES.to_string())));
+            }
+
+            let new_split_id = conn.transaction(|conn| {
+                let line_payee_id = match payee_name.as_deref() {
+                    Some(payee_name) => Some(find_or_create_payee(conn, &owner, payee_name)?),
+                    None => None,
+                };
+                let new_split_id = diesel::insert_into(splits)
+                    .values(&NewSplit {
+                        email: owner.clone(),
+                        account_id: split_account.account_id,
+                        amount: client_split.amount,
+                        notes: client_split.notes.clone(),
+                        transaction_date: trans_date,
+                        currency: trans_currency.clone(),
+                    })
+                    .returning(split_id)
+                    .get_result::<i32>(conn)?;
+                let lines: Vec<NewSplitLine> = client_split
+                    .lines
+                    .iter()
+                    .zip(line_cat_ids.iter())
+                    .map(|(line, line_cat_id)| NewSplitLine {
+                        email: owner.clone(),
+                        category_id: *line_cat_id,
+                        amount: line.amount,
+                        notes: line.notes.clone().or_else(|| client_split.notes.clone()),
+                        account_id: split_account.account_id,
+                        transaction_date: trans_date,
+                        currency: trans_currency.clone(),
+                        split_id: new_split_id,
+                        payee_id: line_payee_id,
+                    })
+                    .collect();
+                diesel::insert_into(transactions)
+                    .values(&lines)
+                    .execute(conn)?;
+                Ok::<_, diesel::result::Error>(new_split_id)
+            })?;
+
+            // the split is already saved, a failed alert check does not undo it
+            for line_cat_id in line_cat_ids {
+                if let Err(e) = record_budget_alerts(&mut conn, &owner, line_cat_id, trans_date) {
+                    eprintln!("Error recording budget alerts: {:?}", e);
+                }
+            }
+            Ok::<_, diesel::result::Error>(Ok(new_split_id))
+        }
+    })
+    .await;
+
+    match result {
+        Ok(Ok(Ok(new_split_id))) => (Status::Created, new_split_id.to_string()),
+        Ok(Ok(Err(rejected))) => rejected,
+        Ok(Err(e)) => {
+            eprintln!("Database error during split creation: {:?}", e);
+            (Status::InternalServerError, "Database error".to_string())
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed during split creation: {:?}", e);
+            (
+                Status::InternalServerError,
+                "Internal server error".to_string(),
+            )
+        }
+    }
+}
+
+// GET /split_details?<split_id>
+// The split with its account and category lines
+pub async fn handle_split_details(
+    user: AuthUser,
+    split_id_str: String,
+    pool: DbPool,
+) -> Result<Json<SplitDetails>, (Status, String)> {
+    let owned_split = check_split_owner(user.email.clone(), split_id_str, pool.clone())
+        .await
+        .map_err(|(status, message)| (status, message.to_string()))?;
+
+    let details_result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        move || {
+            use crate::schema::accounts::dsl as acc;
+            use crate::schema::categories::dsl as cat;
+            use crate::schema::transactions::dsl as trans;
+
+            let mut conn = pool.get().expect("Failed to get database connection");
+            let split_account_name = acc::accounts
+                .filter(acc::account_id.eq(owned_split.account_id))
+                .select(acc::account_name)
+                .first::<String>(&mut conn)?;
+            let lines = transactions
+                .inner_join(cat::categories)
+                .left_join(crate::schema::payees::dsl::payees)
+                .filter(trans::split_id.eq(owned_split.split_id))
+                .select((
+                    trans::trans_id,
+                    cat::nickname,
+                    trans::amount,
+                    trans::notes,
+                    crate::schema::payees::dsl::name.nullable(),
+                ))
+                .order_by(trans::trans_id)
+                .load::<(i32, String, Decimal, Option<String>, Option<String>)>(&mut conn)?
+                .into_iter()
+                .map(|(line_id, category_name, amount, notes, payee)| SplitLine {
+                    trans_id: line_id,
+                    category_name,
+                    amount,
+                    notes,
+                    payee,
+                })
+                .collect();
+            Ok::<_, diesel::result::Error>(SplitDetails {
+                split: owned_split,
+                account_name: split_account_name,
+                lines,
+            })
+        }
+    })
+    .await;
+
+    match details_result {
+        Ok(Ok(details)) => Ok(Json(details)),
+        Ok(Err(e)) => {
+            eprintln!("Database error during split retrieval: {:?}", e);
+            Err((Status::InternalServerError, "Database error".to_string()))
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed during split retrieval: {:?}", e);
+            Err((
+                Status::InternalServerError,
+                "Internal server error".to_string(),
+            ))
+        }
+    }
+}
+
+// DELETE /delete_split?<split_id>
+// Deletes the split, its category lines go with it
+pub async fn handle_delete_split(
+    user: AuthUser,
+    split_id_str: String,
+    pool: DbPool,
+) -> (Status, &'static str) {
+    let owned_split = match check_split_owner(user.email.clone(), split_id_str, pool.clone()).await
+    {
+        Ok(owned_split) => owned_split,
+        Err(err) => return err,
+    };
+
+    let deletion_result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        let email_to_check = user.email.clone();
+        move || {
+            let mut conn = pool.get().expect("Failed to get database connection");
+            diesel::delete(
+                splits
+                    .filter(split_id.eq(owned_split.split_id))
+                    .filter(crate::schema::splits::dsl::email.eq(email_to_check)),
+            )
+            .execute(&mut conn)
+        }
+    })
+    .await;
+
+    match deletion_result {
+        Ok(Ok(rows_deleted)) if rows_deleted > 0 => (Status::Ok, "Split successfully deleted"),
+        Ok(Ok(_)) => (Status::InternalServerError, "Failed to delete the split"),
+        Ok(Err(e)) => {
+            eprintln!("Error during split deletion: {:?}", e);
+            (
+                Status::InternalServerError,
+                "Database error during deletion",
+            )
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed during split deletion: {:?}", e);
+            (Status::InternalServerError, "Internal server error")
+        }
+    }
+}
+
+// Ids owned by other users are reported exactly like ids that do not exist
+pub async fn check_split_owner(
+    email_str: String,
+    split_id_str: String,
+    pool: DbPool,
+) -> Result<Split, (Status, &'static str)> {
+    let split_id_int = match split_id_str.trim().parse::<i32>() {
+        Ok(split_id_int) => split_id_int,
+        Err(_) => return Err((Status::BadRequest, "Invalid split ID")),
+    };
+
+    let split_exists = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        move || {
+            let mut conn = pool.get().expect("Failed to get database connection");
+            splits
+                .filter(split_id.eq(split_id_int))
+                .filter(crate::schema::splits::dsl::email.eq(email_str))
+                .first::<Split>(&mut conn)
+                .optional()
+        }
+    })
+    .await;
+
+    match split_exists {
+        Ok(Ok(Some(owned_split))) => Ok(owned_split),
+        Ok(Ok(None)) => Err((Status::NotFound, "No split found for the provided ID")),
+        Ok(Err(e)) => {
+            eprintln!("Error checking split ownership: {:?}", e);
+            Err((Status::InternalServerError, "Database error"))
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed during split check: {:?}", e);
+            Err((Status::InternalServerError, "Internal server error"))
+        }
+    }
+}
+
+// A split line may only move to a category of the same type as the other lines
+pub async fn check_split_line_category(
+    line_split_id: i32,
+    line_id: i32,
+    new_cat_id: i32,
+    pool: DbPool,
+) -> Result<(), (Status, &'static str)> {
+    let types_result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        move || {
+            use crate::schema::categories::dsl as cat;
+            use crate::schema::transactions::dsl as trans;
+
+            let mut conn = pool.get().expect("Failed to get database connection");
+            let new_type = cat::categories
+                .filter(cat::category_id.eq(new_cat_id))
+                .select(cat::category_type)
+                .first::<String>(&mut conn)?;
+            // lines whose category was purged have no type left to match
+            let other_types = trans::transactions
+                .inner_join(cat::categories)
+                .filter(trans::split_id.eq(line_split_id))
+                .filter(trans::trans_id.ne(line_id))
+                .select(cat::category_type)
+                .load::<String>(&mut conn)?;
+            Ok::<_, diesel::result::Error>(other_types.iter().all(|other| *other == new_type))
+        }
+    })
+    .await;
+
+    match types_result {
+        Ok(Ok(true)) => Ok(()),
+        Ok(Ok(false)) => Err((Status::BadRequest, SPLIT_MIXED_TYPES)),
+        Ok(Err(e)) => {
+            eprintln!("Error checking split line categories: {:?}", e);
+            Err((Status::InternalServerError, "Database error"))
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed during split line check: {:?}", e);
+            Err((Status::InternalServerError, "Internal server error"))
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use crate::models::tag::{NewTag, NewTransactionTag};
+use crate::schema::tags::dsl::*;
+use crate::schema::transaction_tags::dsl::{trans_id, transaction_tags};
+use diesel::prelude::*;
+use std::collections::HashMap;
+
+// Some helper functions
+
+// Replaces the tags of a transaction with `names`, already normalized.
+// Tags the owner has not used before are created.
+pub fn set_transaction_tags(
+    conn: &mut PgConnection,
+    owner: &str,
+    tagged_trans_id: i32,
+    names: &[String],
+) -> QueryResult<()> {
+    diesel::delete(transaction_tags.filter(trans_id.eq(tagged_trans_id))).execute(conn)?;
+    if names.is_empty() {
+        return Ok(());
+    }
+
+    let new_tags: Vec<NewTag> = names
+        .iter()
+        .map(|tag_name| NewTag {
+            email: owner.to_string(),
+            name: tag_name.clone(),
+        })
+        .collect();
+    diesel::insert_into(tags)
+        .values(&new_tags)
+        .on_conflict((email, name))
+        .do_nothing()
+        .execute(conn)?;
+    let links: Vec<NewTransactionTag> = tags
+        .filter(email.eq(owner))
+        .filter(name.eq_any(names))
+        .select(tag_id)
+        .load::<i32>(conn)?
+        .into_iter()
+        .map(|linked_tag_id| NewTransactionTag {
+            trans_id: tagged_trans_id,
+            tag_id: linked_tag_id,
+        })
+        .collect();
+    diesel::insert_into(transaction_tags)
+        .values(&links)
+        .execute(conn)?;
+    Ok(())
+}
+
+// Ids of the owner's transactions carrying the tag `tag_name`, already normalized
+pub fn transactions_tagged(
+    conn: &mut PgConnection,
+    owner: &str,
+    tag_name: &str,
+) -> QueryResult<Vec<i32>> {
+    transaction_tags
+        .inner_join(tags)
+        .filter(email.eq(owner))
+        .filter(name.eq(tag_name))
+        .select(trans_id)
+        .load::<i32>(conn)
+}
+
+// The tag names of each of `trans_ids`, sorted, transactions without tags are left out
+pub fn load_transaction_tags(
+    conn: &mut PgConnection,
+    trans_ids: &[i32],
+) -> QueryResult<HashMap<i32, Vec<String>>> {
+    let mut tags_of: HashMap<i32, Vec<String>> = HashMap::new();
+    let links = transaction_tags
+        .inner_join(tags)
+        .filter(trans_id.eq_any(trans_ids))
+        .select((trans_id, name))
+        .order_by(name)
+        .load::<(i32, String)>(conn)?;
+    for (tagged_trans_id, tag_name) in links {
+        tags_of.entry(tagged_trans_id).or_default().push(tag_name);
+    }
+    Ok(tags_of)
+}
@@ -1,42 +1,50 @@
-use crate::db::DbPool;
+use crate::currency::normalize_currency;
+use crate::db::{escape_like, DbPool};
+use crate::guards::AuthUser;
+use crate::handlers::notification_handler::record_budget_alerts;
+use crate::handlers::payee_handler::{find_or_create_payee, find_payee, load_payee_names};
+use crate::handlers::split_handler::check_split_line_category;
+use crate::handlers::tag_handler::{
+    load_transaction_tags, set_transaction_tags, transactions_tagged,
+};
 use crate::models::account::Account;
 use crate::models::category::Category;
-use crate::models::transaction::{ClientTransaction, NewTransaction, Transaction};
-use crate::models::user::User;
+use crate::models::payee::{normalize_payee, INVALID_PAYEE};
+use crate::models::tag::{normalize_tag, normalize_tags, INVALID_TAG};
+use crate::models::transaction::{
+    AccountTransaction, ClientTransaction, ClientTransactionUpdate, NewTransaction,
+    SearchCursor, SearchTransaction, TaggedTransaction, Transaction, TransactionChanges,
+    TransactionPage, TransactionSearch, TransactionSort,
+};
+use crate::period::BudgetCalendar;
 use crate::schema::accounts::dsl::*;
 use crate::schema::categories::dsl::*;
 use crate::schema::transactions::dsl::*;
-use crate::schema::users::dsl::{email as user_email, users}; // For users table
+use crate::schema::splits::dsl::splits;
+use crate::schema::transfers::dsl::transfers;
 use chrono::prelude::*;
 use diesel::prelude::*;
 use rocket::http::Status;
 use rocket::serde::json::Json;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+const TRANSFER_LEG_UPDATE: &str =
+    "Transfer legs cannot be updated, delete the transfer and record it again";
+const SPLIT_LINE_UPDATE: &str =
+    "Split lines can only change category, payee, notes and tags, delete the split and record it again";
 
 // POST add transaction
 pub async fn handle_add_transaction(
+    user: AuthUser,
     new_trans: ClientTransaction,
     pool: DbPool,
 ) -> (Status, String) {
-    // Step 1: Validate input
-    if new_trans.email.is_empty() {
-        return (Status::BadRequest, "Invalid input".to_string());
-    }
-
-    // Step 1.1: Check if the email exists in users table
-    let (email_status, _user_email) =
-        check_email_valid(new_trans.email.clone(), pool.clone()).await;
-
-    if email_status != Status::Ok {
-        return (
-            Status::BadRequest,
-            "No user found for the provided email".to_string(),
-        );
-    }
-
-    // Step 1.2: Check if the category_id exists
+    // Step 1: Check if the category_id exists
     let (cat_status, curr_cat_id) = check_category_name(
-        new_trans.email.clone(),
+        user.email.clone(),
         new_trans.category_name.clone(),
+        false,
         pool.clone(),
     )
     .await;
@@ -48,10 +56,11 @@ pub async fn handle_add_transaction(
         );
     }
 
-    // Step 1.3: Check if account_id exists
+    // Step 1.1: Check if account_id exists
     let (acc_status, curr_acc_id) = check_account_name(
-        new_trans.email.clone(),
+        user.email.clone(),
         new_trans.account_name.clone(),
+        false,
         pool.clone(),
     )
     .await;
@@ -62,159 +71,428 @@ pub async fn handle_add_transaction(
             "No account found for the provided email".to_string(),
         );
     }
+    // Step 1.2: use the date from the receipt if the client sent one
+    let trans_date = match new_trans.transaction_date.as_deref() {
+        Some(date_str) if !date_str.trim().is_empty() => match parse_client_date(date_str) {
+            Some(parsed_date) => parsed_date,
+            None => return (Status::BadRequest, "Invalid transaction date".to_string()),
+        },
+        _ => Utc::now(),
+    };
+
+    // Step 1.3: a currency other than the account's, if the client sent one
+    let trans_currency = match new_trans.currency.as_deref() {
+        Some(code) if !code.trim().is_empty() => match normalize_currency(code) {
+            Some(valid_code) => Some(valid_code),
+            None => return (Status::BadRequest, "Invalid currency code".to_string()),
+        },
+        _ => None,
+    };
+
+    // Step 1.4: the tags, if the client sent some
+    let Some(tag_names) = normalize_tags(&new_trans.tags) else {
+        return (Status::BadRequest, INVALID_TAG.to_string());
+    };
+
+    // Step 1.5: the payee, if the client sent one
+    let payee_name = match new_trans.payee.as_deref() {
+        Some(raw) if !raw.trim().is_empty() => match normalize_payee(raw) {
+            Some(valid_name) => Some(valid_name),
+            None => return (Status::BadRequest, INVALID_PAYEE.to_string()),
+        },
+        _ => None,
+    };
+
     // Step 2: construct new transaction to be added
     let db_new_trans = NewTransaction {
-        email: new_trans.email.clone(),
-        category_id: curr_cat_id.clone(),
-        amount: new_trans.amount.clone(),
+        email: user.email.clone(),
+        category_id: curr_cat_id,
+        amount: new_trans.amount,
         notes: new_trans.notes.clone(),
-        account_id: curr_acc_id.clone(),
-        transaction_date: Utc::now().to_string(),
+        account_id: curr_acc_id,
+        transaction_date: trans_date,
+        currency: trans_currency,
+        payee_id: None,
     };
 
-    // Step 3: add new transaction to DB
+    // Step 3: add new transaction with its payee and tags to DB, then check the category's
+    // budget alerts
     let result = tokio::task::spawn_blocking({
         let pool = pool.clone();
+        let mut db_new_trans = db_new_trans;
         move || {
             let mut conn = pool.get().expect("Failed to get database connection");
-            let inserted_row: Result<Vec<i32>, diesel::result::Error> =
-                diesel::insert_into(transactions)
+            let inserted_row = conn.transaction(|conn| {
+                if let Some(payee_name) = payee_name.as_deref() {
+                    db_new_trans.payee_id =
+                        Some(find_or_create_payee(conn, &db_new_trans.email, payee_name)?);
+                }
+                let row_ids: Vec<i32> = diesel::insert_into(transactions)
                     .values(&db_new_trans) // Use one copy of the NewTransaction
                     .returning(trans_id)
-                    .get_results(&mut conn);
+                    .get_results(conn)?;
+                set_transaction_tags(conn, &db_new_trans.email, row_ids[0], &tag_names)?;
+                Ok::<_, diesel::result::Error>(row_ids)
+            });
             match inserted_row {
-                Ok(row_ids) => return (Status::Created, row_ids[0].to_string()),
-                Err(_) => return (Status::InternalServerError, "Database error".to_string()),
+                Ok(row_ids) => {
+                    // the transaction is already saved, a failed alert check does not undo it
+                    if let Err(e) = record_budget_alerts(
+                        &mut conn,
+                        &db_new_trans.email,
+                        db_new_trans.category_id,
+                        db_new_trans.transaction_date,
+                    ) {
+                        eprintln!("Error recording budget alerts: {:?}", e);
+                    }
+                    (Status::Created, row_ids[0].to_string())
+                }
+                Err(_) => (Status::InternalServerError, "Database error".to_string()),
             }
         }
     })
     .await;
 
     match result {
-        Err(_) => return (Status::InternalServerError, "Database error".to_string()),
-        Ok(db_res) => return db_res,
+        Err(_) => (Status::InternalServerError, "Database error".to_string()),
+        Ok(db_res) => db_res,
     }
 }
 
 // DELETE delete transaction
-pub async fn handle_delete_transaction(tx_id: String, pool: DbPool) -> (Status, &'static str) {
-    // Check if email is empty or account_name is empty
-    if tx_id.is_empty() {
-        return (Status::BadRequest, "Invalid input");
-    }
+pub async fn handle_delete_transaction(
+    user: AuthUser,
+    tx_id: String,
+    pool: DbPool,
+) -> (Status, &'static str) {
+    // Step 1: make sure the transaction belongs to the caller
+    let owned_trans = match check_transaction_owner(user.email.clone(), tx_id, pool.clone()).await
+    {
+        Ok(owned_trans) => owned_trans,
+        Err(err) => return err,
+    };
 
-    let tx_id_int: i32 = tx_id.parse::<i32>().unwrap();
-    // Check if transaction ID exists
-    let tx_exists = tokio::task::spawn_blocking({
+    // Step 2: delete it, still scoped to the caller
+    // deleting either leg of a transfer deletes the transfer, its legs go with it,
+    // and deleting a line of a split deletes the whole split the same way
+    let deletion_result = tokio::task::spawn_blocking({
         let pool = pool.clone();
+        let tx_id_int = owned_trans.trans_id;
+        let email_to_check = user.email.clone();
         move || {
             let mut conn = pool.get().expect("Failed to get database connection");
-            transactions
-                .filter(trans_id.eq(tx_id_int))
-                .first::<Transaction>(&mut conn)
-                .optional()
+            match (owned_trans.transfer_id, owned_trans.split_id) {
+                (Some(leg_transfer_id), _) => diesel::delete(
+                    transfers
+                        .filter(crate::schema::transfers::dsl::transfer_id.eq(leg_transfer_id))
+                        .filter(crate::schema::transfers::dsl::email.eq(email_to_check)),
+                )
+                .execute(&mut conn),
+                (None, Some(line_split_id)) => diesel::delete(
+                    splits
+                        .filter(crate::schema::splits::dsl::split_id.eq(line_split_id))
+                        .filter(crate::schema::splits::dsl::email.eq(email_to_check)),
+                )
+                .execute(&mut conn),
+                (None, None) => diesel::delete(
+                    transactions
+                        .filter(trans_id.eq(tx_id_int))
+                        .filter(crate::schema::transactions::dsl::email.eq(email_to_check)),
+                )
+                .execute(&mut conn),
+            }
         }
     })
     .await;
 
-    match tx_exists {
-        Ok(Ok(None)) => {
-            // No transaction found for this ID
-            return (
-                Status::BadRequest,
-                "No transaction found for the provided ID",
-            );
+    match deletion_result {
+        Ok(Ok(rows_deleted)) => {
+            if rows_deleted > 0 {
+                (Status::Ok, "Transaction successfully deleted")
+            } else {
+                (
+                    Status::InternalServerError,
+                    "Failed to delete the transaction",
+                )
+            }
         }
-        Ok(Ok(Some(_))) => {
-            // Transaction found, proceed with transaction deletion
-            let deletion_result = tokio::task::spawn_blocking({
-                let pool = pool.clone();
-                move || {
-                    let mut conn = pool.get().expect("Failed to get database connection");
-                    diesel::delete(transactions.filter(trans_id.eq(tx_id_int))).execute(&mut conn)
-                }
-            })
-            .await;
-
-            match deletion_result {
-                Ok(Ok(rows_deleted)) => {
-                    if rows_deleted > 0 {
-                        (Status::Ok, "Transaction successfully deleted")
-                    } else {
-                        (
-                            Status::InternalServerError,
-                            "Failed to delete the transaction",
-                        )
+        Ok(Err(e)) => {
+            eprintln!("Error during deletion: {:?}", e);
+            (
+                Status::InternalServerError,
+                "Database error during deletion",
+            )
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed during deletion: {:?}", e);
+            (Status::InternalServerError, "Internal server error")
+        }
+    }
+}
+
+// PATCH update transaction
+pub async fn handle_update_transaction(
+    user: AuthUser,
+    tx_id: String,
+    changes: ClientTransactionUpdate,
+    pool: DbPool,
+) -> (Status, &'static str) {
+    // Step 1: make sure the transaction belongs to the caller
+    let owned_trans = match check_transaction_owner(user.email.clone(), tx_id, pool.clone()).await
+    {
+        Ok(owned_trans) => owned_trans,
+        Err(err) => return err,
+    };
+    // the two legs of a transfer only change together
+    if owned_trans.transfer_id.is_some() {
+        return (Status::BadRequest, TRANSFER_LEG_UPDATE);
+    }
+    // the lines of a split have to keep adding up to it
+    let moves_split_line = changes.amount.is_some()
+        || changes.account_name.is_some()
+        || changes.transaction_date.is_some()
+        || changes.currency.is_some();
+    if owned_trans.split_id.is_some() && moves_split_line {
+        return (Status::BadRequest, SPLIT_LINE_UPDATE);
+    }
+
+    // Step 2: resolve category and account names the same way add_trans does
+    let new_cat_id = match changes.category_name {
+        Some(cat_name) => {
+            let (cat_status, curr_cat_id) =
+                check_category_name(user.email.clone(), cat_name, false, pool.clone()).await;
+            if cat_status != Status::Ok {
+                return (Status::BadRequest, "No category found for the provided email");
+            }
+            Some(curr_cat_id)
+        }
+        None => None,
+    };
+    if let (Some(line_split_id), Some(line_cat_id)) = (owned_trans.split_id, new_cat_id) {
+        if let Err(err) = check_split_line_category(
+            line_split_id,
+            owned_trans.trans_id,
+            line_cat_id,
+            pool.clone(),
+        )
+        .await
+        {
+            return err;
+        }
+    }
+
+    let new_acc_id = match changes.account_name {
+        Some(acc_name) => {
+            let (acc_status, curr_acc_id) =
+                check_account_name(user.email.clone(), acc_name, false, pool.clone()).await;
+            if acc_status != Status::Ok {
+                return (Status::BadRequest, "No account found for the provided email");
+            }
+            Some(curr_acc_id)
+        }
+        None => None,
+    };
+
+    let new_date = match changes.transaction_date {
+        Some(date_str) => match parse_client_date(&date_str) {
+            Some(parsed_date) => Some(parsed_date),
+            None => return (Status::BadRequest, "Invalid transaction date"),
+        },
+        None => None,
+    };
+
+    let new_currency = match changes.currency {
+        Some(code) if code.trim().is_empty() => Some(None),
+        Some(code) => match normalize_currency(&code) {
+            Some(valid_code) => Some(Some(valid_code)),
+            None => return (Status::BadRequest, "Invalid currency code"),
+        },
+        None => None,
+    };
+
+    let new_notes = changes
+        .notes
+        .map(|raw| Some(raw).filter(|raw| !raw.trim().is_empty()));
+
+    let new_tags = match changes.tags {
+        Some(raw_tags) => match normalize_tags(&raw_tags) {
+            Some(tag_names) => Some(tag_names),
+            None => return (Status::BadRequest, INVALID_TAG),
+        },
+        None => None,
+    };
+
+    let new_payee = match changes.payee {
+        Some(raw) if raw.trim().is_empty() => Some(None),
+        Some(raw) => match normalize_payee(&raw) {
+            Some(valid_name) => Some(Some(valid_name)),
+            None => return (Status::BadRequest, INVALID_PAYEE),
+        },
+        None => None,
+    };
+
+    let mut db_changes = TransactionChanges {
+        category_id: new_cat_id,
+        amount: changes.amount,
+        notes: new_notes,
+        account_id: new_acc_id,
+        transaction_date: new_date,
+        currency: new_currency,
+        payee_id: None,
+    };
+
+    if db_changes.is_empty() && new_tags.is_none() && new_payee.is_none() {
+        return (Status::BadRequest, "No fields to update");
+    }
+
+    // Step 3: apply the changes, still scoped to the caller, then check budget alerts
+    // for wherever the transaction ends up
+    let alert_cat_id = db_changes.category_id.or(owned_trans.category_id);
+    let alert_date = db_changes
+        .transaction_date
+        .unwrap_or(owned_trans.transaction_date);
+    let update_result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        let tx_id_int = owned_trans.trans_id;
+        let email_to_check = user.email.clone();
+        move || {
+            let mut conn = pool.get().expect("Failed to get database connection");
+            let rows_updated = conn.transaction(|conn| {
+                db_changes.payee_id = match new_payee {
+                    Some(Some(payee_name)) => {
+                        Some(Some(find_or_create_payee(conn, &email_to_check, &payee_name)?))
                     }
-                }
-                Ok(Err(e)) => {
-                    eprintln!("Error during deletion: {:?}", e);
-                    (
-                        Status::InternalServerError,
-                        "Database error during deletion",
+                    Some(None) => Some(None),
+                    None => None,
+                };
+                // a tags-only update leaves the row itself as is
+                let rows_updated = if db_changes.is_empty() {
+                    1
+                } else {
+                    diesel::update(
+                        transactions
+                            .filter(trans_id.eq(tx_id_int))
+                            .filter(crate::schema::transactions::dsl::email.eq(&email_to_check)),
                     )
+                    .set(&db_changes)
+                    .execute(conn)?
+                };
+                if let (true, Some(tag_names)) = (rows_updated > 0, &new_tags) {
+                    set_transaction_tags(conn, &email_to_check, tx_id_int, tag_names)?;
                 }
-                Err(e) => {
-                    eprintln!("Blocking task failed during deletion: {:?}", e);
-                    (Status::InternalServerError, "Internal server error")
+                Ok::<_, diesel::result::Error>(rows_updated)
+            })?;
+            if let (true, Some(alert_cat_id)) = (rows_updated > 0, alert_cat_id) {
+                if let Err(e) =
+                    record_budget_alerts(&mut conn, &email_to_check, alert_cat_id, alert_date)
+                {
+                    eprintln!("Error recording budget alerts: {:?}", e);
                 }
             }
+            Ok::<_, diesel::result::Error>(rows_updated)
+        }
+    })
+    .await;
+
+    match update_result {
+        Ok(Ok(rows_updated)) => {
+            if rows_updated > 0 {
+                (Status::Ok, "Transaction successfully updated")
+            } else {
+                (
+                    Status::InternalServerError,
+                    "Failed to update the transaction",
+                )
+            }
         }
         Ok(Err(e)) => {
-            eprintln!("Error checking user existence: {:?}", e);
-            return (Status::InternalServerError, "Database error");
+            eprintln!("Error during transaction update: {:?}", e);
+            (Status::InternalServerError, "Database error during update")
         }
         Err(e) => {
-            eprintln!("Blocking task failed during user check: {:?}", e);
-            return (Status::InternalServerError, "Internal server error");
+            eprintln!("Blocking task failed during transaction update: {:?}", e);
+            (Status::InternalServerError, "Internal server error")
         }
     }
 }
 
 // Some helper functions
-pub async fn check_email_valid(email_str: String, pool: DbPool) -> (Status, String) {
-    let user_exists = tokio::task::spawn_blocking({
+
+// Date-time layouts accepted without an offset, read as UTC
+const CLIENT_DATETIME_FORMATS: [&str; 4] = [
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+];
+// Day-only layouts, read as midnight UTC
+const CLIENT_DATE_FORMATS: [&str; 3] = ["%Y-%m-%d", "%Y/%m/%d", "%Y%m%d"];
+
+// Dates from the client: ISO 8601 / RFC 3339 timestamps, the `... UTC` strings we store,
+// naive date-times (UTC) or plain days (midnight UTC)
+pub fn parse_client_date(date_str: &str) -> Option<DateTime<Utc>> {
+    let date_str = date_str.trim();
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(date_str) {
+        return Some(parsed.with_timezone(&Utc));
+    }
+    if let Ok(parsed) = date_str.parse::<DateTime<Utc>>() {
+        return Some(parsed);
+    }
+    for format in CLIENT_DATETIME_FORMATS {
+        if let Ok(parsed) = NaiveDateTime::parse_from_str(date_str, format) {
+            return Some(parsed.and_utc());
+        }
+    }
+    CLIENT_DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(date_str, format).ok())
+        .and_then(|day| day.and_hms_opt(0, 0, 0))
+        .map(|midnight| midnight.and_utc())
+}
+
+// Every transaction mutation goes through this check first.
+// Ids owned by other users are reported exactly like ids that do not exist.
+pub async fn check_transaction_owner(
+    email_str: String,
+    tx_id: String,
+    pool: DbPool,
+) -> Result<Transaction, (Status, &'static str)> {
+    let tx_id_int = match tx_id.trim().parse::<i32>() {
+        Ok(tx_id_int) => tx_id_int,
+        Err(_) => return Err((Status::BadRequest, "Invalid transaction ID")),
+    };
+
+    let tx_exists = tokio::task::spawn_blocking({
         let pool = pool.clone();
-        let email_to_check = email_str.clone();
         move || {
             let mut conn = pool.get().expect("Failed to get database connection");
-            users
-                .filter(user_email.eq(email_to_check))
-                .first::<User>(&mut conn)
+            transactions
+                .filter(trans_id.eq(tx_id_int))
+                .filter(crate::schema::transactions::dsl::email.eq(email_str))
+                .first::<Transaction>(&mut conn)
                 .optional()
         }
     })
     .await;
 
-    match user_exists {
-        Ok(Ok(None)) => {
-            // No user found for this email
-            return (
-                Status::BadRequest,
-                "No user found for the provided email".to_string(),
-            );
-        }
-        Ok(Ok(Some(user))) => {
-            // User found, proceed to category name existence check
-            return (Status::Ok, user.email);
-        }
+    match tx_exists {
+        Ok(Ok(Some(owned_trans))) => Ok(owned_trans),
+        Ok(Ok(None)) => Err((Status::NotFound, "No transaction found for the provided ID")),
         Ok(Err(e)) => {
-            eprintln!("Error checking user existence: {:?}", e);
-            return (Status::InternalServerError, "Database error".to_string());
+            eprintln!("Error checking transaction ownership: {:?}", e);
+            Err((Status::InternalServerError, "Database error"))
         }
         Err(e) => {
-            eprintln!("Blocking task failed during user check: {:?}", e);
-            return (
-                Status::InternalServerError,
-                "Internal server error".to_string(),
-            );
+            eprintln!("Blocking task failed during transaction check: {:?}", e);
+            Err((Status::InternalServerError, "Internal server error"))
         }
     }
 }
-
+// Pickers leave archived categories out, transaction lists still find them
 pub async fn check_category_name(
     email_str: String,
     cat_name: String,
+    include_archived: bool,
     pool: DbPool,
 ) -> (Status, i32) {
     // If category is empty, return bad request
@@ -229,38 +507,43 @@ pub async fn check_category_name(
         let email_to_check = email_str.clone();
         move || {
             let mut conn = pool.get().expect("Failed to get database connection");
-            categories
+            let mut query = categories
                 .filter(crate::schema::categories::dsl::email.eq(email_to_check))
                 .filter(nickname.eq(cat_to_check))
-                .first::<Category>(&mut conn)
-                .optional()
+                .into_boxed();
+            if !include_archived {
+                query = query.filter(crate::schema::categories::dsl::archived_at.is_null());
+            }
+            query.first::<Category>(&mut conn).optional()
         }
     })
     .await;
 
-    let _ = match category_exists {
+    match category_exists {
         Ok(Ok(None)) => {
             // No category found for this name
-            return (Status::BadRequest, 0);
+            (Status::BadRequest, 0)
         }
         Ok(Ok(Some(match_category))) => {
             // User found, proceed to category name existence check
-            return (Status::Ok, match_category.category_id);
+            (Status::Ok, match_category.category_id)
         }
         Ok(Err(e)) => {
             eprintln!("Error checking category existence: {:?}", e);
-            return (Status::InternalServerError, 0);
+            (Status::InternalServerError, 0)
         }
         Err(e) => {
             eprintln!("Blocking task failed during user check: {:?}", e);
-            return (Status::InternalServerError, 0);
+            (Status::InternalServerError, 0)
         }
-    };
+    }
 }
 
+// Pickers leave archived accounts out, transaction lists still find them
 pub async fn check_account_name(
     email_str: String,
     acc_name: String,
+    include_archived: bool,
     pool: DbPool,
 ) -> (Status, i32) {
     let account_exists = tokio::task::spawn_blocking({
@@ -269,48 +552,51 @@ pub async fn check_account_name(
         let acc_to_check = acc_name.clone();
         move || {
             let mut conn = pool.get().expect("Failed to get database connection");
-            accounts
+            let mut query = accounts
                 .filter(crate::schema::accounts::dsl::email.eq(email_to_check))
                 .filter(account_name.eq(acc_to_check))
-                .first::<Account>(&mut conn)
-                .optional()
+                .into_boxed();
+            if !include_archived {
+                query = query.filter(crate::schema::accounts::dsl::archived_at.is_null());
+            }
+            query.first::<Account>(&mut conn).optional()
         }
     })
     .await;
 
-    let _ = match account_exists {
+    match account_exists {
         Ok(Ok(None)) => {
-            return (Status::BadRequest, 0);
+            (Status::BadRequest, 0)
         }
         Ok(Ok(Some(match_acc))) => {
-            return (Status::Ok, match_acc.account_id);
+            (Status::Ok, match_acc.account_id)
         }
         Ok(Err(e)) => {
             eprintln!("Error checking account existence: {:?}", e);
-            return (Status::InternalServerError, 0);
+            (Status::InternalServerError, 0)
         }
         Err(e) => {
             eprintln!("Blocking task failed during user check: {:?}", e);
-            return (Status::InternalServerError, 0);
+            (Status::InternalServerError, 0)
         }
-    };
+    }
 }
 
-// GET /category_trans?category_name=<>&email=<>
+// GET /category_trans?category_name=<>&tag=<>
 pub async fn handle_category_summary(
-    email_str: String,
+    user: AuthUser,
     category_name: String,
+    tag: Option<String>,
     pool: DbPool,
-) -> (Status, Json<Vec<Transaction>>) {
-    // Step 1: validate email
-    let (email_status, _user_email) = check_email_valid(email_str.clone(), pool.clone()).await;
-
-    if email_status != Status::Ok {
-        return (Status::BadRequest, Json(vec![]));
-    }
-    // Step 2: validate category_id
+) -> (Status, Json<Vec<TaggedTransaction>>) {
+    // Step 1: validate category_id and the tag filter
+    let tag_filter = match tag.as_deref().map(normalize_tag) {
+        Some(None) => return (Status::BadRequest, Json(vec![])),
+        Some(Some(tag_name)) => Some(tag_name),
+        None => None,
+    };
     let (cat_status, curr_cat_id) =
-        check_category_name(email_str, category_name, pool.clone()).await;
+        check_category_name(user.email.clone(), category_name, true, pool.clone()).await;
 
     if cat_status != Status::Ok {
         return (Status::BadRequest, Json(vec![]));
@@ -318,12 +604,34 @@ pub async fn handle_category_summary(
 
     let category_result = tokio::task::spawn_blocking({
         let pool = pool.clone();
-        let cat_to_search = curr_cat_id.clone();
+        let cat_to_search = curr_cat_id;
+        let owner = user.email.clone();
         move || {
             let mut conn = pool.get().expect("Failed to get database connection");
-            transactions
+            let mut query = transactions
                 .filter(crate::schema::transactions::dsl::category_id.eq(cat_to_search))
-                .load::<Transaction>(&mut conn)
+                .into_boxed();
+            if let Some(tag_name) = tag_filter {
+                query = query.filter(trans_id.eq_any(transactions_tagged(&mut conn, &owner, &tag_name)?));
+            }
+            let trans_list = query.load::<Transaction>(&mut conn)?;
+            // Step 2: the tags and payee of each transaction
+            let trans_ids: Vec<i32> = trans_list.iter().map(|trans| trans.trans_id).collect();
+            let mut tags_of = load_transaction_tags(&mut conn, &trans_ids)?;
+            let used_payee_ids: Vec<i32> =
+                trans_list.iter().filter_map(|trans| trans.payee_id).collect();
+            let payee_names = load_payee_names(&mut conn, &used_payee_ids)?;
+            let tagged_trans = trans_list
+                .into_iter()
+                .map(|trans| TaggedTransaction {
+                    tags: tags_of.remove(&trans.trans_id).unwrap_or_default(),
+                    payee: trans
+                        .payee_id
+                        .and_then(|used_payee_id| payee_names.get(&used_payee_id).cloned()),
+                    transaction: trans,
+                })
+                .collect::<Vec<TaggedTransaction>>();
+            Ok::<_, diesel::result::Error>(tagged_trans)
         }
     })
     .await;
@@ -347,20 +655,20 @@ pub async fn handle_category_summary(
     }
 }
 
-// GET /account_trans?account_name=<>&email=<>
+// GET /account_trans?account_name=<>&tag=<>
 pub async fn handle_account_summary(
-    email_str: String,
+    user: AuthUser,
     acc_name: String,
+    tag: Option<String>,
     pool: DbPool,
-) -> (Status, Json<Vec<Transaction>>) {
-    // Step 1: validate email
-    let (email_status, _user_email) = check_email_valid(email_str.clone(), pool.clone()).await;
-
-    if email_status != Status::Ok {
-        return (Status::BadRequest, Json(vec![]));
-    }
-    // Step 2: validate account name
-    let (acc_status, curr_acc_id) = check_account_name(email_str, acc_name, pool.clone()).await;
+) -> (Status, Json<Vec<AccountTransaction>>) {
+    // Step 1: validate account name and the tag filter
+    let tag_filter = match tag.as_deref().map(normalize_tag) {
+        Some(None) => return (Status::BadRequest, Json(vec![])),
+        Some(Some(tag_name)) => Some(tag_name),
+        None => None,
+    };
+    let (acc_status, curr_acc_id) = check_account_name(user.email.clone(), acc_name, true, pool.clone()).await;
 
     if acc_status != Status::Ok {
         return (Status::BadRequest, Json(vec![]));
@@ -368,12 +676,52 @@ pub async fn handle_account_summary(
 
     let account_result = tokio::task::spawn_blocking({
         let pool = pool.clone();
-        let acc_to_search = curr_acc_id.clone();
+        let acc_to_search = curr_acc_id;
+        let owner = user.email.clone();
         move || {
             let mut conn = pool.get().expect("Failed to get database connection");
-            transactions
+            let mut query = transactions
                 .filter(crate::schema::transactions::dsl::account_id.eq(acc_to_search))
-                .load::<Transaction>(&mut conn)
+                .into_boxed();
+            if let Some(tag_name) = tag_filter {
+                query = query.filter(trans_id.eq_any(transactions_tagged(&mut conn, &owner, &tag_name)?));
+            }
+            let trans_list = query.load::<Transaction>(&mut conn)?;
+            // Step 2: the account on the other side of each transfer
+            let leg_transfer_ids: Vec<i32> =
+                trans_list.iter().filter_map(|trans| trans.transfer_id).collect();
+            let counterparts: HashMap<i32, String> = transactions
+                .inner_join(accounts)
+                .filter(transfer_id.eq_any(leg_transfer_ids))
+                .filter(crate::schema::transactions::dsl::account_id.ne(acc_to_search))
+                .select((transfer_id.assume_not_null(), account_name))
+                .load::<(i32, String)>(&mut conn)?
+                .into_iter()
+                .collect();
+            // Step 3: the tags and payee of each transaction
+            let trans_ids: Vec<i32> = trans_list.iter().map(|trans| trans.trans_id).collect();
+            let mut tags_of = load_transaction_tags(&mut conn, &trans_ids)?;
+            let used_payee_ids: Vec<i32> =
+                trans_list.iter().filter_map(|trans| trans.payee_id).collect();
+            let payee_names = load_payee_names(&mut conn, &used_payee_ids)?;
+            let account_trans = trans_list
+                .into_iter()
+                .map(|trans| AccountTransaction {
+                    kind: match trans.transfer_id {
+                        Some(_) => "transfer".to_string(),
+                        None => "transaction".to_string(),
+                    },
+                    counterpart_account: trans
+                        .transfer_id
+                        .and_then(|leg_transfer_id| counterparts.get(&leg_transfer_id).cloned()),
+                    tags: tags_of.remove(&trans.trans_id).unwrap_or_default(),
+                    payee: trans
+                        .payee_id
+                        .and_then(|used_payee_id| payee_names.get(&used_payee_id).cloned()),
+                    transaction: trans,
+                })
+                .collect::<Vec<AccountTransaction>>();
+            Ok::<_, diesel::result::Error>(account_trans)
         }
     })
     .await;
@@ -396,3 +744,334 @@ pub async fn handle_account_summary(
         }
     }
 }
+
+// Page size of /transactions unless the client asks for another one
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 500;
+// from or to is a valid day whose start cannot be placed in time, e.g. the last day chrono knows
+const OUT_OF_RANGE_DAY: &str = "Invalid date: from and to must be within the supported range";
+
+// GET /transactions?<from>&<to>&<min_amount>&<max_amount>&<category>&<account>&<q>&<tag>&<payee>&<sort>&<limit>&<cursor>
+// One page of the caller's transactions matching every filter given.
+// The next page starts after `next_cursor`, so rows added meanwhile never shift it.
+pub async fn handle_transaction_search(
+    user: AuthUser,
+    search: TransactionSearch,
+    pool: DbPool,
+) -> Result<Json<TransactionPage>, (Status, String)> {
+    let bad_request = |message: &str| (Status::BadRequest, message.to_string());
+
+    // Step 1: validate the filters that need no lookup
+    let sort = match search.sort.as_deref() {
+        Some(sort_name) => TransactionSort::parse(sort_name).ok_or_else(|| {
+            bad_request("Invalid sort: expected date_desc, date_asc, amount_desc or amount_asc")
+        })?,
+        None => TransactionSort::DateDesc,
+    };
+    let cursor = match search.cursor.as_deref() {
+        Some(raw) => {
+            Some(SearchCursor::parse(raw, sort).ok_or_else(|| bad_request("Invalid cursor"))?)
+        }
+        None => None,
+    };
+    let page_size = match search.limit.as_deref() {
+        Some(limit_str) => match limit_str.trim().parse::<i64>() {
+            Ok(page_size) if (1..=MAX_PAGE_SIZE).contains(&page_size) => page_size,
+            _ => return Err(bad_request("Invalid limit: expected 1 to 500")),
+        },
+        None => DEFAULT_PAGE_SIZE,
+    };
+    let parse_amount = |raw: Option<&str>| match raw {
+        Some(amount_str) => amount_str
+            .trim()
+            .parse::<Decimal>()
+            .map(Some)
+            .map_err(|_| bad_request("Invalid amount")),
+        None => Ok(None),
+    };
+    let min_amount = parse_amount(search.min_amount.as_deref())?;
+    let max_amount = parse_amount(search.max_amount.as_deref())?;
+    if let (Some(min), Some(max)) = (min_amount, max_amount) {
+        if min > max {
+            return Err(bad_request(
+                "Invalid amount range: min_amount is above max_amount",
+            ));
+        }
+    }
+    let parse_day = |raw: Option<&str>| match raw {
+        Some(day_str) => NaiveDate::parse_from_str(day_str.trim(), "%Y-%m-%d")
+            .map(Some)
+            .map_err(|_| bad_request("Invalid date: from and to must be YYYY-MM-DD days")),
+        None => Ok(None),
+    };
+    let first_day = parse_day(search.from.as_deref())?;
+    let last_day = parse_day(search.to.as_deref())?;
+    if let (Some(first), Some(last)) = (first_day, last_day) {
+        if first > last {
+            return Err(bad_request("Invalid date range: from is after to"));
+        }
+    }
+    let tag_filter = match search.tag.as_deref() {
+        Some(raw) => Some(normalize_tag(raw).ok_or_else(|| bad_request(INVALID_TAG))?),
+        None => None,
+    };
+    let note_filter = search
+        .q
+        .as_deref()
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(|text| format!("%{}%", escape_like(text)));
+
+    // Step 2: resolve the names among the caller's, archived ones included, then load
+    // one row more than the page to know whether another page follows
+    let search_result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        let owner = user.email.clone();
+        move || {
+            use crate::schema::accounts::dsl as acc;
+            use crate::schema::categories::dsl as cat;
+            use crate::schema::transactions::dsl as trans;
+
+            let mut conn = pool.get().expect("Failed to get database connection");
+            let calendar = BudgetCalendar::load(&mut conn, &owner)?;
+            let mut query = trans::transactions
+                .filter(trans::email.eq(&owner))
+                .into_boxed();
+
+            if let Some(first) = first_day {
+                let Some(start) = calendar.local_midnight(first) else {
+                    return Ok(Err(OUT_OF_RANGE_DAY.to_string()));
+                };
+                query = query.filter(trans::transaction_date.ge(start.with_timezone(&Utc)));
+            }
+            if let Some(last) = last_day {
+                let Some(end) = last
+                    .succ_opt()
+                    .and_then(|next_day| calendar.local_midnight(next_day))
+                else {
+                    return Ok(Err(OUT_OF_RANGE_DAY.to_string()));
+                };
+                query = query.filter(trans::transaction_date.lt(end.with_timezone(&Utc)));
+            }
+            if let Some(min) = min_amount {
+                query = query.filter(trans::amount.ge(min));
+            }
+            if let Some(max) = max_amount {
+                query = query.filter(trans::amount.le(max));
+            }
+            if !search.categories.is_empty() {
+                let cat_ids: HashMap<String, i32> = cat::categories
+                    .filter(cat::email.eq(&owner))
+                    .filter(cat::nickname.eq_any(&search.categories))
+                    .select((cat::nickname, cat::category_id))
+                    .load::<(String, i32)>(&mut conn)?
+                    .into_iter()
+                    .collect();
+                if let Some(missing) = search.categories.iter().find(|n| !cat_ids.contains_key(*n))
+                {
+                    return Ok(Err(format!(
+                        "No category found for the provided email: {}",
+                        missing
+                    )));
+                }
+                query = query
+                    .filter(trans::category_id.eq_any(cat_ids.into_values().collect::<Vec<i32>>()));
+            }
+            if !search.accounts.is_empty() {
+                let acc_ids: HashMap<String, i32> = acc::accounts
+                    .filter(acc::email.eq(&owner))
+                    .filter(acc::account_name.eq_any(&search.accounts))
+                    .select((acc::account_name, acc::account_id))
+                    .load::<(String, i32)>(&mut conn)?
+                    .into_iter()
+                    .collect();
+                if let Some(missing) = search.accounts.iter().find(|n| !acc_ids.contains_key(*n)) {
+                    return Ok(Err(format!(
+                        "No account found for the provided email: {}",
+                        missing
+                    )));
+                }
+                query = query
+                    .filter(trans::account_id.eq_any(acc_ids.into_values().collect::<Vec<i32>>()));
+            }
+            // a substring match, served by the trigram index on the notes
+            if let Some(pattern) = note_filter {
+                query = query.filter(trans::notes.ilike(pattern));
+            }
+            if let Some(tag_name) = tag_filter {
+                query = query.filter(
+                    trans::trans_id.eq_any(transactions_tagged(&mut conn, &owner, &tag_name)?),
+                );
+            }
+            if let Some(payee_name) = search.payee.as_deref() {
+                let Some(found) = find_payee(&mut conn, &owner, payee_name)? else {
+                    return Ok(Err("No payee found for the provided email".to_string()));
+                };
+                query = query.filter(trans::payee_id.eq(found.payee_id));
+            }
+
+            query = match (sort, cursor) {
+                (TransactionSort::DateDesc, Some(SearchCursor::Date(at, last_id))) => query.filter(
+                    trans::transaction_date.lt(at).or(trans::transaction_date
+                        .eq(at)
+                        .and(trans::trans_id.lt(last_id))),
+                ),
+                (TransactionSort::DateAsc, Some(SearchCursor::Date(at, last_id))) => query.filter(
+                    trans::transaction_date.gt(at).or(trans::transaction_date
+                        .eq(at)
+                        .and(trans::trans_id.gt(last_id))),
+                ),
+                (TransactionSort::AmountDesc, Some(SearchCursor::Amount(key, last_id))) => query
+                    .filter(
+                        trans::amount
+                            .lt(key)
+                            .or(trans::amount.eq(key).and(trans::trans_id.lt(last_id))),
+                    ),
+                (TransactionSort::AmountAsc, Some(SearchCursor::Amount(key, last_id))) => query
+                    .filter(
+                        trans::amount
+                            .gt(key)
+                            .or(trans::amount.eq(key).and(trans::trans_id.gt(last_id))),
+                    ),
+                _ => query,
+            };
+            query = match sort {
+                TransactionSort::DateDesc => {
+                    query.order_by((trans::transaction_date.desc(), trans::trans_id.desc()))
+                }
+                TransactionSort::DateAsc => {
+                    query.order_by((trans::transaction_date.asc(), trans::trans_id.asc()))
+                }
+                TransactionSort::AmountDesc => {
+                    query.order_by((trans::amount.desc(), trans::trans_id.desc()))
+                }
+                TransactionSort::AmountAsc => {
+                    query.order_by((trans::amount.asc(), trans::trans_id.asc()))
+                }
+            };
+            let mut trans_list = query.limit(page_size + 1).load::<Transaction>(&mut conn)?;
+            let next_cursor = if trans_list.len() as i64 > page_size {
+                trans_list.truncate(page_size as usize);
+                trans_list
+                    .last()
+                    .map(|last| SearchCursor::after(sort, last).encode(sort))
+            } else {
+                None
+            };
+
+            // Step 3: the names, tags and payee of each row of the page
+            let page_cat_ids: Vec<i32> = trans_list.iter().filter_map(|t| t.category_id).collect();
+            let cat_names: HashMap<i32, String> = cat::categories
+                .filter(cat::category_id.eq_any(page_cat_ids))
+                .select((cat::category_id, cat::nickname))
+                .load::<(i32, String)>(&mut conn)?
+                .into_iter()
+                .collect();
+            let page_acc_ids: Vec<i32> = trans_list.iter().map(|t| t.account_id).collect();
+            let acc_names: HashMap<i32, String> = acc::accounts
+                .filter(acc::account_id.eq_any(page_acc_ids))
+                .select((acc::account_id, acc::account_name))
+                .load::<(i32, String)>(&mut conn)?
+                .into_iter()
+                .collect();
+            let trans_ids: Vec<i32> = trans_list.iter().map(|t| t.trans_id).collect();
+            let mut tags_of = load_transaction_tags(&mut conn, &trans_ids)?;
+            let used_payee_ids: Vec<i32> = trans_list.iter().filter_map(|t| t.payee_id).collect();
+            let payee_names = load_payee_names(&mut conn, &used_payee_ids)?;
+            let rows = trans_list
+                .into_iter()
+                .map(|trans| SearchTransaction {
+                    category_name: trans
+                        .category_id
+                        .and_then(|row_cat_id| cat_names.get(&row_cat_id).cloned()),
+                    account_name: acc_names
+                        .get(&trans.account_id)
+                        .cloned()
+                        .unwrap_or_default(),
+                    tags: tags_of.remove(&trans.trans_id).unwrap_or_default(),
+                    payee: trans
+                        .payee_id
+                        .and_then(|used_payee_id| payee_names.get(&used_payee_id).cloned()),
+                    transaction: trans,
+                })
+                .collect::<Vec<SearchTransaction>>();
+            Ok::<_, diesel::result::Error>(Ok(TransactionPage {
+                transactions: rows,
+                next_cursor,
+            }))
+        }
+    })
+    .await;
+
+    match search_result {
+        Ok(Ok(Ok(page))) => Ok(Json(page)),
+        Ok(Ok(Err(rejected))) => Err((Status::BadRequest, rejected)),
+        Ok(Err(e)) => {
+            eprintln!("Database error during transaction search: {:?}", e);
+            Err((Status::InternalServerError, "Database error".to_string()))
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed during transaction search: {:?}", e);
+            Err((
+                Status::InternalServerError,
+                "Internal server error".to_string(),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_client_date;
+    use chrono::{DateTime, Utc};
+
+    fn date(raw: &str) -> Option<DateTime<Utc>> {
+        parse_client_date(raw)
+    }
+
+    fn at(text: &str) -> Option<DateTime<Utc>> {
+        Some(text.parse().unwrap())
+    }
+
+    #[test]
+    fn reads_timestamps_with_an_offset() {
+        assert_eq!(date("2026-10-18T12:30:00Z"), at("2026-10-18T12:30:00Z"));
+        assert_eq!(
+            date("2026-10-18T08:30:00-04:00"),
+            at("2026-10-18T12:30:00Z")
+        );
+        assert_eq!(
+            date("2026-10-18T12:30:00.250Z"),
+            at("2026-10-18T12:30:00.250Z")
+        );
+        assert_eq!(date("2026-10-18 12:30:00 UTC"), at("2026-10-18T12:30:00Z"));
+    }
+
+    #[test]
+    fn reads_naive_date_times_as_utc() {
+        assert_eq!(date("2026-10-18T12:30:00"), at("2026-10-18T12:30:00Z"));
+        assert_eq!(
+            date("2026-10-18 12:30:00.5"),
+            at("2026-10-18T12:30:00.500Z")
+        );
+        assert_eq!(date("2026-10-18T12:30"), at("2026-10-18T12:30:00Z"));
+        assert_eq!(date("2026-10-18 12:30"), at("2026-10-18T12:30:00Z"));
+    }
+
+    #[test]
+    fn reads_days_as_midnight_utc() {
+        assert_eq!(date("2026-10-18"), at("2026-10-18T00:00:00Z"));
+        assert_eq!(date("2026/10/18"), at("2026-10-18T00:00:00Z"));
+        assert_eq!(date("20261018"), at("2026-10-18T00:00:00Z"));
+        assert_eq!(date(" 2026-10-18 "), at("2026-10-18T00:00:00Z"));
+    }
+
+    #[test]
+    fn rejects_impossible_or_unknown_dates() {
+        assert_eq!(date("2026-02-30"), None);
+        assert_eq!(date("2026-10-18T25:00"), None);
+        assert_eq!(date("18/10/2026"), None);
+        assert_eq!(date("yesterday"), None);
+        assert_eq!(date(""), None);
+    }
+}
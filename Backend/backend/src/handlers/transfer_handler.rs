@@ -0,0 +1,131 @@
+use crate::currency::RateTable;
+use crate::db::DbPool;
+use crate::guards::AuthUser;
+use crate::handlers::transaction_handler::parse_client_date;
+use crate::models::account::Account;
+use crate::models::transfer::{ClientTransfer, NewTransfer, NewTransferLeg};
+use crate::schema::accounts::dsl::{account_name, accounts, archived_at, email};
+use crate::schema::transactions::dsl::transactions;
+use crate::schema::transfers::dsl::{transfer_id, transfers};
+use chrono::Utc;
+use diesel::prelude::*;
+use rocket::http::Status;
+
+// POST /transfer
+// Moves money between two of the caller's accounts as two linked transactions without
+// a category, so it counts neither as income nor as spending
+pub async fn handle_create_transfer(
+    user: AuthUser,
+    client_transfer: ClientTransfer,
+    pool: DbPool,
+) -> (Status, String) {
+    // Step 1: validate input
+    if client_transfer.from_account == client_transfer.to_account {
+        return (
+            Status::BadRequest,
+            "A transfer needs two different accounts".to_string(),
+        );
+    }
+    let to_amount_invalid = client_transfer
+        .to_amount
+        .is_some_and(|to_amount| to_amount <= rust_decimal::Decimal::ZERO);
+    if client_transfer.amount <= rust_decimal::Decimal::ZERO || to_amount_invalid {
+        return (
+            Status::BadRequest,
+            "Transfer amount must be positive".to_string(),
+        );
+    }
+    let trans_date = match client_transfer.transaction_date.as_deref() {
+        Some(date_str) if !date_str.trim().is_empty() => match parse_client_date(date_str) {
+            Some(parsed_date) => parsed_date,
+            None => return (Status::BadRequest, "Invalid transaction date".to_string()),
+        },
+        _ => Utc::now(),
+    };
+
+    // Step 2: find both accounts, convert the amount when their currencies differ,
+    // then write the transfer and its two legs at once
+    let result = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        let owner = user.email.clone();
+        move || {
+            let mut conn = pool.get().expect("Failed to get database connection");
+            let find_account = |conn: &mut PgConnection, name: &str| {
+                accounts
+                    .filter(email.eq(&owner))
+                    .filter(account_name.eq(name))
+                    .filter(archived_at.is_null())
+                    .first::<Account>(conn)
+                    .optional()
+            };
+            let (Some(from_acc), Some(to_acc)) = (
+                find_account(&mut conn, &client_transfer.from_account)?,
+                find_account(&mut conn, &client_transfer.to_account)?,
+            ) else {
+                return Ok(Err((
+                    Status::BadRequest,
+                    "No account found for the provided email".to_string(),
+                )));
+            };
+
+            let to_amount = match client_transfer.to_amount {
+                Some(to_amount) => to_amount,
+                None if from_acc.currency == to_acc.currency => client_transfer.amount,
+                None => {
+                    let rates =
+                        RateTable::load(&mut conn, &to_acc.currency, [trans_date.date_naive()])?;
+                    match rates.convert(
+                        client_transfer.amount,
+                        &from_acc.currency,
+                        trans_date.date_naive(),
+                    ) {
+                        Ok(converted) => converted,
+                        Err(e) => return Ok(Err((Status::UnprocessableEntity, e))),
+                    }
+                }
+            };
+
+            conn.transaction(|conn| {
+                let new_transfer_id = diesel::insert_into(transfers)
+                    .values(&NewTransfer {
+                        email: owner.clone(),
+                    })
+                    .returning(transfer_id)
+                    .get_result::<i32>(conn)?;
+                let legs = [
+                    (from_acc.account_id, -client_transfer.amount),
+                    (to_acc.account_id, to_amount),
+                ]
+                .map(|(leg_account_id, leg_amount)| NewTransferLeg {
+                    email: owner.clone(),
+                    amount: leg_amount,
+                    notes: client_transfer.notes.clone(),
+                    account_id: leg_account_id,
+                    transaction_date: trans_date,
+                    transfer_id: new_transfer_id,
+                });
+                diesel::insert_into(transactions)
+                    .values(&legs[..])
+                    .execute(conn)?;
+                Ok::<_, diesel::result::Error>(Ok(new_transfer_id))
+            })
+        }
+    })
+    .await;
+
+    match result {
+        Ok(Ok(Ok(new_transfer_id))) => (Status::Created, new_transfer_id.to_string()),
+        Ok(Ok(Err(rejected))) => rejected,
+        Ok(Err(e)) => {
+            eprintln!("Database error during transfer creation: {:?}", e);
+            (Status::InternalServerError, "Database error".to_string())
+        }
+        Err(e) => {
+            eprintln!("Blocking task failed during transfer creation: {:?}", e);
+            (
+                Status::InternalServerError,
+                "Internal server error".to_string(),
+            )
+        }
+    }
+}
@@ -1,24 +1,55 @@
 #[macro_use]
 extern crate rocket;
 
+mod currency;
 mod db;
+mod guards;
 mod handlers;
 mod models;
+mod period;
 mod routes;
 mod schema;
 
 // ROUTES
-use routes::account::{account_create, account_summary, delete_account};
-use routes::auth::signup;
-use routes::category::{category_create, category_summary, category_update, delete_category};
-use routes::report::{report_details, report_overview};
-use routes::transaction::{account_summary_trans, add_trans, category_summary_trans, delete_trans};
+use routes::account::{
+    account_balance, account_balance_history, account_create, account_summary, account_update,
+    delete_account, purge_account, unarchive_account,
+};
+use routes::auth::{get_settings, login, logout, signup, update_settings};
+use routes::category::{
+    category_create, category_summary, category_update, delete_category, merge_category,
+    purge_category, unarchive_category,
+};
+use routes::exchange_rate::import_rates;
+use routes::import::{import_commit, import_preview, import_profile};
+use routes::notification::{notifications, read_notifications};
+use routes::payee::{merge_payee, payees};
+use routes::recurring::{delete_recurring, recurring_create, recurring_summary, recurring_update};
+use routes::report::{
+    report_cashflow, report_details, report_networth, report_overview, report_payees,
+    report_tags,
+};
+use routes::transaction::{
+    account_summary_trans, add_trans, category_summary_trans, delete_trans, transactions,
+    update_trans,
+};
+use routes::split::{delete_split, split_create, split_details};
+use routes::transfer::transfer;
+
+use db::DbPool;
+use rocket::fairing::AdHoc;
 
 #[get("/livereload/<_..>")]
 fn livereload_catcher() -> &'static str {
     "LiveReload route placeholder"
 }
 
+// Every request rejected by the `AuthUser` guard gets the same answer
+#[catch(401)]
+fn unauthorized() -> &'static str {
+    "Unauthorized"
+}
+
 #[get("/")]
 fn index() -> &'static str {
     "Welcome to Financial Tracker Backend!"
@@ -27,23 +58,69 @@ fn index() -> &'static str {
 #[launch]
 fn rocket() -> _ {
     let pool = db::establish_connection();
+    handlers::exchange_rate_handler::load_rates_from_env(&pool);
 
     rocket::build()
         .manage(pool)
         .mount("/", routes![index])
         .mount("/", routes![signup])
+        .mount("/", routes![login])
+        .mount("/", routes![logout])
+        .mount("/", routes![get_settings])
+        .mount("/", routes![update_settings])
         .mount("/", routes![account_create])
         .mount("/", routes![account_summary])
         .mount("/", routes![delete_account])
+        .mount("/", routes![account_update])
+        .mount("/", routes![unarchive_account])
+        .mount("/", routes![purge_account])
+        .mount("/", routes![account_balance])
+        .mount("/", routes![account_balance_history])
         .mount("/", routes![livereload_catcher])
         .mount("/", routes![category_create])
         .mount("/", routes![delete_category])
+        .mount("/", routes![merge_category])
+        .mount("/", routes![unarchive_category])
+        .mount("/", routes![purge_category])
         .mount("/", routes![category_summary])
         .mount("/", routes![category_update])
         .mount("/", routes![add_trans])
         .mount("/", routes![delete_trans])
+        .mount("/", routes![update_trans])
+        .mount("/", routes![transfer])
+        .mount("/", routes![split_create])
+        .mount("/", routes![split_details])
+        .mount("/", routes![delete_split])
+        .mount("/", routes![payees])
+        .mount("/", routes![merge_payee])
         .mount("/", routes![category_summary_trans])
         .mount("/", routes![account_summary_trans])
+        .mount("/", routes![transactions])
         .mount("/", routes![report_overview])
         .mount("/", routes![report_details])
+        .mount("/", routes![report_cashflow])
+        .mount("/", routes![report_networth])
+        .mount("/", routes![report_tags])
+        .mount("/", routes![report_payees])
+        .mount("/", routes![import_rates])
+        .mount("/", routes![import_preview])
+        .mount("/", routes![import_commit])
+        .mount("/", routes![import_profile])
+        .mount("/", routes![notifications])
+        .mount("/", routes![read_notifications])
+        .mount("/", routes![recurring_create])
+        .mount("/", routes![recurring_summary])
+        .mount("/", routes![recurring_update])
+        .mount("/", routes![delete_recurring])
+        // post due recurring transactions once the server is up, then on a timer
+        .attach(AdHoc::on_liftoff("Recurring transactions", |rocket| {
+            Box::pin(async move {
+                if let Some(pool) = rocket.state::<DbPool>() {
+                    tokio::spawn(handlers::recurring_handler::post_due_recurring_on_timer(
+                        pool.clone(),
+                    ));
+                }
+            })
+        }))
+        .register("/", catchers![unauthorized])
 }
@@ -1,7 +1,12 @@
 use diesel::prelude::*;
 use crate::schema::accounts;
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
+// Accounts of these types hold debt: their balance is what is owed, not what is held
+pub const LIABILITY_ACCOUNT_TYPES: [&str; 1] = ["credit"];
+
 // Struct for querying users
 // optional
 #[derive(Debug, Queryable, Serialize, Deserialize)]
@@ -10,6 +15,19 @@ pub struct Account {
     pub email: String,
     pub account_type: String,
     pub account_name: String,
+    pub currency: String,
+    // in `currency`, what the account held (or owed) on `opening_date`
+    pub opening_balance: Decimal,
+    // `None` means the balance counts every transaction of the account
+    pub opening_date: Option<NaiveDate>,
+    // archived accounts take no new transactions, `None` while active
+    pub archived_at: Option<DateTime<Utc>>,
+}
+
+impl Account {
+    pub fn is_liability(&self) -> bool {
+        LIABILITY_ACCOUNT_TYPES.contains(&self.account_type.to_ascii_lowercase().as_str())
+    }
 }
 
 // Struct for inserting new users
@@ -19,4 +37,77 @@ pub struct NewAccount {
     pub email: String,
     pub account_type: String,
     pub account_name: String,
+    pub currency: String,
+    pub opening_balance: Decimal,
+    pub opening_date: Option<NaiveDate>,
+}
+
+// Struct for new accounts from client side
+// The owner is taken from the session, not from the request body
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClientAccount {
+    pub account_type: String,
+    pub account_name: String,
+    // Defaults to CAD when missing
+    pub currency: Option<String>,
+    // Defaults to 0 when missing, the amount owed for credit accounts
+    pub opening_balance: Option<Decimal>,
+    // YYYY-MM-DD, transactions before it are already in the opening balance
+    pub opening_date: Option<String>,
+}
+
+// Struct for account updates from client side
+// Both fields are optional, only the provided ones are changed
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClientAccountUpdate {
+    pub account_name: Option<String>,
+    pub account_type: Option<String>,
+}
+
+// Struct for applying account updates, `None` fields are left untouched
+#[derive(AsChangeset)]
+#[diesel(table_name = accounts)]
+pub struct AccountChanges {
+    pub account_name: Option<String>,
+    pub account_type: Option<String>,
+}
+
+impl AccountChanges {
+    pub fn is_empty(&self) -> bool {
+        self.account_name.is_none() && self.account_type.is_none()
+    }
+}
+
+// Struct for sending the balance of an account to the client, in the account's currency
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AccountBalance {
+    pub account_name: String,
+    pub account_type: String,
+    pub currency: String,
+    pub opening_balance: Decimal,
+    pub opening_date: Option<NaiveDate>,
+    // credit accounts are liabilities, their balance is what is owed
+    pub liability: bool,
+    pub archived: bool,
+    pub balance: Decimal,
+    // what the account adds to net worth: the balance, negated for liabilities
+    pub net_value: Decimal,
+}
+
+// One point of a balance-over-time series, the balance at the end of `date`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BalancePoint {
+    pub date: NaiveDate,
+    pub balance: Decimal,
+}
+
+// Struct for sending the balance of an account over time to the client
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BalanceHistory {
+    pub account_name: String,
+    pub currency: String,
+    pub liability: bool,
+    // daily, weekly, monthly or yearly, the step between two points
+    pub interval: String,
+    pub points: Vec<BalancePoint>,
 }
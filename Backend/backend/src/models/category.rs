@@ -1,7 +1,66 @@
 use diesel::prelude::*;
 use crate::schema::categories;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
+// Every category is one of these, transfers count neither as income nor as spending
+pub const CATEGORY_TYPES: [&str; 3] = ["income", "expense", "transfer"];
+
+// Category types are stored lower case
+pub fn normalize_category_type(raw: &str) -> Option<String> {
+    let raw = raw.trim().to_ascii_lowercase();
+    if CATEGORY_TYPES.contains(&raw.as_str()) {
+        Some(raw)
+    } else {
+        None
+    }
+}
+
+// What an expense category's previous budget period leaves to the current one
+pub const ROLLOVER_POLICIES: [&str; 3] = ["none", "carry_unused", "carry_overspend"];
+
+// Rollover policies are stored lower case
+pub fn normalize_rollover(raw: &str) -> Option<String> {
+    let raw = raw.trim().to_ascii_lowercase();
+    if ROLLOVER_POLICIES.contains(&raw.as_str()) {
+        Some(raw)
+    } else {
+        None
+    }
+}
+
+// Percentages of the budget a new category is alerted at
+pub const DEFAULT_ALERT_THRESHOLDS: [i32; 1] = [100];
+// Alerts past ten times the budget are not useful
+pub const MAX_ALERT_THRESHOLD: i32 = 1000;
+
+// Thresholds are kept sorted without duplicates, an empty list turns alerts off
+pub fn normalize_alert_thresholds(mut thresholds: Vec<i32>) -> Option<Vec<i32>> {
+    if thresholds
+        .iter()
+        .any(|threshold| !(1..=MAX_ALERT_THRESHOLD).contains(threshold))
+    {
+        return None;
+    }
+    thresholds.sort_unstable();
+    thresholds.dedup();
+    Some(thresholds)
+}
+
+// "50,80,100" (a trailing % is allowed), "" or "none" for no alerts
+pub fn parse_alert_thresholds(raw: &str) -> Option<Vec<i32>> {
+    let raw = raw.trim();
+    if raw.is_empty() || raw.eq_ignore_ascii_case("none") {
+        return Some(vec![]);
+    }
+    let thresholds = raw
+        .split(',')
+        .map(|part| part.trim().trim_end_matches('%').trim().parse::<i32>().ok())
+        .collect::<Option<Vec<i32>>>()?;
+    normalize_alert_thresholds(thresholds)
+}
+
 // Struct for querying users
 // optional
 #[derive(Debug, Queryable, Serialize, Deserialize)]
@@ -10,8 +69,13 @@ pub struct Category {
     pub email: String,
     pub nickname : String,
     pub category_type : String,
-    pub budget: f64,
+    pub budget: Decimal,
     pub budget_freq: String,
+    pub rollover: String,
+    // percentages of the budget that raise a notification, see the notifications table
+    pub alert_thresholds: Vec<Option<i32>>,
+    // archived categories take no new transactions, `None` while active
+    pub archived_at: Option<DateTime<Utc>>,
 }
 
 // Struct for inserting new users
@@ -21,6 +85,57 @@ pub struct NewCategory {
     pub email: String,
     pub nickname : String,
     pub category_type : String,
-    pub budget: f64,
+    pub budget: Decimal,
     pub budget_freq: String,
+    pub rollover: String,
+    pub alert_thresholds: Vec<i32>,
+}
+
+// Struct for new categories from client side
+// The owner is taken from the session, not from the request body
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClientCategory {
+    pub nickname : String,
+    pub category_type : String,
+    pub budget: Decimal,
+    pub budget_freq: String,
+    // Defaults to "none" when missing
+    pub rollover: Option<String>,
+    // Defaults to [100] when missing
+    pub alert_thresholds: Option<Vec<i32>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_alert_thresholds;
+
+    #[test]
+    fn reads_percentages_sorted_without_duplicates() {
+        assert_eq!(parse_alert_thresholds("50,80,100"), Some(vec![50, 80, 100]));
+        assert_eq!(
+            parse_alert_thresholds(" 100, 50 ,80 "),
+            Some(vec![50, 80, 100])
+        );
+        assert_eq!(parse_alert_thresholds("80%, 100 %"), Some(vec![80, 100]));
+        assert_eq!(parse_alert_thresholds("100,100,50"), Some(vec![50, 100]));
+        assert_eq!(parse_alert_thresholds("1,1000"), Some(vec![1, 1000]));
+    }
+
+    #[test]
+    fn blank_or_none_turns_alerts_off() {
+        assert_eq!(parse_alert_thresholds(""), Some(vec![]));
+        assert_eq!(parse_alert_thresholds("  "), Some(vec![]));
+        assert_eq!(parse_alert_thresholds("none"), Some(vec![]));
+        assert_eq!(parse_alert_thresholds("None"), Some(vec![]));
+    }
+
+    #[test]
+    fn rejects_out_of_range_or_unknown_values() {
+        assert_eq!(parse_alert_thresholds("0"), None);
+        assert_eq!(parse_alert_thresholds("-10,50"), None);
+        assert_eq!(parse_alert_thresholds("1001"), None);
+        assert_eq!(parse_alert_thresholds("50,,100"), None);
+        assert_eq!(parse_alert_thresholds("12.5"), None);
+        assert_eq!(parse_alert_thresholds("half"), None);
+    }
 }
@@ -0,0 +1,19 @@
+use crate::schema::exchange_rates;
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+// Struct for inserting exchange rates
+// One row of the rates CSV: `date,from,to,rate`
+#[derive(Debug, Insertable, Serialize, Deserialize, Clone)]
+#[diesel(table_name = exchange_rates)]
+pub struct NewExchangeRate {
+    #[serde(alias = "date")]
+    pub rate_date: NaiveDate,
+    #[serde(alias = "from")]
+    pub from_currency: String,
+    #[serde(alias = "to")]
+    pub to_currency: String,
+    pub rate: Decimal,
+}
@@ -0,0 +1,135 @@
+use crate::schema::import_profiles;
+use chrono::{DateTime, NaiveDate, Utc};
+use diesel::prelude::*;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+// How a statement tells money leaving the account apart from money coming in:
+// bank exports usually write outflows negative, card exports positive
+pub const SIGN_CONVENTIONS: [&str; 2] = ["negative_outflow", "positive_outflow"];
+pub const DEFAULT_SIGN_CONVENTION: &str = "negative_outflow";
+pub const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+// Sign conventions are stored lower case
+pub fn normalize_sign_convention(raw: &str) -> Option<String> {
+    let raw = raw.trim().to_ascii_lowercase();
+    if SIGN_CONVENTIONS.contains(&raw.as_str()) {
+        Some(raw)
+    } else {
+        None
+    }
+}
+
+// Struct for querying import profiles, one per account
+#[derive(Debug, Queryable, Serialize)]
+pub struct ImportProfile {
+    pub profile_id: i32,
+    pub email: String,
+    pub account_id: i32,
+    // `None` once the category is purged
+    pub category_id: Option<i32>,
+    pub has_header: bool,
+    pub date_column: String,
+    pub date_format: String,
+    pub amount_column: Option<String>,
+    pub debit_column: Option<String>,
+    pub credit_column: Option<String>,
+    pub description_column: Option<String>,
+    pub payee_column: Option<String>,
+    pub sign_convention: String,
+    pub updated_at: DateTime<Utc>,
+    // `None` when every row goes to `category_id`, or once the category is purged
+    pub inflow_category_id: Option<i32>,
+}
+
+// Struct for saving the profile of an account, replacing the previous one
+#[derive(Insertable, AsChangeset)]
+#[diesel(table_name = import_profiles)]
+#[diesel(treat_none_as_null = true)]
+pub struct NewImportProfile {
+    pub email: String,
+    pub account_id: i32,
+    pub category_id: Option<i32>,
+    pub has_header: bool,
+    pub date_column: String,
+    pub date_format: String,
+    pub amount_column: Option<String>,
+    pub debit_column: Option<String>,
+    pub credit_column: Option<String>,
+    pub description_column: Option<String>,
+    pub payee_column: Option<String>,
+    pub sign_convention: String,
+    pub updated_at: DateTime<Utc>,
+    pub inflow_category_id: Option<i32>,
+}
+
+// Column mapping from client side
+// Columns are header names, ignoring case, or 1-based positions like "3"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportMapping {
+    // the category of the imported rows, only of the rows taking money out when
+    // `inflow_category_name` is set
+    pub category_name: Option<String>,
+    // optional, the category of the rows bringing money in
+    pub inflow_category_name: Option<String>,
+    // Defaults to true
+    pub has_header: Option<bool>,
+    pub date_column: String,
+    // a chrono format such as "%m/%d/%Y", defaults to "%Y-%m-%d"
+    pub date_format: Option<String>,
+    // either one signed amount column, or debit and/or credit columns
+    pub amount_column: Option<String>,
+    pub debit_column: Option<String>,
+    pub credit_column: Option<String>,
+    // stored as the notes
+    pub description_column: Option<String>,
+    pub payee_column: Option<String>,
+    // "negative_outflow" (default) or "positive_outflow", only used with `amount_column`
+    pub sign_convention: Option<String>,
+}
+
+// Body of /import_preview and /import_commit
+// The owner is taken from the session, not from the request body
+#[derive(Debug, Deserialize)]
+pub struct ImportRequest {
+    pub account_name: String,
+    // the statement as exported by the bank
+    pub csv: String,
+    // `None` uses the profile saved for the account
+    pub mapping: Option<ImportMapping>,
+}
+
+// One line of a statement as it would be imported
+#[derive(Debug, Serialize)]
+pub struct ImportRow {
+    pub line: usize,
+    pub date: Option<NaiveDate>,
+    // the category the row goes to
+    pub category_name: Option<String>,
+    // as stored in the category, spending for expenses and earnings for income
+    pub amount: Option<Decimal>,
+    pub description: Option<String>,
+    pub payee: Option<String>,
+    // "new", "duplicate" (already recorded on the account) or "invalid"
+    pub status: String,
+    pub error: Option<String>,
+}
+
+// Response of /import_preview
+#[derive(Debug, Serialize)]
+pub struct ImportPreview {
+    pub account_name: String,
+    pub mapping: ImportMapping,
+    pub new_rows: usize,
+    pub duplicates: usize,
+    pub invalid: usize,
+    pub rows: Vec<ImportRow>,
+}
+
+// Response of /import_commit
+#[derive(Debug, Serialize)]
+pub struct ImportResult {
+    pub imported: usize,
+    pub duplicates: usize,
+    pub invalid: usize,
+}
@@ -1,4 +1,13 @@
 pub mod user;
 pub mod account;
 pub mod category;
-pub mod transaction;
\ No newline at end of file
+pub mod exchange_rate;
+pub mod import;
+pub mod notification;
+pub mod payee;
+pub mod recurring;
+pub mod session;
+pub mod split;
+pub mod tag;
+pub mod transaction;
+pub mod transfer;
\ No newline at end of file
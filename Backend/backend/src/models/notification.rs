@@ -0,0 +1,31 @@
+use crate::schema::notifications;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// Struct for querying notifications
+// The owner is left out, only the caller's notifications are listed
+#[derive(Debug, Queryable, Serialize, Deserialize)]
+pub struct Notification {
+    pub notification_id: i32,
+    pub category_id: i32,
+    // percentage of the budget that was reached
+    pub threshold: i32,
+    // start of the budget period it was reached in
+    pub period_start: DateTime<Utc>,
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+    // `None` until the user dismisses it
+    pub read_at: Option<DateTime<Utc>>,
+}
+
+// Struct for inserting new notifications
+#[derive(Insertable)]
+#[diesel(table_name = notifications)]
+pub struct NewNotification {
+    pub email: String,
+    pub category_id: i32,
+    pub threshold: i32,
+    pub period_start: DateTime<Utc>,
+    pub message: String,
+}
@@ -0,0 +1,42 @@
+use crate::schema::payees;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// Long enough for `Shoppers Drug Mart #1234` style names from statements
+pub const MAX_PAYEE_LENGTH: usize = 80;
+pub const INVALID_PAYEE: &str = "Invalid payee: expected at most 80 characters";
+
+// Payee names keep the casing of their first use, runs of spaces are collapsed.
+// Matching ignores case, `Starbucks` and `STARBUCKS` are the same payee.
+pub fn normalize_payee(raw: &str) -> Option<String> {
+    let name = raw.split_whitespace().collect::<Vec<&str>>().join(" ");
+    if name.is_empty() || name.chars().count() > MAX_PAYEE_LENGTH {
+        None
+    } else {
+        Some(name)
+    }
+}
+
+// Struct for querying payees
+#[derive(Debug, Queryable, Serialize, Deserialize)]
+pub struct Payee {
+    pub payee_id: i32,
+    pub email: String,
+    pub name: String,
+}
+
+// Struct for inserting new payees
+#[derive(Insertable)]
+#[diesel(table_name = payees)]
+pub struct NewPayee {
+    pub email: String,
+    pub name: String,
+}
+
+// A row of /payees, most used first
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PayeeSummary {
+    pub payee_id: i32,
+    pub name: String,
+    pub transactions: i64,
+}
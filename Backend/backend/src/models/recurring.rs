@@ -0,0 +1,270 @@
+use crate::schema::recurring_transactions;
+use chrono::{Days, Months, NaiveDate};
+use diesel::prelude::*;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+// A recurrence repeats every `interval_count` of these
+pub const RECURRING_FREQUENCIES: [&str; 4] = ["daily", "weekly", "monthly", "yearly"];
+
+// Frequencies are stored lower case
+pub fn normalize_frequency(raw: &str) -> Option<String> {
+    let raw = raw.trim().to_ascii_lowercase();
+    if RECURRING_FREQUENCIES.contains(&raw.as_str()) {
+        Some(raw)
+    } else {
+        None
+    }
+}
+
+// The `n`th occurrence of a schedule, the first one (n = 0) being `start`.
+// Monthly and yearly schedules keep the day of `start`, falling back to the last day
+// of shorter months (a schedule starting Jan 31 posts Feb 28, then Mar 31).
+pub fn occurrence(start: NaiveDate, freq: &str, every: i32, n: i32) -> Option<NaiveDate> {
+    let steps = u32::try_from(every.checked_mul(n)?).ok()?;
+    match freq {
+        "daily" => start.checked_add_days(Days::new(steps as u64)),
+        "weekly" => start.checked_add_days(Days::new(steps as u64 * 7)),
+        "monthly" => start.checked_add_months(Months::new(steps)),
+        "yearly" => start.checked_add_months(Months::new(steps.checked_mul(12)?)),
+        _ => None,
+    }
+}
+
+// Struct for querying recurring transactions
+#[derive(Debug, Queryable, Serialize, Deserialize, Clone)]
+pub struct Recurring {
+    pub recurring_id: i32,
+    pub email: String,
+    pub category_id: i32,
+    pub account_id: i32,
+    pub amount: Decimal,
+    pub notes: Option<String>,
+    // `None` means the currency of the account
+    pub currency: Option<String>,
+    pub frequency: String,
+    pub interval_count: i32,
+    pub start_date: NaiveDate,
+    // last day an occurrence may fall on, `None` repeats forever
+    pub end_date: Option<NaiveDate>,
+    pub posted_count: i32,
+    pub next_date: NaiveDate,
+    pub paused: bool,
+}
+
+impl Recurring {
+    pub fn occurrence(&self, n: i32) -> Option<NaiveDate> {
+        occurrence(self.start_date, &self.frequency, self.interval_count, n)
+    }
+
+    // Whether `day` is still within the schedule
+    pub fn runs_on(&self, day: NaiveDate) -> bool {
+        self.end_date.is_none_or(|last_day| day <= last_day)
+    }
+}
+
+// Struct for inserting new recurring transactions
+#[derive(Insertable)]
+#[diesel(table_name = recurring_transactions)]
+pub struct NewRecurring {
+    pub email: String,
+    pub category_id: i32,
+    pub account_id: i32,
+    pub amount: Decimal,
+    pub notes: Option<String>,
+    pub currency: Option<String>,
+    pub frequency: String,
+    pub interval_count: i32,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    pub next_date: NaiveDate,
+}
+
+// Struct for new recurring transactions from client side
+// Like transactions, the user only knows the names of accounts and categories
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClientRecurring {
+    pub category_name: String,
+    pub account_name: String,
+    pub amount: Decimal,
+    pub notes: Option<String>,
+    // Only needed when it differs from the account's currency
+    pub currency: Option<String>,
+    pub frequency: String,
+    // Defaults to 1 when missing, e.g. 2 with "weekly" is every other week
+    pub interval_count: Option<i32>,
+    // YYYY-MM-DD, the first occurrence
+    pub start_date: String,
+    pub end_date: Option<String>,
+}
+
+// Struct for recurring transaction updates from client side
+// Every field is optional, only the provided ones are changed
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClientRecurringUpdate {
+    pub category_name: Option<String>,
+    pub account_name: Option<String>,
+    pub amount: Option<Decimal>,
+    pub notes: Option<String>,
+    pub currency: Option<String>,
+    pub frequency: Option<String>,
+    pub interval_count: Option<i32>,
+    pub start_date: Option<String>,
+    // An empty string removes the end date
+    pub end_date: Option<String>,
+    pub paused: Option<bool>,
+}
+
+// Struct for applying recurring transaction updates, `None` fields are left untouched
+#[derive(AsChangeset, Default)]
+#[diesel(table_name = recurring_transactions)]
+pub struct RecurringChanges {
+    pub category_id: Option<i32>,
+    pub account_id: Option<i32>,
+    pub amount: Option<Decimal>,
+    pub notes: Option<String>,
+    pub currency: Option<String>,
+    pub frequency: Option<String>,
+    pub interval_count: Option<i32>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<Option<NaiveDate>>,
+    pub posted_count: Option<i32>,
+    pub next_date: Option<NaiveDate>,
+    pub paused: Option<bool>,
+}
+
+impl RecurringChanges {
+    pub fn is_empty(&self) -> bool {
+        self.category_id.is_none()
+            && self.account_id.is_none()
+            && self.amount.is_none()
+            && self.notes.is_none()
+            && self.currency.is_none()
+            && self.frequency.is_none()
+            && self.interval_count.is_none()
+            && self.start_date.is_none()
+            && self.end_date.is_none()
+            && self.paused.is_none()
+    }
+}
+
+// Struct listing recurring transactions back to the client, with names instead of ids
+#[derive(Debug, Queryable, Serialize, Deserialize)]
+pub struct RecurringSummary {
+    pub recurring_id: i32,
+    pub category_name: String,
+    pub account_name: String,
+    pub amount: Decimal,
+    pub notes: Option<String>,
+    pub currency: Option<String>,
+    pub frequency: String,
+    pub interval_count: i32,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    pub next_date: NaiveDate,
+    pub paused: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Recurring;
+    use chrono::NaiveDate;
+    use rust_decimal::Decimal;
+
+    fn day(text: &str) -> NaiveDate {
+        text.parse().unwrap()
+    }
+
+    fn schedule(start: &str, freq: &str, every: i32, end: Option<&str>) -> Recurring {
+        Recurring {
+            recurring_id: 1,
+            email: "user@example.com".to_string(),
+            category_id: 1,
+            account_id: 1,
+            amount: Decimal::ONE,
+            notes: None,
+            currency: None,
+            frequency: freq.to_string(),
+            interval_count: every,
+            start_date: day(start),
+            end_date: end.map(day),
+            posted_count: 0,
+            next_date: day(start),
+            paused: false,
+        }
+    }
+
+    fn occurrences(recurring: &Recurring, count: i32) -> Vec<NaiveDate> {
+        (0..count).filter_map(|n| recurring.occurrence(n)).collect()
+    }
+
+    #[test]
+    fn steps_by_the_interval() {
+        let daily = schedule("2026-12-30", "daily", 1, None);
+        assert_eq!(
+            occurrences(&daily, 3),
+            [day("2026-12-30"), day("2026-12-31"), day("2027-01-01")]
+        );
+        let fortnightly = schedule("2026-10-05", "weekly", 2, None);
+        assert_eq!(
+            occurrences(&fortnightly, 3),
+            [day("2026-10-05"), day("2026-10-19"), day("2026-11-02")]
+        );
+        let quarterly = schedule("2026-01-15", "monthly", 3, None);
+        assert_eq!(
+            occurrences(&quarterly, 3),
+            [day("2026-01-15"), day("2026-04-15"), day("2026-07-15")]
+        );
+    }
+
+    #[test]
+    fn keeps_the_start_day_after_short_months() {
+        let monthly = schedule("2026-01-31", "monthly", 1, None);
+        assert_eq!(
+            occurrences(&monthly, 4),
+            [
+                day("2026-01-31"),
+                day("2026-02-28"),
+                day("2026-03-31"),
+                day("2026-04-30")
+            ]
+        );
+        let yearly = schedule("2024-02-29", "yearly", 1, None);
+        assert_eq!(
+            occurrences(&yearly, 5),
+            [
+                day("2024-02-29"),
+                day("2025-02-28"),
+                day("2026-02-28"),
+                day("2027-02-28"),
+                day("2028-02-29")
+            ]
+        );
+    }
+
+    #[test]
+    fn has_no_occurrence_for_bad_schedules() {
+        assert_eq!(
+            schedule("2026-10-01", "hourly", 1, None).occurrence(1),
+            None
+        );
+        assert_eq!(
+            schedule("2026-10-01", "daily", 1, None).occurrence(-1),
+            None
+        );
+        assert_eq!(
+            schedule("2026-10-01", "daily", i32::MAX, None).occurrence(2),
+            None
+        );
+    }
+
+    #[test]
+    fn runs_until_the_end_date_included() {
+        let open_ended = schedule("2026-10-01", "monthly", 1, None);
+        assert!(open_ended.runs_on(day("2099-12-31")));
+        let ending = schedule("2026-10-01", "monthly", 1, Some("2026-12-01"));
+        assert!(ending.runs_on(day("2026-11-01")));
+        assert!(ending.runs_on(day("2026-12-01")));
+        assert!(!ending.runs_on(day("2026-12-02")));
+    }
+}
@@ -0,0 +1,22 @@
+use crate::schema::sessions;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// Struct for inserting new sessions
+#[derive(Insertable, Serialize, Deserialize, Clone)]
+#[diesel(table_name = sessions)]
+pub struct NewSession {
+    pub token: String,
+    pub email: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+// Body returned to the client after a successful login
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LoginResponse {
+    pub token: String,
+    pub username: String,
+    pub email: String,
+    pub expires_at: DateTime<Utc>,
+}
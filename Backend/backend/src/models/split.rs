@@ -0,0 +1,91 @@
+use crate::schema::{splits, transactions};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+// Struct for querying splits, the payment as it left the account
+#[derive(Debug, Queryable, Serialize)]
+pub struct Split {
+    pub split_id: i32,
+    pub email: String,
+    pub account_id: i32,
+    pub amount: Decimal,
+    pub notes: Option<String>,
+    pub transaction_date: DateTime<Utc>,
+    // `None` means the currency of the account
+    pub currency: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+// Struct for inserting new splits, the category lines point back to it
+#[derive(Insertable)]
+#[diesel(table_name = splits)]
+pub struct NewSplit {
+    pub email: String,
+    pub account_id: i32,
+    pub amount: Decimal,
+    pub notes: Option<String>,
+    pub transaction_date: DateTime<Utc>,
+    pub currency: Option<String>,
+}
+
+// Struct for inserting one category line of a split
+// It shares the account, date and currency of the split
+#[derive(Insertable)]
+#[diesel(table_name = transactions)]
+pub struct NewSplitLine {
+    pub email: String,
+    pub category_id: i32,
+    pub amount: Decimal,
+    pub notes: Option<String>,
+    pub account_id: i32,
+    pub transaction_date: DateTime<Utc>,
+    pub currency: Option<String>,
+    pub split_id: i32,
+    pub payee_id: Option<i32>,
+}
+
+// Struct for new splits from client side
+// The owner is taken from the session, not from the request body
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClientSplit {
+    pub account_name: String,
+    // the lines must add up to it
+    pub amount: Decimal,
+    // used for the lines without notes of their own
+    pub notes: Option<String>,
+    // Defaults to the time of the request when missing
+    pub transaction_date: Option<String>,
+    // Only needed when it differs from the account's currency
+    pub currency: Option<String>,
+    // the payee of every line, created the first time it is used
+    pub payee: Option<String>,
+    pub lines: Vec<ClientSplitLine>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClientSplitLine {
+    pub category_name: String,
+    pub amount: Decimal,
+    pub notes: Option<String>,
+}
+
+// A line of /split_details
+#[derive(Debug, Serialize)]
+pub struct SplitLine {
+    pub trans_id: i32,
+    pub category_name: String,
+    pub amount: Decimal,
+    pub notes: Option<String>,
+    pub payee: Option<String>,
+}
+
+// Response of /split_details
+#[derive(Debug, Serialize)]
+pub struct SplitDetails {
+    #[serde(flatten)]
+    pub split: Split,
+    pub account_name: String,
+    pub lines: Vec<SplitLine>,
+}
@@ -0,0 +1,96 @@
+use crate::schema::{tags, transaction_tags};
+use diesel::prelude::*;
+
+// Long enough for `vacation2026` style names, short enough to list
+pub const MAX_TAG_LENGTH: usize = 40;
+pub const INVALID_TAG: &str =
+    "Invalid tag: expected letters, digits, - or _ and at most 40 characters";
+
+// Tags are stored lower case without the leading `#`, so `#Vacation2026` and
+// `vacation2026` are the same tag. Letters, digits, `-` and `_` only.
+pub fn normalize_tag(raw: &str) -> Option<String> {
+    let name = raw.trim();
+    let name = name.strip_prefix('#').unwrap_or(name).to_lowercase();
+    let valid_chars = name
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+    if name.is_empty() || name.chars().count() > MAX_TAG_LENGTH || !valid_chars {
+        None
+    } else {
+        Some(name)
+    }
+}
+
+// Normalizes every tag of a request and drops repeats, `None` if any of them is invalid
+pub fn normalize_tags(raw_tags: &[String]) -> Option<Vec<String>> {
+    let mut names: Vec<String> = Vec::with_capacity(raw_tags.len());
+    for raw in raw_tags {
+        let name = normalize_tag(raw)?;
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    Some(names)
+}
+
+// Struct for inserting new tags
+#[derive(Insertable)]
+#[diesel(table_name = tags)]
+pub struct NewTag {
+    pub email: String,
+    pub name: String,
+}
+
+// Struct for linking a transaction to a tag
+#[derive(Insertable)]
+#[diesel(table_name = transaction_tags)]
+pub struct NewTransactionTag {
+    pub trans_id: i32,
+    pub tag_id: i32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{normalize_tag, normalize_tags};
+
+    fn tag(raw: &str) -> Option<String> {
+        normalize_tag(raw)
+    }
+
+    fn named(name: &str) -> Option<String> {
+        Some(name.to_string())
+    }
+
+    #[test]
+    fn stores_tags_lower_case_without_the_hash() {
+        assert_eq!(tag("vacation2026"), named("vacation2026"));
+        assert_eq!(tag("#Vacation2026"), named("vacation2026"));
+        assert_eq!(tag("  #road-trip_2026 "), named("road-trip_2026"));
+        assert_eq!(tag("Café"), named("café"));
+    }
+
+    #[test]
+    fn rejects_empty_long_or_punctuated_tags() {
+        assert_eq!(tag(""), None);
+        assert_eq!(tag("#"), None);
+        assert_eq!(tag("##double"), None);
+        assert_eq!(tag("two words"), None);
+        assert_eq!(tag("a,b"), None);
+        assert_eq!(tag(&"a".repeat(41)), None);
+        assert_eq!(tag(&"é".repeat(40)), Some("é".repeat(40)));
+    }
+
+    #[test]
+    fn drops_repeated_tags_of_a_request() {
+        let raw = ["#Trip".to_string(), "trip".to_string(), "food".to_string()];
+        assert_eq!(
+            normalize_tags(&raw),
+            Some(vec!["trip".to_string(), "food".to_string()])
+        );
+        assert_eq!(
+            normalize_tags(&["ok".to_string(), "not ok".to_string()]),
+            None
+        );
+        assert_eq!(normalize_tags(&[]), Some(vec![]));
+    }
+}
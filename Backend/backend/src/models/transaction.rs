@@ -1,4 +1,6 @@
 use crate::schema::transactions;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -8,11 +10,19 @@ use serde::{Deserialize, Serialize};
 pub struct Transaction {
     pub trans_id: i32,
     pub email: String,
-    pub category_id: i32,
-    pub amount: f64,
+    // `None` for transfer legs
+    pub category_id: Option<i32>,
+    pub amount: Decimal,
     pub notes: Option<String>,
     pub account_id: i32,
-    pub transaction_date: String,
+    pub transaction_date: DateTime<Utc>,
+    // `None` means the currency of the account
+    pub currency: Option<String>,
+    // the transfer this transaction is a leg of
+    pub transfer_id: Option<i32>,
+    // the split this transaction is a category line of
+    pub split_id: Option<i32>,
+    pub payee_id: Option<i32>,
 }
 
 // Struct for inserting new transactions
@@ -21,19 +31,281 @@ pub struct Transaction {
 pub struct NewTransaction {
     pub email: String,
     pub category_id: i32,
-    pub amount: f64,
+    pub amount: Decimal,
     pub notes: Option<String>,
     pub account_id: i32,
-    pub transaction_date: String,
+    pub transaction_date: DateTime<Utc>,
+    pub currency: Option<String>,
+    pub payee_id: Option<i32>,
 }
 
 // Struct for new transactions from client side
 // Note that user is only aware of the names of accounts and categories
+// The owner is taken from the session, not from the request body
 #[derive(Debug, Queryable, Serialize, Deserialize)]
 pub struct ClientTransaction {
-    pub email: String,
     pub category_name: String,
-    pub amount: f64,
+    pub amount: Decimal,
     pub notes: Option<String>,
     pub account_name: String,
+    // Defaults to the time of the request when missing
+    pub transaction_date: Option<String>,
+    // Only needed when it differs from the account's currency
+    pub currency: Option<String>,
+    // `vacation2026` or `#vacation2026`, see `normalize_tag`
+    #[serde(default)]
+    pub tags: Vec<String>,
+    // created the first time it is used, see `normalize_payee`
+    pub payee: Option<String>,
+}
+
+// Struct for transaction updates from client side
+// Every field is optional, only the provided ones are changed
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClientTransactionUpdate {
+    pub category_name: Option<String>,
+    pub amount: Option<Decimal>,
+    // blank removes the notes
+    pub notes: Option<String>,
+    pub account_name: Option<String>,
+    pub transaction_date: Option<String>,
+    // blank goes back to the account's currency
+    pub currency: Option<String>,
+    // replaces every tag of the transaction, an empty list removes them
+    pub tags: Option<Vec<String>>,
+    // an empty name removes the payee
+    pub payee: Option<String>,
+}
+
+// Struct for applying transaction updates, `None` fields are left untouched
+#[derive(AsChangeset)]
+#[diesel(table_name = transactions)]
+pub struct TransactionChanges {
+    pub category_id: Option<i32>,
+    pub amount: Option<Decimal>,
+    // `Some(None)` removes the notes
+    pub notes: Option<Option<String>>,
+    pub account_id: Option<i32>,
+    pub transaction_date: Option<DateTime<Utc>>,
+    // `Some(None)` goes back to the account's currency
+    pub currency: Option<Option<String>>,
+    // `Some(None)` removes the payee
+    pub payee_id: Option<Option<i32>>,
+}
+
+impl TransactionChanges {
+    pub fn is_empty(&self) -> bool {
+        self.category_id.is_none()
+            && self.amount.is_none()
+            && self.notes.is_none()
+            && self.account_id.is_none()
+            && self.transaction_date.is_none()
+            && self.currency.is_none()
+            && self.payee_id.is_none()
+    }
+}
+
+// A row of /category_trans, with the tags and payee name of the transaction
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaggedTransaction {
+    #[serde(flatten)]
+    pub transaction: Transaction,
+    pub tags: Vec<String>,
+    pub payee: Option<String>,
+}
+
+// A row of /account_trans, telling transfers apart from categorized transactions
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AccountTransaction {
+    #[serde(flatten)]
+    pub transaction: Transaction,
+    // "transaction" or "transfer"
+    pub kind: String,
+    // for transfers, the account on the other side
+    pub counterpart_account: Option<String>,
+    pub tags: Vec<String>,
+    pub payee: Option<String>,
+}
+
+// Orders of /transactions, `trans_id` breaks ties so pages never overlap
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransactionSort {
+    DateDesc,
+    DateAsc,
+    AmountDesc,
+    AmountAsc,
+}
+
+impl TransactionSort {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim() {
+            "date_desc" => Some(Self::DateDesc),
+            "date_asc" => Some(Self::DateAsc),
+            "amount_desc" => Some(Self::AmountDesc),
+            "amount_asc" => Some(Self::AmountAsc),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::DateDesc => "date_desc",
+            Self::DateAsc => "date_asc",
+            Self::AmountDesc => "amount_desc",
+            Self::AmountAsc => "amount_asc",
+        }
+    }
+}
+
+// Where a /transactions page ends: the sort key and id of its last row.
+// Sent to the client as `<sort>~<trans_id>~<key>`, the key being microseconds since
+// the epoch for date orders and the amount for amount orders.
+#[derive(Debug, Clone, Copy)]
+pub enum SearchCursor {
+    Date(DateTime<Utc>, i32),
+    Amount(Decimal, i32),
+}
+
+impl SearchCursor {
+    pub fn after(sort: TransactionSort, last: &Transaction) -> Self {
+        match sort {
+            TransactionSort::DateDesc | TransactionSort::DateAsc => {
+                Self::Date(last.transaction_date, last.trans_id)
+            }
+            TransactionSort::AmountDesc | TransactionSort::AmountAsc => {
+                Self::Amount(last.amount, last.trans_id)
+            }
+        }
+    }
+
+    // `None` for a malformed cursor or one made for another sort order
+    pub fn parse(raw: &str, sort: TransactionSort) -> Option<Self> {
+        let mut parts = raw.trim().splitn(3, '~');
+        let (sort_name, last_id, key) = (parts.next()?, parts.next()?, parts.next()?);
+        if sort_name != sort.name() {
+            return None;
+        }
+        let last_id = last_id.parse::<i32>().ok()?;
+        match sort {
+            TransactionSort::DateDesc | TransactionSort::DateAsc => {
+                let micros = key.parse::<i64>().ok()?;
+                Some(Self::Date(
+                    DateTime::from_timestamp_micros(micros)?,
+                    last_id,
+                ))
+            }
+            TransactionSort::AmountDesc | TransactionSort::AmountAsc => {
+                Some(Self::Amount(key.parse::<Decimal>().ok()?, last_id))
+            }
+        }
+    }
+
+    pub fn encode(&self, sort: TransactionSort) -> String {
+        match self {
+            Self::Date(at, last_id) => {
+                format!("{}~{}~{}", sort.name(), last_id, at.timestamp_micros())
+            }
+            Self::Amount(key, last_id) => format!("{}~{}~{}", sort.name(), last_id, key),
+        }
+    }
+}
+
+// A row of /transactions, with the names the client knows its category and account by
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchTransaction {
+    #[serde(flatten)]
+    pub transaction: Transaction,
+    // `None` for transfer legs
+    pub category_name: Option<String>,
+    pub account_name: String,
+    pub tags: Vec<String>,
+    pub payee: Option<String>,
+}
+
+// Response of /transactions, `next_cursor` is `None` on the last page
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TransactionPage {
+    pub transactions: Vec<SearchTransaction>,
+    pub next_cursor: Option<String>,
+}
+
+// Filters of /transactions as sent by the client, every one of them is optional
+#[derive(Debug)]
+pub struct TransactionSearch {
+    // YYYY-MM-DD days in the owner's time zone, both included
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub min_amount: Option<String>,
+    pub max_amount: Option<String>,
+    // any of these categories or accounts
+    pub categories: Vec<String>,
+    pub accounts: Vec<String>,
+    // text the notes contain, ignoring case, as one substring rather than words
+    pub q: Option<String>,
+    pub tag: Option<String>,
+    pub payee: Option<String>,
+    pub sort: Option<String>,
+    pub limit: Option<String>,
+    pub cursor: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SearchCursor, TransactionSort};
+    use chrono::{DateTime, Utc};
+    use rust_decimal::Decimal;
+
+    fn at(text: &str) -> DateTime<Utc> {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn date_cursors_round_trip_to_the_microsecond() {
+        let last = at("2026-10-18T12:30:00.123456Z");
+        for sort in [TransactionSort::DateDesc, TransactionSort::DateAsc] {
+            let encoded = SearchCursor::Date(last, 42).encode(sort);
+            assert_eq!(encoded, format!("{}~42~1792326600123456", sort.name()));
+            assert!(matches!(
+                SearchCursor::parse(&encoded, sort),
+                Some(SearchCursor::Date(parsed, 42)) if parsed == last
+            ));
+        }
+    }
+
+    #[test]
+    fn amount_cursors_round_trip_exactly() {
+        let key: Decimal = "-12.50".parse().unwrap();
+        for sort in [TransactionSort::AmountDesc, TransactionSort::AmountAsc] {
+            let encoded = SearchCursor::Amount(key, 7).encode(sort);
+            assert_eq!(encoded, format!("{}~7~-12.50", sort.name()));
+            assert!(matches!(
+                SearchCursor::parse(&encoded, sort),
+                Some(SearchCursor::Amount(parsed, 7)) if parsed == key
+            ));
+        }
+    }
+
+    #[test]
+    fn rejects_cursors_of_another_sort_order() {
+        let encoded =
+            SearchCursor::Date(at("2026-10-18T12:30:00Z"), 42).encode(TransactionSort::DateDesc);
+        assert!(SearchCursor::parse(&encoded, TransactionSort::DateAsc).is_none());
+        assert!(SearchCursor::parse(&encoded, TransactionSort::AmountDesc).is_none());
+    }
+
+    #[test]
+    fn rejects_malformed_cursors() {
+        let sort = TransactionSort::AmountDesc;
+        for raw in [
+            "",
+            "amount_desc",
+            "amount_desc~7",
+            "amount_desc~seven~12.50",
+            "amount_desc~7~twelve",
+            "amount_desc~7~12.50~extra",
+        ] {
+            assert!(SearchCursor::parse(raw, sort).is_none(), "{}", raw);
+        }
+        assert!(SearchCursor::parse("date_desc~42~soon", TransactionSort::DateDesc).is_none());
+    }
 }
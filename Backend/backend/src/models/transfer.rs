@@ -0,0 +1,40 @@
+use crate::schema::{transactions, transfers};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+// Struct for inserting new transfers, the legs point back to it
+#[derive(Insertable)]
+#[diesel(table_name = transfers)]
+pub struct NewTransfer {
+    pub email: String,
+}
+
+// Struct for inserting one leg of a transfer, in its account's currency
+// The leg leaving an account is negative, the one arriving is positive
+#[derive(Insertable)]
+#[diesel(table_name = transactions)]
+pub struct NewTransferLeg {
+    pub email: String,
+    pub amount: Decimal,
+    pub notes: Option<String>,
+    pub account_id: i32,
+    pub transaction_date: DateTime<Utc>,
+    pub transfer_id: i32,
+}
+
+// Struct for new transfers from client side
+// The owner is taken from the session, not from the request body
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClientTransfer {
+    pub from_account: String,
+    pub to_account: String,
+    // in the currency of `from_account`
+    pub amount: Decimal,
+    // in the currency of `to_account`, converted from `amount` when missing
+    pub to_amount: Option<Decimal>,
+    pub notes: Option<String>,
+    // Defaults to the time of the request when missing
+    pub transaction_date: Option<String>,
+}
@@ -9,6 +9,8 @@ pub struct User {
     pub email: String,
     pub password: String,
     pub username: String,
+    pub time_zone: String,
+    pub period_start_day: i32,
 }
 
 // Struct for inserting new users
@@ -19,3 +21,32 @@ pub struct NewUser {
     pub password: String,
     pub username: String,
 }
+
+// Struct for login requests
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LoginUser {
+    pub email: String,
+    pub password: String,
+}
+
+// Budget calendar settings of the logged-in user
+#[derive(Debug, Queryable, Serialize, Deserialize)]
+pub struct UserSettings {
+    pub time_zone: String,
+    pub period_start_day: i32,
+}
+
+// Struct for settings updates from client side, missing fields are left untouched
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClientSettings {
+    pub time_zone: Option<String>,
+    pub period_start_day: Option<i32>,
+}
+
+// Struct for applying settings updates, `None` fields are left untouched
+#[derive(AsChangeset)]
+#[diesel(table_name = users)]
+pub struct SettingsChanges {
+    pub time_zone: Option<String>,
+    pub period_start_day: Option<i32>,
+}
@@ -0,0 +1,454 @@
+use crate::schema::users::dsl::{email, period_start_day, time_zone, users};
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, Months, NaiveDate, TimeZone, Utc, Weekday,
+};
+use chrono_tz::Tz;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// A budget month may start on any day every month has
+pub const MAX_PERIOD_START_DAY: i32 = 28;
+// A selector that parses but names no period, e.g. week 53 of a year with 52 weeks
+pub const UNKNOWN_PERIOD: &str = "Invalid period: no such period in the calendar";
+
+// IANA time zone names, e.g. "America/Toronto"
+pub fn parse_time_zone(name: &str) -> Option<Tz> {
+    name.trim().parse::<Tz>().ok()
+}
+
+// Bounds of one budget period, `start` included and `end` excluded, in the owner's time zone
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Period {
+    pub start: DateTime<FixedOffset>,
+    pub end: DateTime<FixedOffset>,
+}
+
+impl Period {
+    pub fn start_utc(&self) -> DateTime<Utc> {
+        self.start.with_timezone(&Utc)
+    }
+
+    pub fn end_utc(&self) -> DateTime<Utc> {
+        self.end.with_timezone(&Utc)
+    }
+
+    // First and last day the period touches, as local or as UTC days
+    pub fn days(&self) -> [NaiveDate; 2] {
+        [
+            self.start.date_naive().min(self.start_utc().date_naive()),
+            self.end.date_naive().max(self.end_utc().date_naive()),
+        ]
+    }
+}
+
+// A past or future period asked for by the client instead of the current one
+#[derive(Debug, Clone, Copy)]
+pub enum PeriodSelector {
+    // "2026"
+    Year(i32),
+    // "2026-09", the budget month starting on the user's period start day
+    Month(i32, u32),
+    // "2026-W40", an ISO week
+    Week(i32, u32),
+    // "2026-09-30"
+    Day(NaiveDate),
+    // from/to days, both included
+    Range(NaiveDate, NaiveDate),
+}
+
+impl PeriodSelector {
+    // `period` or both `from` and `to`, None when the client asked for the current periods
+    pub fn parse(
+        period: Option<&str>,
+        from: Option<&str>,
+        to: Option<&str>,
+    ) -> Result<Option<Self>, &'static str> {
+        match (period, from, to) {
+            (None, None, None) => Ok(None),
+            (Some(spec), None, None) => Self::parse_spec(spec.trim())
+                .map(Some)
+                .ok_or("Invalid period: expected YYYY, YYYY-MM, YYYY-Www or YYYY-MM-DD"),
+            (None, Some(from), Some(to)) => {
+                let parse_day = |day: &str| NaiveDate::parse_from_str(day.trim(), "%Y-%m-%d").ok();
+                match (parse_day(from), parse_day(to)) {
+                    (Some(first), Some(last)) if first <= last => Ok(Some(Self::Range(first, last))),
+                    _ => Err("Invalid range: from and to must be YYYY-MM-DD days, from first"),
+                }
+            }
+            _ => Err("Use either period or both from and to"),
+        }
+    }
+
+    fn parse_spec(spec: &str) -> Option<Self> {
+        if let Ok(day) = NaiveDate::parse_from_str(spec, "%Y-%m-%d") {
+            return Some(Self::Day(day));
+        }
+        if let Some((year, week)) = spec.split_once("-W") {
+            let (year, week) = (year.parse().ok()?, week.parse().ok()?);
+            NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)?;
+            return Some(Self::Week(year, week));
+        }
+        if let Some((year, month)) = spec.split_once('-') {
+            let (year, month) = (year.parse().ok()?, month.parse().ok()?);
+            NaiveDate::from_ymd_opt(year, month, 1)?;
+            return Some(Self::Month(year, month));
+        }
+        let year = spec.parse().ok()?;
+        NaiveDate::from_ymd_opt(year, 1, 1)?;
+        Some(Self::Year(year))
+    }
+
+    // The period of the same kind just before this one
+    pub fn previous(&self) -> Option<Self> {
+        match *self {
+            Self::Year(year) => Some(Self::Year(year - 1)),
+            Self::Month(year, month) => {
+                let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+                let before = first.checked_sub_months(Months::new(1))?;
+                Some(Self::Month(before.year(), before.month()))
+            }
+            Self::Week(year, week) => {
+                let monday = NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)?;
+                let before = (monday - Duration::days(7)).iso_week();
+                Some(Self::Week(before.year(), before.week()))
+            }
+            Self::Day(day) => Some(Self::Day(day.pred_opt()?)),
+            Self::Range(first, last) => {
+                let length = last - first + Duration::days(1);
+                Some(Self::Range(first - length, last - length))
+            }
+        }
+    }
+
+    // How many of these periods fit in a year, None for ranges
+    pub fn per_year(&self) -> Option<i64> {
+        match self {
+            Self::Year(_) => Some(1),
+            Self::Month(_, _) => Some(12),
+            Self::Week(_, _) => Some(52),
+            Self::Day(_) => Some(365),
+            Self::Range(_, _) => None,
+        }
+    }
+}
+
+// How a user cuts time into budget periods
+pub struct BudgetCalendar {
+    tz: Tz,
+    start_day: u32,
+}
+
+impl BudgetCalendar {
+    pub fn load(conn: &mut PgConnection, owner: &str) -> QueryResult<Self> {
+        let (zone_name, start_day) = users
+            .filter(email.eq(owner))
+            .select((time_zone, period_start_day))
+            .first::<(String, i32)>(conn)?;
+        // a zone chrono-tz no longer knows falls back to UTC rather than failing the report
+        let tz = parse_time_zone(&zone_name).unwrap_or(Tz::UTC);
+        Ok(Self::new(tz, start_day))
+    }
+
+    fn new(tz: Tz, start_day: i32) -> Self {
+        Self {
+            tz,
+            start_day: start_day.clamp(1, MAX_PERIOD_START_DAY) as u32,
+        }
+    }
+
+    // The user's local day at `at`
+    pub fn today(&self, at: DateTime<Utc>) -> NaiveDate {
+        at.with_timezone(&self.tz).date_naive()
+    }
+
+    // The period of `freq` containing `at`, None for frequencies without a period
+    // daily: the calendar day, weekly: the ISO week (Monday first),
+    // monthly: from the period start day to the same day next month, yearly: the calendar year
+    pub fn period_containing(&self, freq: &str, at: DateTime<Utc>) -> Option<Period> {
+        let today = self.today(at);
+        let (first_day, next_first_day) = match freq {
+            "daily" => (today, today.succ_opt()?),
+            "weekly" => {
+                let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+                (monday, monday + Duration::days(7))
+            }
+            "monthly" => {
+                let this_month = today.with_day(self.start_day)?;
+                let first_day = if today >= this_month {
+                    this_month
+                } else {
+                    this_month.checked_sub_months(Months::new(1))?
+                };
+                (first_day, first_day.checked_add_months(Months::new(1))?)
+            }
+            "yearly" => {
+                let new_year = NaiveDate::from_ymd_opt(today.year(), 1, 1)?;
+                (new_year, new_year.with_year(today.year() + 1)?)
+            }
+            _ => return None,
+        };
+        Some(Period {
+            start: self.local_midnight(first_day)?,
+            end: self.local_midnight(next_first_day)?,
+        })
+    }
+
+    // The period of `freq` just before `current`
+    pub fn period_before(&self, freq: &str, current: &Period) -> Option<Period> {
+        self.period_containing(freq, current.start_utc() - Duration::nanoseconds(1))
+    }
+
+    // Bounds of a period picked by the client
+    pub fn resolve(&self, selector: PeriodSelector) -> Option<Period> {
+        let (first_day, next_first_day) = match selector {
+            PeriodSelector::Year(year) => (
+                NaiveDate::from_ymd_opt(year, 1, 1)?,
+                NaiveDate::from_ymd_opt(year + 1, 1, 1)?,
+            ),
+            PeriodSelector::Month(year, month) => {
+                let first_day = NaiveDate::from_ymd_opt(year, month, self.start_day)?;
+                (first_day, first_day.checked_add_months(Months::new(1))?)
+            }
+            PeriodSelector::Week(year, week) => {
+                let monday = NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)?;
+                (monday, monday + Duration::days(7))
+            }
+            PeriodSelector::Day(day) => (day, day.succ_opt()?),
+            PeriodSelector::Range(first, last) => (first, last.succ_opt()?),
+        };
+        Some(Period {
+            start: self.local_midnight(first_day)?,
+            end: self.local_midnight(next_first_day)?,
+        })
+    }
+
+    // Start of a local day, skipping ahead when a DST change removes midnight
+    pub fn local_midnight(&self, day: NaiveDate) -> Option<DateTime<FixedOffset>> {
+        (0..3).find_map(|hour| {
+            let local = day.and_hms_opt(hour, 0, 0)?;
+            self.tz
+                .from_local_datetime(&local)
+                .earliest()
+                .map(|instant| instant.fixed_offset())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BudgetCalendar, Period, PeriodSelector};
+    use chrono::{DateTime, Duration, NaiveDate, Utc};
+    use chrono_tz::Tz;
+
+    fn at(text: &str) -> DateTime<Utc> {
+        text.parse().unwrap()
+    }
+
+    fn day(text: &str) -> NaiveDate {
+        text.parse().unwrap()
+    }
+
+    fn bounds(period: Option<Period>) -> (String, String) {
+        let period = period.unwrap();
+        (period.start.to_rfc3339(), period.end.to_rfc3339())
+    }
+
+    fn expected(start: &str, end: &str) -> (String, String) {
+        (start.to_string(), end.to_string())
+    }
+
+    fn selected(period: &str) -> Option<PeriodSelector> {
+        PeriodSelector::parse(Some(period), None, None).unwrap()
+    }
+
+    #[test]
+    fn keeps_the_period_start_day_in_every_month() {
+        assert_eq!(BudgetCalendar::new(Tz::UTC, 0).start_day, 1);
+        assert_eq!(BudgetCalendar::new(Tz::UTC, -5).start_day, 1);
+        assert_eq!(BudgetCalendar::new(Tz::UTC, 15).start_day, 15);
+        assert_eq!(BudgetCalendar::new(Tz::UTC, 31).start_day, 28);
+    }
+
+    #[test]
+    fn starts_months_on_the_period_start_day() {
+        let calendar = BudgetCalendar::new(Tz::UTC, 15);
+        assert_eq!(
+            bounds(calendar.period_containing("monthly", at("2026-10-18T12:00:00Z"))),
+            expected("2026-10-15T00:00:00+00:00", "2026-11-15T00:00:00+00:00")
+        );
+        assert_eq!(
+            bounds(calendar.period_containing("monthly", at("2026-10-14T23:59:59Z"))),
+            expected("2026-09-15T00:00:00+00:00", "2026-10-15T00:00:00+00:00")
+        );
+        // the last start day still falls in February
+        let calendar = BudgetCalendar::new(Tz::UTC, 28);
+        assert_eq!(
+            bounds(calendar.period_containing("monthly", at("2026-03-01T00:00:00Z"))),
+            expected("2026-02-28T00:00:00+00:00", "2026-03-28T00:00:00+00:00")
+        );
+    }
+
+    #[test]
+    fn cuts_periods_in_the_local_time_zone() {
+        let calendar = BudgetCalendar::new(Tz::America__Toronto, 1);
+        // still the 17th in Toronto
+        assert_eq!(
+            calendar.today(at("2026-10-18T02:00:00Z")),
+            day("2026-10-17")
+        );
+        assert_eq!(
+            bounds(calendar.period_containing("daily", at("2026-10-18T02:00:00Z"))),
+            expected("2026-10-17T00:00:00-04:00", "2026-10-18T00:00:00-04:00")
+        );
+        // weeks start on Monday, the 18th is a Sunday
+        assert_eq!(
+            bounds(calendar.period_containing("weekly", at("2026-10-18T12:00:00Z"))),
+            expected("2026-10-12T00:00:00-04:00", "2026-10-19T00:00:00-04:00")
+        );
+        assert_eq!(
+            bounds(calendar.period_containing("yearly", at("2026-10-18T12:00:00Z"))),
+            expected("2026-01-01T00:00:00-05:00", "2027-01-01T00:00:00-05:00")
+        );
+        assert!(calendar
+            .period_containing("hourly", at("2026-10-18T12:00:00Z"))
+            .is_none());
+    }
+
+    #[test]
+    fn follows_daylight_saving_changes() {
+        let calendar = BudgetCalendar::new(Tz::America__Toronto, 1);
+        let spring = calendar
+            .period_containing("daily", at("2026-03-08T12:00:00Z"))
+            .unwrap();
+        assert_eq!(spring.start.to_rfc3339(), "2026-03-08T00:00:00-05:00");
+        assert_eq!(spring.end.to_rfc3339(), "2026-03-09T00:00:00-04:00");
+        assert_eq!(spring.end_utc() - spring.start_utc(), Duration::hours(23));
+        let fall = calendar
+            .period_containing("daily", at("2026-11-01T12:00:00Z"))
+            .unwrap();
+        assert_eq!(fall.end_utc() - fall.start_utc(), Duration::hours(25));
+    }
+
+    #[test]
+    fn skips_ahead_when_midnight_does_not_exist() {
+        // Havana moves clocks from midnight straight to 1am
+        let calendar = BudgetCalendar::new(Tz::America__Havana, 1);
+        assert_eq!(
+            calendar
+                .local_midnight(day("2026-03-08"))
+                .unwrap()
+                .to_rfc3339(),
+            "2026-03-08T01:00:00-04:00"
+        );
+        assert_eq!(
+            calendar
+                .local_midnight(day("2026-03-09"))
+                .unwrap()
+                .to_rfc3339(),
+            "2026-03-09T00:00:00-04:00"
+        );
+    }
+
+    #[test]
+    fn finds_the_period_before() {
+        let calendar = BudgetCalendar::new(Tz::America__Toronto, 15);
+        let current = calendar
+            .period_containing("monthly", at("2026-03-20T12:00:00Z"))
+            .unwrap();
+        assert_eq!(
+            bounds(calendar.period_before("monthly", &current)),
+            expected("2026-02-15T00:00:00-05:00", "2026-03-15T00:00:00-04:00")
+        );
+    }
+
+    #[test]
+    fn parses_each_kind_of_period() {
+        assert!(matches!(selected("2026"), Some(PeriodSelector::Year(2026))));
+        assert!(matches!(
+            selected("2026-09"),
+            Some(PeriodSelector::Month(2026, 9))
+        ));
+        assert!(matches!(
+            selected(" 2026-W40 "),
+            Some(PeriodSelector::Week(2026, 40))
+        ));
+        assert!(matches!(
+            selected("2026-09-30"),
+            Some(PeriodSelector::Day(on)) if on == day("2026-09-30")
+        ));
+        assert!(matches!(
+            PeriodSelector::parse(None, Some("2026-09-01"), Some("2026-09-30")),
+            Ok(Some(PeriodSelector::Range(first, last)))
+                if first == day("2026-09-01") && last == day("2026-09-30")
+        ));
+        assert!(matches!(PeriodSelector::parse(None, None, None), Ok(None)));
+    }
+
+    #[test]
+    fn rejects_malformed_periods() {
+        for spec in [
+            "2026-13",
+            "2026-W54",
+            "2026-02-30",
+            "2026-09-x",
+            "last month",
+            "",
+        ] {
+            assert!(
+                PeriodSelector::parse(Some(spec), None, None).is_err(),
+                "{}",
+                spec
+            );
+        }
+        // backwards or partial ranges, or a period mixed with a range
+        assert!(PeriodSelector::parse(None, Some("2026-09-30"), Some("2026-09-01")).is_err());
+        assert!(PeriodSelector::parse(None, Some("2026-09-01"), None).is_err());
+        assert!(
+            PeriodSelector::parse(Some("2026"), Some("2026-09-01"), Some("2026-09-30")).is_err()
+        );
+    }
+
+    #[test]
+    fn steps_back_one_period_of_the_same_kind() {
+        assert!(matches!(
+            PeriodSelector::Year(2026).previous(),
+            Some(PeriodSelector::Year(2025))
+        ));
+        assert!(matches!(
+            PeriodSelector::Month(2026, 1).previous(),
+            Some(PeriodSelector::Month(2025, 12))
+        ));
+        // 2020 has 53 ISO weeks
+        assert!(matches!(
+            PeriodSelector::Week(2021, 1).previous(),
+            Some(PeriodSelector::Week(2020, 53))
+        ));
+        assert!(matches!(
+            PeriodSelector::Range(day("2026-09-01"), day("2026-09-10")).previous(),
+            Some(PeriodSelector::Range(first, last))
+                if first == day("2026-08-22") && last == day("2026-08-31")
+        ));
+    }
+
+    #[test]
+    fn resolves_selected_periods_in_the_users_calendar() {
+        let calendar = BudgetCalendar::new(Tz::America__Toronto, 15);
+        assert_eq!(
+            bounds(calendar.resolve(selected("2026-09").unwrap())),
+            expected("2026-09-15T00:00:00-04:00", "2026-10-15T00:00:00-04:00")
+        );
+        assert_eq!(
+            bounds(calendar.resolve(selected("2026-W40").unwrap())),
+            expected("2026-09-28T00:00:00-04:00", "2026-10-05T00:00:00-04:00")
+        );
+        // both days of a range are included
+        let range = PeriodSelector::Range(day("2026-09-01"), day("2026-09-30"));
+        assert_eq!(
+            bounds(calendar.resolve(range)),
+            expected("2026-09-01T00:00:00-04:00", "2026-10-01T00:00:00-04:00")
+        );
+        // week 53 parses for a year that has one only
+        assert!(selected("2026-W53").is_some());
+        assert!(PeriodSelector::parse(Some("2025-W53"), None, None).is_err());
+    }
+}
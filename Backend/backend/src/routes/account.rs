@@ -1,40 +1,94 @@
 use rocket::serde::json::Json;
 use rocket::State;
 use rocket::http::Status;
-use crate::models::account::NewAccount;
+use crate::models::account::{ClientAccount, ClientAccountUpdate};
 use crate::db::DbPool;
+use crate::guards::AuthUser;
 use crate::handlers::account_handler;
 use rocket::form::FromForm;
-use crate::models::account::Account;
+use crate::models::account::{Account, AccountBalance, BalanceHistory};
 #[allow(unused_imports)]
 use rocket::serde::Serialize;
 
 #[post("/account_create", format = "json", data = "<new_acc>")]
-pub async fn account_create(new_acc: Json<NewAccount>, pool: &State<DbPool>) -> (Status, String) {
-    account_handler::handle_account_create(new_acc.into_inner(), pool.inner().clone()).await
+pub async fn account_create(user: AuthUser, new_acc: Json<ClientAccount>, pool: &State<DbPool>) -> (Status, String) {
+    account_handler::handle_account_create(user, new_acc.into_inner(), pool.inner().clone()).await
 }
 
-// A struct to parse the query parameter
+// GET route listing the caller's accounts, archived ones only when asked for
+#[get("/account_summary?<include_archived>")]
+pub async fn account_summary(user: AuthUser, include_archived: Option<bool>, pool: &State<DbPool>) -> (Status, Json<Vec<Account>>) {
+    account_handler::handle_account_summary(user, include_archived.unwrap_or(false), pool.inner().clone()).await
+}
+
+// For /account_update
 #[derive(FromForm)]
-pub struct AccountQuery {
-    pub email: String,
+pub struct UpdateAccountQuery {
+    pub account_name: String,
 }
 
-// GET route that uses a query parameter
-#[get("/account_summary?<account_query..>")]
-pub async fn account_summary(account_query: AccountQuery, pool: &State<DbPool>) -> (Status, Json<Vec<Account>>) {
-    account_handler::handle_account_summary(account_query.email, pool.inner().clone()).await
+// PATCH
+#[patch("/account_update?<update_query..>", format = "json", data = "<changes>")]
+pub async fn account_update(user: AuthUser, update_query: UpdateAccountQuery, changes: Json<ClientAccountUpdate>, pool: &State<DbPool>) -> (Status, &'static str) {
+    account_handler::handle_account_update(user, update_query.account_name, changes.into_inner(), pool.inner().clone()).await
 }
 
 // For /delete_account
 #[derive(FromForm)]
 pub struct DeleteAccountQuery {
-    pub email: String,
     pub account_name: String,
 }
 
-// DELETE
+// DELETE, archives the account
 #[delete("/delete_account?<delete_query..>")]
-pub async fn delete_account(delete_query: DeleteAccountQuery, pool: &State<DbPool>) -> (Status, &'static str) {
-    account_handler::handle_delete_account(delete_query.email, delete_query.account_name, pool.inner().clone()).await
-}
\ No newline at end of file
+pub async fn delete_account(user: AuthUser, delete_query: DeleteAccountQuery, pool: &State<DbPool>) -> (Status, &'static str) {
+    account_handler::handle_delete_account(user, delete_query.account_name, pool.inner().clone()).await
+}
+
+// PATCH
+#[patch("/unarchive_account?<unarchive_query..>")]
+pub async fn unarchive_account(user: AuthUser, unarchive_query: DeleteAccountQuery, pool: &State<DbPool>) -> (Status, &'static str) {
+    account_handler::handle_unarchive_account(user, unarchive_query.account_name, pool.inner().clone()).await
+}
+
+// For /purge_account, `confirm` repeats the account name
+#[derive(FromForm)]
+pub struct PurgeAccountQuery {
+    pub account_name: String,
+    pub confirm: Option<String>,
+}
+
+// DELETE, really deletes an archived account and its transactions
+#[delete("/purge_account?<purge_query..>")]
+pub async fn purge_account(user: AuthUser, purge_query: PurgeAccountQuery, pool: &State<DbPool>) -> (Status, &'static str) {
+    account_handler::handle_purge_account(user, purge_query.account_name, purge_query.confirm, pool.inner().clone()).await
+}
+
+// GET route with the current balance of one account, or of every account without a name
+#[get("/account_balance?<account_name>&<include_archived>")]
+pub async fn account_balance(user: AuthUser, account_name: Option<String>, include_archived: Option<bool>, pool: &State<DbPool>) -> Result<Json<Vec<AccountBalance>>, (Status, String)> {
+    account_handler::handle_account_balance(user, account_name, include_archived.unwrap_or(false), pool.inner().clone()).await
+}
+
+// For /account_balance_history, `interval` defaults to monthly, `from` and `to` are YYYY-MM-DD
+#[derive(FromForm)]
+pub struct BalanceHistoryQuery {
+    pub account_name: String,
+    pub interval: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+// GET route with the balance of an account over time
+#[get("/account_balance_history?<history_query..>")]
+pub async fn account_balance_history(user: AuthUser, history_query: BalanceHistoryQuery, pool: &State<DbPool>) -> Result<Json<BalanceHistory>, (Status, String)> {
+    account_handler::handle_balance_history(
+        user,
+        history_query.account_name,
+        history_query.interval,
+        history_query.from,
+        history_query.to,
+        pool.inner().clone(),
+    )
+    .await
+}
@@ -1,11 +1,43 @@
 use rocket::serde::json::Json;
 use rocket::State;
 use rocket::http::Status;
-use crate::models::user::{NewUser};
+use crate::models::session::LoginResponse;
+use crate::models::user::{ClientSettings, LoginUser, NewUser, UserSettings};
 use crate::db::DbPool;
+use crate::guards::AuthUser;
 use crate::handlers::auth_handler;
 
 #[post("/signup", format = "json", data = "<new_user>")]
 pub async fn signup(new_user: Json<NewUser>, pool: &State<DbPool>) -> (Status, &'static str) {
     auth_handler::handle_signup(new_user.into_inner(), pool.inner().clone()).await
 }
+
+#[post("/login", format = "json", data = "<credentials>")]
+pub async fn login(
+    credentials: Json<LoginUser>,
+    pool: &State<DbPool>,
+) -> Result<Json<LoginResponse>, (Status, &'static str)> {
+    auth_handler::handle_login(credentials.into_inner(), pool.inner().clone()).await
+}
+
+#[delete("/logout")]
+pub async fn logout(user: AuthUser, pool: &State<DbPool>) -> (Status, &'static str) {
+    auth_handler::handle_logout(user, pool.inner().clone()).await
+}
+
+#[get("/settings")]
+pub async fn get_settings(
+    user: AuthUser,
+    pool: &State<DbPool>,
+) -> Result<Json<UserSettings>, (Status, &'static str)> {
+    auth_handler::handle_get_settings(user, pool.inner().clone()).await
+}
+
+#[patch("/settings", format = "json", data = "<changes>")]
+pub async fn update_settings(
+    user: AuthUser,
+    changes: Json<ClientSettings>,
+    pool: &State<DbPool>,
+) -> (Status, &'static str) {
+    auth_handler::handle_update_settings(user, changes.into_inner(), pool.inner().clone()).await
+}
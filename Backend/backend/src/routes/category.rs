@@ -1,7 +1,8 @@
 use crate::db::DbPool;
+use crate::guards::AuthUser;
 use crate::handlers::category_handler;
 use crate::models::category::Category;
-use crate::models::category::NewCategory;
+use crate::models::category::ClientCategory;
 use rocket::http::Status;
 use rocket::serde::json::Json;
 #[allow(unused_imports)]
@@ -9,50 +10,123 @@ use rocket::serde::Serialize;
 use rocket::State;
 
 #[post("/category_create", format = "json", data = "<new_cat>")]
-pub async fn category_create(new_cat: Json<NewCategory>, pool: &State<DbPool>) -> (Status, String) {
-    category_handler::handle_category_create(new_cat.into_inner(), pool.inner().clone()).await
+pub async fn category_create(
+    user: AuthUser,
+    new_cat: Json<ClientCategory>,
+    pool: &State<DbPool>,
+) -> (Status, String) {
+    category_handler::handle_category_create(user, new_cat.into_inner(), pool.inner().clone())
+        .await
 }
 
-// For /delete_category
+// For /delete_category, `reassign_to` names the category that takes over its transactions
 #[derive(FromForm)]
 pub struct DeleteCategoryQuery {
-    pub email: String,
     pub category_nickname: String,
+    pub reassign_to: Option<String>,
 }
 
-// DELETE
+// DELETE, archives the category
 #[delete("/delete_category?<delete_query..>")]
 pub async fn delete_category(
+    user: AuthUser,
     delete_query: DeleteCategoryQuery,
     pool: &State<DbPool>,
 ) -> (Status, &'static str) {
     category_handler::handle_delete_category(
-        delete_query.email,
+        user,
         delete_query.category_nickname,
+        delete_query.reassign_to,
         pool.inner().clone(),
     )
     .await
 }
 
-// A struct to parse the query parameter
+// For /merge_category, `into` is the category that is kept
+#[derive(FromForm)]
+pub struct MergeCategoryQuery {
+    pub category_nickname: String,
+    pub into: String,
+}
+
+// POST, merges a category into another one
+#[post("/merge_category?<merge_query..>")]
+pub async fn merge_category(
+    user: AuthUser,
+    merge_query: MergeCategoryQuery,
+    pool: &State<DbPool>,
+) -> (Status, &'static str) {
+    category_handler::handle_merge_category(
+        user,
+        merge_query.category_nickname,
+        merge_query.into,
+        pool.inner().clone(),
+    )
+    .await
+}
+
+// For /unarchive_category
+#[derive(FromForm)]
+pub struct UnarchiveCategoryQuery {
+    pub category_nickname: String,
+}
+
+// PATCH
+#[patch("/unarchive_category?<unarchive_query..>")]
+pub async fn unarchive_category(
+    user: AuthUser,
+    unarchive_query: UnarchiveCategoryQuery,
+    pool: &State<DbPool>,
+) -> (Status, &'static str) {
+    category_handler::handle_unarchive_category(
+        user,
+        unarchive_query.category_nickname,
+        pool.inner().clone(),
+    )
+    .await
+}
+
+// For /purge_category, `confirm` repeats the category nickname
 #[derive(FromForm)]
-pub struct CategoryQuery {
-    pub email: String,
+pub struct PurgeCategoryQuery {
+    pub category_nickname: String,
+    pub confirm: Option<String>,
+}
+
+// DELETE, really deletes an archived category and its transactions
+#[delete("/purge_category?<purge_query..>")]
+pub async fn purge_category(
+    user: AuthUser,
+    purge_query: PurgeCategoryQuery,
+    pool: &State<DbPool>,
+) -> (Status, &'static str) {
+    category_handler::handle_purge_category(
+        user,
+        purge_query.category_nickname,
+        purge_query.confirm,
+        pool.inner().clone(),
+    )
+    .await
 }
 
-// GET route that uses a query parameter
-#[get("/category_summary?<category_query..>")]
+// GET route listing the caller's categories, archived ones only when asked for
+#[get("/category_summary?<include_archived>")]
 pub async fn category_summary(
-    category_query: CategoryQuery,
+    user: AuthUser,
+    include_archived: Option<bool>,
     pool: &State<DbPool>,
 ) -> (Status, Json<Vec<Category>>) {
-    category_handler::handle_category_summary(category_query.email, pool.inner().clone()).await
+    category_handler::handle_category_summary(
+        user,
+        include_archived.unwrap_or(false),
+        pool.inner().clone(),
+    )
+    .await
 }
 
 // A struct to parse the query parameter
 #[derive(FromForm)]
 pub struct CategoryUpdateQuery {
-    pub email: String,
     pub field: String,
     pub category_nickname: String,
     pub new_value: String,
@@ -61,11 +135,12 @@ pub struct CategoryUpdateQuery {
 // POST route that uses a query parameter
 #[post("/category_update?<update_query..>")]
 pub async fn category_update(
+    user: AuthUser,
     update_query: CategoryUpdateQuery,
     pool: &State<DbPool>,
 ) -> (Status, String) {
     category_handler::handle_category_update(
-        update_query.email,
+        user,
         update_query.field,
         update_query.category_nickname,
         update_query.new_value,
@@ -0,0 +1,11 @@
+use crate::db::DbPool;
+use crate::guards::AuthUser;
+use crate::handlers::exchange_rate_handler;
+use rocket::http::Status;
+use rocket::State;
+
+// POST, body is the CSV text, rate administrators only
+#[post("/import_rates", data = "<csv_body>")]
+pub async fn import_rates(user: AuthUser, csv_body: String, pool: &State<DbPool>) -> (Status, String) {
+    exchange_rate_handler::handle_import_rates(user, csv_body, pool.inner().clone()).await
+}
@@ -0,0 +1,46 @@
+use crate::db::DbPool;
+use crate::guards::AuthUser;
+use crate::handlers::import_handler;
+use crate::models::import::{ImportMapping, ImportPreview, ImportRequest, ImportResult};
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::State;
+
+// POST, shows how a statement would be imported without saving anything
+#[post("/import_preview", format = "json", data = "<import_request>")]
+pub async fn import_preview(
+    user: AuthUser,
+    import_request: Json<ImportRequest>,
+    pool: &State<DbPool>,
+) -> Result<Json<ImportPreview>, (Status, String)> {
+    import_handler::handle_import_preview(user, import_request.into_inner(), pool.inner().clone())
+        .await
+}
+
+// POST, same body as /import_preview
+#[post("/import_commit", format = "json", data = "<import_request>")]
+pub async fn import_commit(
+    user: AuthUser,
+    import_request: Json<ImportRequest>,
+    pool: &State<DbPool>,
+) -> Result<Json<ImportResult>, (Status, String)> {
+    import_handler::handle_import_commit(user, import_request.into_inner(), pool.inner().clone())
+        .await
+}
+
+// For /import_profile
+#[derive(FromForm)]
+pub struct ImportProfileQuery {
+    pub account_name: String,
+}
+
+// GET, the mapping saved by the last import into the account
+#[get("/import_profile?<profile_query..>")]
+pub async fn import_profile(
+    user: AuthUser,
+    profile_query: ImportProfileQuery,
+    pool: &State<DbPool>,
+) -> Result<Json<ImportMapping>, (Status, String)> {
+    import_handler::handle_import_profile(user, profile_query.account_name, pool.inner().clone())
+        .await
+}
@@ -1,5 +1,12 @@
 pub mod account;
 pub mod auth;
 pub mod category;
+pub mod exchange_rate;
+pub mod import;
+pub mod notification;
+pub mod payee;
+pub mod recurring;
 pub mod report;
+pub mod split;
 pub mod transaction;
+pub mod transfer;
@@ -0,0 +1,28 @@
+use crate::db::DbPool;
+use crate::guards::AuthUser;
+use crate::handlers::notification_handler;
+use crate::models::notification::Notification;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::State;
+
+// GET, newest first, `unread=true` leaves out the ones already read
+#[get("/notifications?<unread>")]
+pub async fn notifications(
+    user: AuthUser,
+    unread: Option<bool>,
+    pool: &State<DbPool>,
+) -> (Status, Json<Vec<Notification>>) {
+    notification_handler::handle_notifications(user, unread, pool.inner().clone()).await
+}
+
+// PATCH, every unread notification when no id is given
+#[patch("/read_notifications?<notification_id>")]
+pub async fn read_notifications(
+    user: AuthUser,
+    notification_id: Option<i32>,
+    pool: &State<DbPool>,
+) -> (Status, String) {
+    notification_handler::handle_read_notifications(user, notification_id, pool.inner().clone())
+        .await
+}
@@ -0,0 +1,46 @@
+use crate::db::DbPool;
+use crate::guards::AuthUser;
+use crate::handlers::payee_handler;
+use crate::models::payee::PayeeSummary;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::State;
+
+// For /payees, `search` narrows the list down for autocompletion
+#[derive(FromForm)]
+pub struct PayeeListQuery {
+    pub search: Option<String>,
+}
+
+// GET
+#[get("/payees?<list_query..>")]
+pub async fn payees(
+    user: AuthUser,
+    list_query: PayeeListQuery,
+    pool: &State<DbPool>,
+) -> (Status, Json<Vec<PayeeSummary>>) {
+    payee_handler::handle_payee_list(user, list_query.search, pool.inner().clone()).await
+}
+
+// For /merge_payee, `into` is the payee that is kept
+#[derive(FromForm)]
+pub struct MergePayeeQuery {
+    pub payee_name: String,
+    pub into: String,
+}
+
+// POST, merges a duplicate payee into another one
+#[post("/merge_payee?<merge_query..>")]
+pub async fn merge_payee(
+    user: AuthUser,
+    merge_query: MergePayeeQuery,
+    pool: &State<DbPool>,
+) -> (Status, &'static str) {
+    payee_handler::handle_merge_payee(
+        user,
+        merge_query.payee_name,
+        merge_query.into,
+        pool.inner().clone(),
+    )
+    .await
+}
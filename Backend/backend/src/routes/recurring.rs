@@ -0,0 +1,60 @@
+use crate::db::DbPool;
+use crate::guards::AuthUser;
+use crate::handlers::recurring_handler;
+use crate::models::recurring::{ClientRecurring, ClientRecurringUpdate, RecurringSummary};
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::State;
+
+#[post("/recurring_create", format = "json", data = "<new_rec>")]
+pub async fn recurring_create(
+    user: AuthUser,
+    new_rec: Json<ClientRecurring>,
+    pool: &State<DbPool>,
+) -> (Status, String) {
+    recurring_handler::handle_recurring_create(user, new_rec.into_inner(), pool.inner().clone())
+        .await
+}
+
+// GET route listing the caller's recurring transactions
+#[get("/recurring_summary")]
+pub async fn recurring_summary(
+    user: AuthUser,
+    pool: &State<DbPool>,
+) -> (Status, Json<Vec<RecurringSummary>>) {
+    recurring_handler::handle_recurring_summary(user, pool.inner().clone()).await
+}
+
+// For /recurring_update and /delete_recurring
+#[derive(FromForm)]
+pub struct RecurringQuery {
+    pub recurring_id: String,
+}
+
+// PATCH, also pauses and resumes
+#[patch("/recurring_update?<rec_query..>", format = "json", data = "<changes>")]
+pub async fn recurring_update(
+    user: AuthUser,
+    rec_query: RecurringQuery,
+    changes: Json<ClientRecurringUpdate>,
+    pool: &State<DbPool>,
+) -> (Status, &'static str) {
+    recurring_handler::handle_recurring_update(
+        user,
+        rec_query.recurring_id,
+        changes.into_inner(),
+        pool.inner().clone(),
+    )
+    .await
+}
+
+// DELETE
+#[delete("/delete_recurring?<rec_query..>")]
+pub async fn delete_recurring(
+    user: AuthUser,
+    rec_query: RecurringQuery,
+    pool: &State<DbPool>,
+) -> (Status, &'static str) {
+    recurring_handler::handle_delete_recurring(user, rec_query.recurring_id, pool.inner().clone())
+        .await
+}
@@ -1,38 +1,126 @@
 use crate::db::DbPool;
+use crate::guards::AuthUser;
 use crate::handlers::report_handler;
-use report_handler::CategorySummary;
+use report_handler::{CashFlowReport, CategorySummary, NetWorthReport, PayeeReport, TagReport};
 use rocket::http::Status;
 use rocket::serde::json::Json;
 #[allow(unused_imports)]
 use rocket::serde::Serialize;
 use rocket::State;
 
-// For /report_overview
-#[derive(FromForm)]
-pub struct OverviewQuery {
-    pub email: String,
-}
-
 // GET
-#[get("/report_overview?<overview_query..>")]
+#[get("/report_overview?<base_currency>")]
 pub async fn report_overview(
-    overview_query: OverviewQuery,
+    user: AuthUser,
+    base_currency: Option<String>,
     pool: &State<DbPool>,
 ) -> (Status, Json<Vec<String>>) {
-    report_handler::handle_report_overview(overview_query.email, pool.inner().clone()).await
+    report_handler::handle_report_overview(user, base_currency, pool.inner().clone()).await
 }
 
-// For /report_details
+// For /report_details, `period` or `from` and `to` pick a period other than the current one
 #[derive(FromForm)]
-pub struct DetailsQuery {
-    pub email: String,
+pub struct ReportDetailsQuery {
+    pub base_currency: Option<String>,
+    pub period: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
 }
 
 // GET
 #[get("/report_details?<details_query..>")]
 pub async fn report_details(
-    details_query: DetailsQuery,
+    user: AuthUser,
+    details_query: ReportDetailsQuery,
+    pool: &State<DbPool>,
+) -> Result<Json<Vec<CategorySummary>>, (Status, String)> {
+    report_handler::handle_report_details(
+        user,
+        details_query.base_currency,
+        details_query.period,
+        details_query.from,
+        details_query.to,
+        pool.inner().clone(),
+    )
+    .await
+}
+
+// GET
+#[get("/report_cashflow?<period>&<base_currency>")]
+pub async fn report_cashflow(
+    user: AuthUser,
+    period: Option<String>,
+    base_currency: Option<String>,
+    pool: &State<DbPool>,
+) -> Result<Json<CashFlowReport>, (Status, String)> {
+    report_handler::handle_report_cashflow(user, period, base_currency, pool.inner().clone()).await
+}
+
+// GET, `as_of` and `from` are YYYY-MM-DD days
+#[get("/report_networth?<as_of>&<from>&<base_currency>")]
+pub async fn report_networth(
+    user: AuthUser,
+    as_of: Option<String>,
+    from: Option<String>,
+    base_currency: Option<String>,
+    pool: &State<DbPool>,
+) -> Result<Json<NetWorthReport>, (Status, String)> {
+    report_handler::handle_report_networth(user, as_of, from, base_currency, pool.inner().clone())
+        .await
+}
+
+// For /report_tags, `period` or `from` and `to` pick a period other than the current month
+#[derive(FromForm)]
+pub struct ReportTagsQuery {
+    pub base_currency: Option<String>,
+    pub period: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+// GET
+#[get("/report_tags?<tags_query..>")]
+pub async fn report_tags(
+    user: AuthUser,
+    tags_query: ReportTagsQuery,
+    pool: &State<DbPool>,
+) -> Result<Json<TagReport>, (Status, String)> {
+    report_handler::handle_report_tags(
+        user,
+        tags_query.period,
+        tags_query.from,
+        tags_query.to,
+        tags_query.base_currency,
+        pool.inner().clone(),
+    )
+    .await
+}
+
+// For /report_payees, `limit` is how many payees to list, 10 by default
+#[derive(FromForm)]
+pub struct ReportPayeesQuery {
+    pub base_currency: Option<String>,
+    pub period: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub limit: Option<String>,
+}
+
+// GET
+#[get("/report_payees?<payees_query..>")]
+pub async fn report_payees(
+    user: AuthUser,
+    payees_query: ReportPayeesQuery,
     pool: &State<DbPool>,
-) -> (Status, Json<Vec<CategorySummary>>) {
-    report_handler::handle_report_details(details_query.email, pool.inner().clone()).await
+) -> Result<Json<PayeeReport>, (Status, String)> {
+    report_handler::handle_report_payees(
+        user,
+        payees_query.period,
+        payees_query.from,
+        payees_query.to,
+        payees_query.base_currency,
+        payees_query.limit,
+        pool.inner().clone(),
+    )
+    .await
 }
@@ -0,0 +1,42 @@
+use crate::db::DbPool;
+use crate::guards::AuthUser;
+use crate::handlers::split_handler;
+use crate::models::split::{ClientSplit, SplitDetails};
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::State;
+
+#[post("/split_create", format = "json", data = "<new_split>")]
+pub async fn split_create(
+    user: AuthUser,
+    new_split: Json<ClientSplit>,
+    pool: &State<DbPool>,
+) -> (Status, String) {
+    split_handler::handle_create_split(user, new_split.into_inner(), pool.inner().clone()).await
+}
+
+// For /split_details and /delete_split
+#[derive(FromForm)]
+pub struct SplitQuery {
+    pub split_id: String,
+}
+
+// GET route with a split and its category lines
+#[get("/split_details?<split_query..>")]
+pub async fn split_details(
+    user: AuthUser,
+    split_query: SplitQuery,
+    pool: &State<DbPool>,
+) -> Result<Json<SplitDetails>, (Status, String)> {
+    split_handler::handle_split_details(user, split_query.split_id, pool.inner().clone()).await
+}
+
+// DELETE, the category lines go with the split
+#[delete("/delete_split?<split_query..>")]
+pub async fn delete_split(
+    user: AuthUser,
+    split_query: SplitQuery,
+    pool: &State<DbPool>,
+) -> (Status, &'static str) {
+    split_handler::handle_delete_split(user, split_query.split_id, pool.inner().clone()).await
+}
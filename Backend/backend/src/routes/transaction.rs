@@ -1,8 +1,9 @@
 use crate::db::DbPool;
+use crate::guards::AuthUser;
 use crate::handlers::transaction_handler;
-use crate::models::transaction::ClientTransaction;
+use crate::models::transaction::{AccountTransaction, ClientTransaction, ClientTransactionUpdate};
 //use crate::models::transaction::NewTransaction;
-use crate::models::transaction::Transaction;
+use crate::models::transaction::{TaggedTransaction, TransactionPage, TransactionSearch};
 use rocket::http::Status;
 use rocket::serde::json::Json;
 #[allow(unused_imports)]
@@ -11,10 +12,12 @@ use rocket::State;
 
 #[post("/add_trans", format = "json", data = "<new_trans>")]
 pub async fn add_trans(
+    user: AuthUser,
     new_trans: Json<ClientTransaction>,
     pool: &State<DbPool>,
 ) -> (Status, String) {
-    transaction_handler::handle_add_transaction(new_trans.into_inner(), pool.inner().clone()).await
+    transaction_handler::handle_add_transaction(user, new_trans.into_inner(), pool.inner().clone())
+        .await
 }
 
 // For /delete_trans
@@ -26,29 +29,60 @@ pub struct DeleteTransQuery {
 // DELETE
 #[delete("/delete_trans?<delete_query..>")]
 pub async fn delete_trans(
+    user: AuthUser,
     delete_query: DeleteTransQuery,
     pool: &State<DbPool>,
 ) -> (Status, &'static str) {
-    transaction_handler::handle_delete_transaction(delete_query.trans_id, pool.inner().clone())
-        .await
+    transaction_handler::handle_delete_transaction(
+        user,
+        delete_query.trans_id,
+        pool.inner().clone(),
+    )
+    .await
+}
+
+// For /update_trans
+#[derive(FromForm)]
+pub struct UpdateTransQuery {
+    pub trans_id: String,
+}
+
+// PATCH
+#[patch("/update_trans?<update_query..>", format = "json", data = "<changes>")]
+pub async fn update_trans(
+    user: AuthUser,
+    update_query: UpdateTransQuery,
+    changes: Json<ClientTransactionUpdate>,
+    pool: &State<DbPool>,
+) -> (Status, &'static str) {
+    transaction_handler::handle_update_transaction(
+        user,
+        update_query.trans_id,
+        changes.into_inner(),
+        pool.inner().clone(),
+    )
+    .await
 }
 
 // For /category_trans
 #[derive(FromForm)]
 pub struct CategoryTransQuery {
     pub category_name: String,
-    pub email: String,
+    // only the transactions with this tag
+    pub tag: Option<String>,
 }
 
 // GET
 #[get("/category_trans?<category_query..>")]
 pub async fn category_summary_trans(
+    user: AuthUser,
     category_query: CategoryTransQuery,
     pool: &State<DbPool>,
-) -> (Status, Json<Vec<Transaction>>) {
+) -> (Status, Json<Vec<TaggedTransaction>>) {
     transaction_handler::handle_category_summary(
-        category_query.email,
+        user,
         category_query.category_name,
+        category_query.tag,
         pool.inner().clone(),
     )
     .await
@@ -58,19 +92,63 @@ pub async fn category_summary_trans(
 #[derive(FromForm)]
 pub struct AccountTransQuery {
     pub account_name: String,
-    pub email: String,
+    // only the transactions with this tag
+    pub tag: Option<String>,
 }
 
 // GET
 #[get("/account_trans?<account_query..>")]
 pub async fn account_summary_trans(
+    user: AuthUser,
     account_query: AccountTransQuery,
     pool: &State<DbPool>,
-) -> (Status, Json<Vec<Transaction>>) {
+) -> (Status, Json<Vec<AccountTransaction>>) {
     transaction_handler::handle_account_summary(
-        account_query.email,
+        user,
         account_query.account_name,
+        account_query.tag,
         pool.inner().clone(),
     )
     .await
 }
+
+// For /transactions, `category` and `account` may be repeated to match any of them
+#[derive(FromForm)]
+pub struct TransactionSearchQuery {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub min_amount: Option<String>,
+    pub max_amount: Option<String>,
+    pub category: Vec<String>,
+    pub account: Vec<String>,
+    pub q: Option<String>,
+    pub tag: Option<String>,
+    pub payee: Option<String>,
+    pub sort: Option<String>,
+    pub limit: Option<String>,
+    pub cursor: Option<String>,
+}
+
+// GET, one page of the transactions matching every filter given
+#[get("/transactions?<search_query..>")]
+pub async fn transactions(
+    user: AuthUser,
+    search_query: TransactionSearchQuery,
+    pool: &State<DbPool>,
+) -> Result<Json<TransactionPage>, (Status, String)> {
+    let search = TransactionSearch {
+        from: search_query.from,
+        to: search_query.to,
+        min_amount: search_query.min_amount,
+        max_amount: search_query.max_amount,
+        categories: search_query.category,
+        accounts: search_query.account,
+        q: search_query.q,
+        tag: search_query.tag,
+        payee: search_query.payee,
+        sort: search_query.sort,
+        limit: search_query.limit,
+        cursor: search_query.cursor,
+    };
+    transaction_handler::handle_transaction_search(user, search, pool.inner().clone()).await
+}
@@ -0,0 +1,17 @@
+use crate::db::DbPool;
+use crate::guards::AuthUser;
+use crate::handlers::transfer_handler;
+use crate::models::transfer::ClientTransfer;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::State;
+
+#[post("/transfer", format = "json", data = "<new_transfer>")]
+pub async fn transfer(
+    user: AuthUser,
+    new_transfer: Json<ClientTransfer>,
+    pool: &State<DbPool>,
+) -> (Status, String) {
+    transfer_handler::handle_create_transfer(user, new_transfer.into_inner(), pool.inner().clone())
+        .await
+}
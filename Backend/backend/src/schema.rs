@@ -6,6 +6,10 @@ diesel::table! {
         email -> Text,
         account_type -> Text,
         account_name -> Text,
+        currency -> Text,
+        opening_balance -> Numeric,
+        opening_date -> Nullable<Date>,
+        archived_at -> Nullable<Timestamptz>,
     }
 }
 
@@ -15,8 +19,117 @@ diesel::table! {
         email -> Text,
         nickname -> Text,
         category_type -> Text,
-        budget -> Float8,
+        budget -> Numeric,
         budget_freq -> Text,
+        rollover -> Text,
+        alert_thresholds -> Array<Nullable<Int4>>,
+        archived_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    exchange_rates (rate_date, from_currency, to_currency) {
+        rate_date -> Date,
+        from_currency -> Text,
+        to_currency -> Text,
+        rate -> Numeric,
+    }
+}
+
+diesel::table! {
+    import_profiles (profile_id) {
+        profile_id -> Int4,
+        email -> Text,
+        account_id -> Int4,
+        category_id -> Nullable<Int4>,
+        has_header -> Bool,
+        date_column -> Text,
+        date_format -> Text,
+        amount_column -> Nullable<Text>,
+        debit_column -> Nullable<Text>,
+        credit_column -> Nullable<Text>,
+        description_column -> Nullable<Text>,
+        payee_column -> Nullable<Text>,
+        sign_convention -> Text,
+        updated_at -> Timestamptz,
+        inflow_category_id -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    notifications (notification_id) {
+        notification_id -> Int4,
+        email -> Text,
+        category_id -> Int4,
+        threshold -> Int4,
+        period_start -> Timestamptz,
+        message -> Text,
+        created_at -> Timestamptz,
+        read_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    payees (payee_id) {
+        payee_id -> Int4,
+        email -> Text,
+        name -> Text,
+    }
+}
+
+diesel::table! {
+    recurring_transactions (recurring_id) {
+        recurring_id -> Int4,
+        email -> Text,
+        category_id -> Int4,
+        account_id -> Int4,
+        amount -> Numeric,
+        notes -> Nullable<Text>,
+        currency -> Nullable<Text>,
+        frequency -> Text,
+        interval_count -> Int4,
+        start_date -> Date,
+        end_date -> Nullable<Date>,
+        posted_count -> Int4,
+        next_date -> Date,
+        paused -> Bool,
+    }
+}
+
+diesel::table! {
+    sessions (token) {
+        token -> Text,
+        email -> Text,
+        created_at -> Timestamptz,
+        expires_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    splits (split_id) {
+        split_id -> Int4,
+        email -> Text,
+        account_id -> Int4,
+        amount -> Numeric,
+        notes -> Nullable<Text>,
+        transaction_date -> Timestamptz,
+        currency -> Nullable<Text>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    tags (tag_id) {
+        tag_id -> Int4,
+        email -> Text,
+        name -> Text,
+    }
+}
+
+diesel::table! {
+    transaction_tags (trans_id, tag_id) {
+        trans_id -> Int4,
+        tag_id -> Int4,
     }
 }
 
@@ -24,11 +137,23 @@ diesel::table! {
     transactions (trans_id) {
         trans_id -> Int4,
         email -> Text,
-        category_id -> Int4,
-        amount -> Float8,
+        category_id -> Nullable<Int4>,
+        amount -> Numeric,
         notes -> Nullable<Text>,
         account_id -> Int4,
-        transaction_date -> Text,
+        transaction_date -> Timestamptz,
+        currency -> Nullable<Text>,
+        transfer_id -> Nullable<Int4>,
+        split_id -> Nullable<Int4>,
+        payee_id -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    transfers (transfer_id) {
+        transfer_id -> Int4,
+        email -> Text,
+        created_at -> Timestamptz,
     }
 }
 
@@ -38,15 +163,37 @@ diesel::table! {
         email -> Text,
         password -> Text,
         username -> Text,
+        time_zone -> Text,
+        period_start_day -> Int4,
     }
 }
 
+diesel::joinable!(import_profiles -> accounts (account_id));
+diesel::joinable!(notifications -> categories (category_id));
+diesel::joinable!(recurring_transactions -> accounts (account_id));
+diesel::joinable!(recurring_transactions -> categories (category_id));
+diesel::joinable!(splits -> accounts (account_id));
+diesel::joinable!(transaction_tags -> tags (tag_id));
+diesel::joinable!(transaction_tags -> transactions (trans_id));
 diesel::joinable!(transactions -> accounts (account_id));
 diesel::joinable!(transactions -> categories (category_id));
+diesel::joinable!(transactions -> payees (payee_id));
+diesel::joinable!(transactions -> splits (split_id));
+diesel::joinable!(transactions -> transfers (transfer_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
     accounts,
     categories,
+    exchange_rates,
+    import_profiles,
+    notifications,
+    payees,
+    recurring_transactions,
+    sessions,
+    splits,
+    tags,
+    transaction_tags,
     transactions,
+    transfers,
     users,
 );
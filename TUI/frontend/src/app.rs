@@ -1,8 +1,13 @@
-use crate::ui::report::{get_report_overview, get_category_overview, get_account_overview};
+use crate::ui::report::{
+    get_account_overview, get_category_overview, get_report_overview, get_unread_notifications,
+    read_notifications,
+};
+use crate::ui::import_main::ImportMain;
 use crate::ui::transaction_create::TransactionCreate;
 use crate::ui::{
     account_main::AccountMain, category_main::CategoryMain, cover::CoverPage, homepage::Homepage,
-    login::LoginPage, report_main::ReportMain, signup::SignupPage,
+    login::{logout, LoginPage}, recurring_main::RecurringMain, report_main::ReportMain,
+    signup::SignupPage,
 };
 #[allow(unused_imports)]
 use crossterm::event::{self, Event, KeyCode, KeyModifiers};
@@ -17,7 +22,9 @@ pub enum State {
     AccountMain,       // Account Main page
     CategoryMain,      // Category Main page
     ReportMain,        // Report page
+    RecurringMain,     // Recurring transactions page
     TransactionCreate, // Transaction Create page
+    ImportMain,        // Statement import page
 }
 
 pub struct App {
@@ -29,7 +36,9 @@ pub struct App {
     pub account_main: Option<AccountMain>, // Account Main (accessed from homepage)
     pub category_main: Option<CategoryMain>, // Category Main (accessed from homepage)
     pub report_main: Option<ReportMain>, // Report page (accessed from homepage)
+    pub recurring_main: Option<RecurringMain>, // Recurring transactions (accessed from homepage)
     pub transaction_create: Option<TransactionCreate>, // Transaction Create page
+    pub import_main: Option<ImportMain>, // Statement import (accessed from homepage)
 }
 
 impl App {
@@ -43,7 +52,9 @@ impl App {
             account_main: None,       // Initially, account_main is not set
             category_main: None,      // Initially, category_main is not set
             report_main: None,        // Initially, report page is not set
+            recurring_main: None,     // Initially, recurring page is not set
             transaction_create: None, // Initially, transaction_create is not set
+            import_main: None,        // Initially, import page is not set
         }
     }
 }
@@ -56,9 +67,10 @@ pub async fn run_app<B: ratatui::backend::Backend>(
         // Render the current state of the app
         // before rendering, load the overview everytime
         if let Some(ref mut curr_homepage) = app.homepage {
-            curr_homepage.report_overview = get_report_overview(curr_homepage.email.clone()).await;
-            curr_homepage.category_overview = get_category_overview(curr_homepage.email.clone()).await;
-            curr_homepage.account_overview = get_account_overview(curr_homepage.email.clone()).await;
+            curr_homepage.report_overview = get_report_overview(curr_homepage.token.clone()).await;
+            curr_homepage.category_overview = get_category_overview(curr_homepage.token.clone()).await;
+            curr_homepage.account_overview = get_account_overview(curr_homepage.token.clone()).await;
+            curr_homepage.notifications = get_unread_notifications(curr_homepage.token.clone()).await;
         }
         terminal.draw(|f| match app.state {
             State::Cover => app.cover_page.render(f),
@@ -84,11 +96,21 @@ pub async fn run_app<B: ratatui::backend::Backend>(
                     report_main.render(f);
                 }
             }
+            State::RecurringMain => {
+                if let Some(ref mut recurring_main) = app.recurring_main {
+                    recurring_main.render(f);
+                }
+            }
             State::TransactionCreate => {
                 if let Some(ref transaction_create) = app.transaction_create {
                     transaction_create.render(f);
                 }
             }
+            State::ImportMain => {
+                if let Some(ref mut import_main) = app.import_main {
+                    import_main.render(f);
+                }
+            }
         })?;
 
         // Handle user input (outside of draw)
@@ -122,41 +144,55 @@ pub async fn run_app<B: ratatui::backend::Backend>(
                         .login_page
                         .handle_input(key_event.code, key_event.modifiers, &mut app.homepage)
                         .await
-                    {
-                        if app.homepage.is_some() {
+                        && app.homepage.is_some() {
                             app.state = State::Homepage; // Transition to Homepage
                         }
-                    }
                 }
                 State::Homepage => {
                     if key_event.code == KeyCode::Esc {
+                        if let Some(ref homepage) = app.homepage {
+                            logout(homepage.token.clone()).await;
+                        }
                         break; // Quit from Homepage
                     }
                     if let Some(ref homepage) = app.homepage {
                         match key_event.code {
                             KeyCode::Char('1') => {
-                                let mut account_main = AccountMain::new(homepage.email.clone());
+                                let mut account_main = AccountMain::new(homepage.token.clone());
                                 account_main.initialize().await;
                                 app.account_main = Some(account_main);
                                 app.state = State::AccountMain;
                             }
                             KeyCode::Char('2') => {
-                                let mut category_main = CategoryMain::new(homepage.email.clone());
+                                let mut category_main = CategoryMain::new(homepage.token.clone());
                                 category_main.initialize().await;
                                 app.category_main = Some(category_main);
                                 app.state = State::CategoryMain;
                             }
                             KeyCode::Char('3') => {
-                                let mut report_main = ReportMain::new(homepage.email.clone());
+                                let mut report_main = ReportMain::new(homepage.token.clone());
                                 report_main.initialize().await;
                                 app.report_main = Some(report_main);
                                 app.state = State::ReportMain;
                             }
+                            KeyCode::Char('4') => {
+                                let mut recurring_main = RecurringMain::new(homepage.token.clone());
+                                recurring_main.initialize().await;
+                                app.recurring_main = Some(recurring_main);
+                                app.state = State::RecurringMain;
+                            }
+                            KeyCode::Char('r') => {
+                                read_notifications(homepage.token.clone()).await;
+                            }
                             KeyCode::Char('n') => {
                                 app.transaction_create =
-                                    Some(TransactionCreate::new(homepage.email.clone()));
+                                    Some(TransactionCreate::new(homepage.token.clone()));
                                 app.state = State::TransactionCreate;
                             }
+                            KeyCode::Char('i') => {
+                                app.import_main = Some(ImportMain::new(homepage.token.clone()));
+                                app.state = State::ImportMain;
+                            }
                             _ => {}
                         }
                     }
@@ -191,6 +227,16 @@ pub async fn run_app<B: ratatui::backend::Backend>(
                         }
                     }
                 }
+                State::RecurringMain => {
+                    if let Some(ref mut recurring_main) = app.recurring_main {
+                        if recurring_main
+                            .handle_input(key_event.code, key_event.modifiers)
+                            .await
+                        {
+                            app.state = State::Homepage;
+                        }
+                    }
+                }
                 State::TransactionCreate => {
                     if key_event.code == KeyCode::Esc {
                         app.state = State::Homepage; // Return to Homepage on Esc
@@ -204,6 +250,16 @@ pub async fn run_app<B: ratatui::backend::Backend>(
                         }
                     }
                 }
+                State::ImportMain => {
+                    if let Some(ref mut import_main) = app.import_main {
+                        if import_main
+                            .handle_input(key_event.code, key_event.modifiers)
+                            .await
+                        {
+                            app.state = State::Homepage;
+                        }
+                    }
+                }
             }
         }
     }
@@ -6,6 +6,7 @@ use ratatui::{
 };
 use crossterm::event::{KeyCode, KeyModifiers};
 use reqwest::Client;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
 #[allow(dead_code)]
@@ -15,41 +16,90 @@ pub struct Account {
     pub email: String,
     pub account_type: String,
     pub account_name: String,
+    pub currency: String,
+}
+
+// An account with its current balance, in the account's currency
+#[allow(dead_code)]
+#[derive(Deserialize, Debug)]
+pub struct AccountBalance {
+    pub account_name: String,
+    pub account_type: String,
+    pub currency: String,
+    // credit accounts are liabilities, their balance is what is owed
+    pub liability: bool,
+    pub balance: Decimal,
+    pub archived: bool,
 }
 
 #[derive(Serialize)]
 pub struct NewAccount {
-    pub email: String,
     pub account_type: String,
     pub account_name: String,
+    // blank is sent as null and the backend uses its default currency
+    #[serde(serialize_with = "blank_as_none")]
+    pub currency: String,
+    // blank opens the account at zero
+    #[serde(serialize_with = "blank_as_none")]
+    pub opening_balance: String,
+    // blank counts every transaction of the account
+    #[serde(serialize_with = "blank_as_none")]
+    pub opening_date: String,
+}
+
+fn blank_as_none<S: serde::Serializer>(value: &str, serializer: S) -> Result<S::Ok, S::Error> {
+    match value.trim() {
+        "" => serializer.serialize_none(),
+        code => serializer.serialize_some(code),
+    }
+}
+
+// Only the fields that changed are sent
+#[derive(Serialize, Debug, Default)]
+pub struct AccountUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_type: Option<String>,
 }
 
 pub struct AccountMain {
-    accounts: Vec<Account>,
+    accounts: Vec<AccountBalance>,
     list_state: ListState,
-    email: String,
+    token: String,
     message: String,
     creating_account: bool,
+    // name of the account being edited, the form then only shows its name and type
+    editing_account: Option<String>,
     new_account: NewAccount,
     active_field: usize,
     client: Client,
+    // archived accounts are hidden unless toggled on
+    show_archived: bool,
+    // account waiting for the purge to be confirmed with 'y'
+    pending_purge: Option<String>,
 }
 
 impl AccountMain {
-    pub fn new(email: String) -> Self {
+    pub fn new(token: String) -> Self {
         let mut instance = Self {
             accounts: Vec::new(),
             list_state: ListState::default(),
-            email: email.clone(),
+            token,
             message: String::new(),
             creating_account: false,
+            editing_account: None,
             new_account: NewAccount {
-                email,
                 account_type: String::new(),
                 account_name: String::new(),
+                currency: String::new(),
+                opening_balance: String::new(),
+                opening_date: String::new(),
             },
             active_field: 0,
             client: Client::new(),
+            show_archived: false,
+            pending_purge: None,
         };
 
         // Manually call fetch_accounts since we can't use async in new
@@ -96,7 +146,7 @@ impl AccountMain {
         let help_text = if self.creating_account {
             "ESC: Back | TAB: Next Field | ENTER: Submit"
         } else {
-            "ESC: Back | N: New Account | D: Delete Account | ↑↓: Navigate"
+            "ESC: Back | N: New | E: Edit | D: Archive | U: Unarchive | X: Purge | A: Show Archived | ↑↓: Navigate"
         };
         let help = Paragraph::new(help_text)
             .style(Style::default().fg(Color::DarkGray))
@@ -108,11 +158,24 @@ impl AccountMain {
         let items: Vec<ListItem> = self.accounts
             .iter()
             .map(|account| {
+                let (balance_label, balance_style) = if account.archived {
+                    ("Balance", Style::default().fg(Color::DarkGray))
+                } else if account.liability {
+                    ("Owed", Style::default().fg(Color::Red))
+                } else {
+                    ("Balance", Style::default().fg(Color::Black))
+                };
                 ListItem::new(format!(
-                    "{}: {}",
+                    "{}: {} ({}) | {}: {} {}{}",
                     account.account_name,
-                    account.account_type
+                    account.account_type,
+                    account.currency,
+                    balance_label,
+                    account.balance,
+                    account.currency,
+                    if account.archived { " [archived]" } else { "" }
                 ))
+                .style(balance_style)
             })
             .collect();
 
@@ -131,6 +194,9 @@ impl AccountMain {
             .constraints([
                 Constraint::Length(3),  // Account Name
                 Constraint::Length(3),  // Account Type
+                Constraint::Length(3),  // Currency
+                Constraint::Length(3),  // Opening Balance
+                Constraint::Length(3),  // Opening Date
             ].as_ref())
             .split(area);
 
@@ -149,12 +215,42 @@ impl AccountMain {
         let type_text = Paragraph::new(self.new_account.account_type.clone())
             .style(Style::default().fg(Color::Black));
         f.render_widget(type_text.block(type_block), create_chunks[1]);
+
+        // currency and opening balance are set once, when the account is created
+        if self.editing_account.is_some() {
+            return;
+        }
+
+        let currency_block = Block::default()
+            .title("Currency (e.g. CAD, USD, EUR; blank for CAD)")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(if self.active_field == 2 { Color::Yellow } else { Color::Black }));
+        let currency_text = Paragraph::new(self.new_account.currency.clone())
+            .style(Style::default().fg(Color::Black));
+        f.render_widget(currency_text.block(currency_block), create_chunks[2]);
+
+        let opening_balance_block = Block::default()
+            .title("Opening Balance (amount owed for credit; blank for 0)")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(if self.active_field == 3 { Color::Yellow } else { Color::Black }));
+        let opening_balance_text = Paragraph::new(self.new_account.opening_balance.clone())
+            .style(Style::default().fg(Color::Black));
+        f.render_widget(opening_balance_text.block(opening_balance_block), create_chunks[3]);
+
+        let opening_date_block = Block::default()
+            .title("Opening Date (YYYY-MM-DD; blank counts all transactions)")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(if self.active_field == 4 { Color::Yellow } else { Color::Black }));
+        let opening_date_text = Paragraph::new(self.new_account.opening_date.clone())
+            .style(Style::default().fg(Color::Black));
+        f.render_widget(opening_date_text.block(opening_date_block), create_chunks[4]);
     }
 
     pub async fn handle_input(&mut self, key: KeyCode, _modifiers: KeyModifiers) -> bool {
         if key == KeyCode::Esc {
             if self.creating_account {
                 self.creating_account = false;
+                self.editing_account = None;
                 return false;
             }
             return true;
@@ -172,15 +268,23 @@ impl AccountMain {
     async fn handle_create_input(&mut self, key: KeyCode) {
         match key {
             KeyCode::Tab => {
-                self.active_field = (self.active_field + 1) % 2;
+                let field_count = if self.editing_account.is_some() { 2 } else { 5 };
+                self.active_field = (self.active_field + 1) % field_count;
             }
             KeyCode::Enter => {
-                self.submit_new_account().await;
+                if self.editing_account.is_some() {
+                    self.submit_account_update().await;
+                } else {
+                    self.submit_new_account().await;
+                }
             }
             KeyCode::Char(c) => {
                 match self.active_field {
                     0 => self.new_account.account_name.push(c),
                     1 => self.new_account.account_type.push(c),
+                    2 => self.new_account.currency.push(c),
+                    3 => self.new_account.opening_balance.push(c),
+                    4 => self.new_account.opening_date.push(c),
                     _ => {}
                 }
             }
@@ -188,6 +292,9 @@ impl AccountMain {
                 match self.active_field {
                     0 => { self.new_account.account_name.pop(); }
                     1 => { self.new_account.account_type.pop(); }
+                    2 => { self.new_account.currency.pop(); }
+                    3 => { self.new_account.opening_balance.pop(); }
+                    4 => { self.new_account.opening_date.pop(); }
                     _ => {}
                 }
             }
@@ -196,13 +303,36 @@ impl AccountMain {
     }
 
     async fn handle_list_input(&mut self, key: KeyCode) {
+        // any key other than 'y' cancels a pending purge
+        if let Some(account_name) = self.pending_purge.take() {
+            if key == KeyCode::Char('y') {
+                self.purge_account(&account_name).await;
+            } else {
+                self.message = "Purge cancelled".to_string();
+            }
+            return;
+        }
+
         match key {
             KeyCode::Char('n') => {
                 self.creating_account = true;
                 self.new_account.account_name.clear();
                 self.new_account.account_type.clear();
+                self.new_account.currency.clear();
+                self.new_account.opening_balance.clear();
+                self.new_account.opening_date.clear();
+                self.editing_account = None;
                 self.active_field = 0;
             }
+            KeyCode::Char('e') => {
+                if let Some(account) = self.list_state.selected().and_then(|i| self.accounts.get(i)) {
+                    self.new_account.account_name = account.account_name.clone();
+                    self.new_account.account_type = account.account_type.clone();
+                    self.editing_account = Some(account.account_name.clone());
+                    self.creating_account = true;
+                    self.active_field = 0;
+                }
+            }
             KeyCode::Char('d') => {
                 if let Some(selected) = self.list_state.selected() {
                     if selected < self.accounts.len() {
@@ -211,6 +341,30 @@ impl AccountMain {
                     }
                 }
             }
+            KeyCode::Char('u') => {
+                if let Some(account) = self.list_state.selected().and_then(|i| self.accounts.get(i)) {
+                    let account_name = account.account_name.clone();
+                    self.unarchive_account(&account_name).await;
+                }
+            }
+            KeyCode::Char('x') => {
+                if let Some(account) = self.list_state.selected().and_then(|i| self.accounts.get(i)) {
+                    if account.archived {
+                        self.message = format!(
+                            "Purge {} and all its transactions for good? Press Y to confirm",
+                            account.account_name
+                        );
+                        self.pending_purge = Some(account.account_name.clone());
+                    } else {
+                        self.message = "Archive the account before purging it".to_string();
+                    }
+                }
+            }
+            KeyCode::Char('a') => {
+                self.show_archived = !self.show_archived;
+                self.list_state.select(None);
+                self.fetch_accounts().await;
+            }
             KeyCode::Up => {
                 let selected = self.list_state.selected().unwrap_or(0);
                 if !self.accounts.is_empty() {
@@ -236,15 +390,18 @@ impl AccountMain {
     }
 
     async fn fetch_accounts(&mut self) {
-        let url = format!("http://localhost:8000/account_summary?email={}", self.email);
-        match self.client.get(&url).send().await {
+        let url = format!(
+            "http://localhost:8000/account_balance?include_archived={}",
+            self.show_archived
+        );
+        match self.client.get(&url).bearer_auth(&self.token).send().await {
             Ok(response) => {
                 match response.status() {
                     reqwest::StatusCode::OK => {
-                        if let Ok(accounts) = response.json::<Vec<Account>>().await {
+                        if let Ok(accounts) = response.json::<Vec<AccountBalance>>().await {
                             self.accounts = accounts;
-                            if !self.accounts.is_empty() && self.list_state.selected().is_none() {
-                                self.list_state.select(Some(0));
+                            if self.list_state.selected().is_none_or(|i| i >= self.accounts.len()) {
+                                self.list_state.select((!self.accounts.is_empty()).then_some(0));
                             }
                             self.message = format!("Loaded {} accounts", self.accounts.len());
                         } else {
@@ -267,9 +424,15 @@ impl AccountMain {
             self.message = "Please fill in all fields".to_string();
             return;
         }
+        let opening_balance = self.new_account.opening_balance.trim();
+        if !opening_balance.is_empty() && opening_balance.parse::<Decimal>().is_err() {
+            self.message = "Opening balance must be a number".to_string();
+            return;
+        }
 
         match self.client
             .post("http://localhost:8000/account_create")
+            .bearer_auth(&self.token)
             .json(&self.new_account)
             .send()
             .await
@@ -298,29 +461,135 @@ impl AccountMain {
         }
     }
 
+    async fn submit_account_update(&mut self) {
+        let Some(original_name) = self.editing_account.clone() else {
+            return;
+        };
+        let Some(original) = self.accounts.iter().find(|acc| acc.account_name == original_name) else {
+            return;
+        };
+        if self.new_account.account_name.trim().is_empty() || self.new_account.account_type.trim().is_empty() {
+            self.message = "Please fill in all fields".to_string();
+            return;
+        }
+
+        let changed = |new_value: &str, old_value: &str| {
+            (new_value != old_value).then(|| new_value.to_string())
+        };
+        let update = AccountUpdate {
+            account_name: changed(&self.new_account.account_name, &original.account_name),
+            account_type: changed(&self.new_account.account_type, &original.account_type),
+        };
+        if update.account_name.is_none() && update.account_type.is_none() {
+            self.message = "Nothing to update".to_string();
+            return;
+        }
+
+        let url = format!(
+            "http://localhost:8000/account_update?account_name={}",
+            original_name
+        );
+        match self.client.patch(&url).bearer_auth(&self.token).json(&update).send().await {
+            Ok(response) => {
+                let status = response.status();
+                let message = response.text().await.unwrap_or_default();
+
+                match status {
+                    reqwest::StatusCode::OK => {
+                        self.message = "Account updated successfully".to_string();
+                        self.creating_account = false;
+                        self.editing_account = None;
+                        self.fetch_accounts().await;
+                    }
+                    _ => {
+                        self.message = format!("Failed to update account: {}", message);
+                    }
+                }
+            }
+            Err(e) => {
+                self.message = format!("Error updating account: {}", e);
+            }
+        }
+    }
+
     async fn delete_account(&mut self, account_name: &str) {
         let url = format!(
-            "http://localhost:8000/delete_account?email={}&account_name={}",
-            self.email, account_name
+            "http://localhost:8000/delete_account?account_name={}",
+            account_name
+        );
+
+        match self.client.delete(&url).bearer_auth(&self.token).send().await {
+            Ok(response) => {
+                let status = response.status();
+                let message = response.text().await.unwrap_or_default();
+
+                match status {
+                    reqwest::StatusCode::OK => {
+                        self.fetch_accounts().await;
+                        self.message = "Account archived successfully".to_string();
+                    }
+                    _ => {
+                        self.message = format!("Failed to archive account: {}", message);
+                    }
+                }
+            }
+            Err(e) => {
+                self.message = format!("Error archiving account: {}", e);
+            }
+        }
+    }
+
+    async fn unarchive_account(&mut self, account_name: &str) {
+        let url = format!(
+            "http://localhost:8000/unarchive_account?account_name={}",
+            account_name
+        );
+
+        match self.client.patch(&url).bearer_auth(&self.token).send().await {
+            Ok(response) => {
+                let status = response.status();
+                let message = response.text().await.unwrap_or_default();
+
+                match status {
+                    reqwest::StatusCode::OK => {
+                        self.fetch_accounts().await;
+                        self.message = "Account unarchived successfully".to_string();
+                    }
+                    _ => {
+                        self.message = format!("Failed to unarchive account: {}", message);
+                    }
+                }
+            }
+            Err(e) => {
+                self.message = format!("Error unarchiving account: {}", e);
+            }
+        }
+    }
+
+    // The backend wants the account name repeated as confirmation
+    async fn purge_account(&mut self, account_name: &str) {
+        let url = format!(
+            "http://localhost:8000/purge_account?account_name={0}&confirm={0}",
+            account_name
         );
 
-        match self.client.delete(&url).send().await {
+        match self.client.delete(&url).bearer_auth(&self.token).send().await {
             Ok(response) => {
                 let status = response.status();
                 let message = response.text().await.unwrap_or_default();
 
                 match status {
                     reqwest::StatusCode::OK => {
-                        self.message = "Account deleted successfully".to_string();
                         self.fetch_accounts().await;
+                        self.message = "Account purged".to_string();
                     }
                     _ => {
-                        self.message = format!("Failed to delete account: {}", message);
+                        self.message = format!("Failed to purge account: {}", message);
                     }
                 }
             }
             Err(e) => {
-                self.message = format!("Error deleting account: {}", e);
+                self.message = format!("Error purging account: {}", e);
             }
         }
     }
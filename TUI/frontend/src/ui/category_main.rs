@@ -6,6 +6,7 @@ use ratatui::{
     Frame,
 };
 use reqwest::Client;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Debug, Clone)]
@@ -14,43 +15,67 @@ pub struct Category {
     pub email: String,
     pub nickname: String,
     pub category_type: String,
-    pub budget: f64,
+    pub budget: Decimal,
     pub budget_freq: String,
+    pub rollover: String,
+    // set when the category is archived
+    pub archived_at: Option<String>,
 }
 
 #[derive(Serialize, Debug)]
 pub struct NewCategory {
-    pub email: String,
     pub nickname: String,
     pub category_type: String,
-    pub budget: f64,
+    pub budget: Decimal,
     pub budget_freq: String,
+    // left out when blank, the backend then uses "none"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rollover: Option<String>,
+}
+
+// Archiving and merging ask for the category that takes over the transactions
+enum ReassignPrompt {
+    Archive(String),
+    Merge(String),
 }
 
 pub struct CategoryMain {
     categories: Vec<Category>,
     list_state: ListState,
-    email: String,
+    token: String,
     message: String,
     creating_category: bool,
     active_field: usize,
     client: Client,
     input_strings: [String; 5],
     last_operation_nickname: Option<String>,
+    // nickname of the category being edited, the form is then prefilled with it
+    editing_category: Option<String>,
+    // archived categories are hidden unless toggled on
+    show_archived: bool,
+    // category waiting for the purge to be confirmed with 'y'
+    pending_purge: Option<String>,
+    reassign_prompt: Option<ReassignPrompt>,
+    reassign_input: String,
 }
 
 impl CategoryMain {
-    pub fn new(email: String) -> Self {
+    pub fn new(token: String) -> Self {
         let mut instance = Self {
             categories: Vec::new(),
             list_state: ListState::default(),
-            email: email.clone(),
+            token,
             message: String::new(),
             creating_category: false,
             active_field: 0,
             client: Client::new(),
             input_strings: Default::default(),
             last_operation_nickname: None,
+            editing_category: None,
+            show_archived: false,
+            pending_purge: None,
+            reassign_prompt: None,
+            reassign_input: String::new(),
         };
 
         instance.message = "Loading categories...".to_string();
@@ -87,6 +112,27 @@ impl CategoryMain {
 
         if self.creating_category {
             self.render_create_category(f, chunks[1]);
+        } else if let Some(prompt) = &self.reassign_prompt {
+            let prompt_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(5), Constraint::Length(3)].as_ref())
+                .split(chunks[1]);
+            let title = match prompt {
+                ReassignPrompt::Archive(nickname) => {
+                    format!("Move the transactions of {} to (blank keeps them)", nickname)
+                }
+                ReassignPrompt::Merge(nickname) => format!("Merge {} into", nickname),
+            };
+            let input = Paragraph::new(self.reassign_input.clone())
+                .style(Style::default().fg(Color::Black))
+                .block(
+                    Block::default()
+                        .title(title)
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(Color::Yellow)),
+                );
+            self.render_category_list(f, prompt_chunks[0]);
+            f.render_widget(input, prompt_chunks[1]);
         } else {
             self.render_category_list(f, chunks[1]);
         }
@@ -103,8 +149,10 @@ impl CategoryMain {
 
         let help_text = if self.creating_category {
             "ESC: Back | TAB: Next Field | ENTER: Submit"
+        } else if self.reassign_prompt.is_some() {
+            "ESC: Cancel | ENTER: Confirm"
         } else {
-            "ESC: Back | N: New Category | D: Delete Category | U: Update Category | ↑↓: Navigate"
+            "ESC: Back | N: New | E: Edit | D: Archive | M: Merge | U: Unarchive | X: Purge | A: Show Archived | ↑↓: Navigate"
         };
         let help = Paragraph::new(help_text)
             .style(Style::default().fg(Color::DarkGray))
@@ -117,13 +165,25 @@ impl CategoryMain {
             .categories
             .iter()
             .map(|category| {
-                ListItem::new(format!(
-                    "{}: {} (Budget: ${} {})",
+                let rollover_str = match category.rollover.as_str() {
+                    "none" => String::new(),
+                    policy => format!(", {}", policy),
+                };
+                let archived_str = if category.archived_at.is_some() { " [archived]" } else { "" };
+                let item = ListItem::new(format!(
+                    "{}: {} (Budget: ${} {}{}){}",
                     category.nickname,
                     category.category_type,
                     category.budget,
-                    category.budget_freq
-                ))
+                    category.budget_freq,
+                    rollover_str,
+                    archived_str
+                ));
+                if category.archived_at.is_some() {
+                    item.style(Style::default().fg(Color::DarkGray))
+                } else {
+                    item
+                }
             })
             .collect();
 
@@ -149,6 +209,7 @@ impl CategoryMain {
                     Constraint::Length(3), // Category Type
                     Constraint::Length(3), // Budget
                     Constraint::Length(3), // Budget Frequency
+                    Constraint::Length(3), // Rollover
                 ]
                 .as_ref(),
             )
@@ -156,12 +217,19 @@ impl CategoryMain {
 
         let fields = [
             ("Nickname", &self.input_strings[0]),
-            ("Category Type", &self.input_strings[1]),
-            ("Budget", &self.input_strings[2]),
+            (
+                "Category Type (income/expense/transfer)",
+                &self.input_strings[1],
+            ),
+            ("Budget (target for income)", &self.input_strings[2]),
             (
                 "Budget Frequency (daily/weekly/monthly)",
                 &self.input_strings[3],
             ),
+            (
+                "Rollover (optional: none/carry_unused/carry_overspend)",
+                &self.input_strings[4],
+            ),
         ];
 
         for (i, (title, content)) in fields.iter().enumerate() {
@@ -179,9 +247,15 @@ impl CategoryMain {
     }
 
     pub async fn handle_input(&mut self, key: KeyCode, _modifiers: KeyModifiers) -> bool {
+        if self.reassign_prompt.is_some() {
+            self.handle_reassign_input(key).await;
+            return false;
+        }
+
         if key == KeyCode::Esc {
             if self.creating_category {
                 self.creating_category = false;
+                self.editing_category = None;
                 return false;
             }
             return true;
@@ -198,54 +272,125 @@ impl CategoryMain {
     async fn handle_create_input(&mut self, key: KeyCode) {
         match key {
             KeyCode::Tab => {
-                self.active_field = (self.active_field + 1) % 4; // Only cycle through 4 fields
+                self.active_field = (self.active_field + 1) % 5;
             }
             KeyCode::Enter => {
-                self.submit_new_category().await;
+                if self.editing_category.is_some() {
+                    self.submit_category_update().await;
+                } else {
+                    self.submit_new_category().await;
+                }
             }
             KeyCode::Char(c) => {
-                if self.active_field < 4 {
-                    // Only allow input for first 4 fields
-                    self.input_strings[self.active_field].push(c);
-                }
+                self.input_strings[self.active_field].push(c);
             }
             KeyCode::Backspace => {
-                if self.active_field < 4 {
-                    // Only allow deletion for first 4 fields
-                    self.input_strings[self.active_field].pop();
+                self.input_strings[self.active_field].pop();
+            }
+            _ => {}
+        }
+    }
+
+    async fn handle_reassign_input(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Esc => {
+                self.reassign_prompt = None;
+                self.message = "Cancelled".to_string();
+            }
+            KeyCode::Enter => {
+                let target = self.reassign_input.trim().to_string();
+                match self.reassign_prompt.take() {
+                    Some(ReassignPrompt::Archive(nickname)) => {
+                        self.delete_category(&nickname, &target).await;
+                    }
+                    Some(ReassignPrompt::Merge(nickname)) => {
+                        if target.is_empty() {
+                            self.message = "Please enter the category to merge into".to_string();
+                            self.reassign_prompt = Some(ReassignPrompt::Merge(nickname));
+                        } else {
+                            self.merge_category(&nickname, &target).await;
+                        }
+                    }
+                    None => {}
                 }
             }
+            KeyCode::Char(c) => {
+                self.reassign_input.push(c);
+            }
+            KeyCode::Backspace => {
+                self.reassign_input.pop();
+            }
             _ => {}
         }
     }
 
     async fn handle_list_input(&mut self, key: KeyCode) {
+        // any key other than 'y' cancels a pending purge
+        if let Some(nickname) = self.pending_purge.take() {
+            if key == KeyCode::Char('y') {
+                self.purge_category(&nickname).await;
+            } else {
+                self.message = "Purge cancelled".to_string();
+            }
+            return;
+        }
+
         match key {
             KeyCode::Char('n') => {
                 self.creating_category = true;
+                self.editing_category = None;
                 self.input_strings = Default::default();
                 self.active_field = 0;
             }
+            KeyCode::Char('e') => {
+                if let Some(category) = self.list_state.selected().and_then(|i| self.categories.get(i)) {
+                    self.input_strings = [
+                        category.nickname.clone(),
+                        category.category_type.clone(),
+                        category.budget.to_string(),
+                        category.budget_freq.clone(),
+                        category.rollover.clone(),
+                    ];
+                    self.editing_category = Some(category.nickname.clone());
+                    self.creating_category = true;
+                    self.active_field = 0;
+                }
+            }
             KeyCode::Char('d') => {
-                if let Some(selected) = self.list_state.selected() {
-                    if selected < self.categories.len() {
-                        let nickname = self.categories[selected].nickname.clone();
-                        self.delete_category(&nickname).await;
-                    }
+                if let Some(category) = self.list_state.selected().and_then(|i| self.categories.get(i)) {
+                    self.reassign_prompt = Some(ReassignPrompt::Archive(category.nickname.clone()));
+                    self.reassign_input.clear();
+                }
+            }
+            KeyCode::Char('m') => {
+                if let Some(category) = self.list_state.selected().and_then(|i| self.categories.get(i)) {
+                    self.reassign_prompt = Some(ReassignPrompt::Merge(category.nickname.clone()));
+                    self.reassign_input.clear();
                 }
             }
             KeyCode::Char('u') => {
-                // delete first
-                if let Some(selected) = self.list_state.selected() {
-                    if selected < self.categories.len() {
-                        let nickname = self.categories[selected].nickname.clone();
-                        self.delete_category(&nickname).await;
+                if let Some(category) = self.list_state.selected().and_then(|i| self.categories.get(i)) {
+                    let nickname = category.nickname.clone();
+                    self.unarchive_category(&nickname).await;
+                }
+            }
+            KeyCode::Char('x') => {
+                if let Some(category) = self.list_state.selected().and_then(|i| self.categories.get(i)) {
+                    if category.archived_at.is_some() {
+                        self.message = format!(
+                            "Purge {} and all its transactions for good? Press Y to confirm",
+                            category.nickname
+                        );
+                        self.pending_purge = Some(category.nickname.clone());
+                    } else {
+                        self.message = "Archive the category before purging it".to_string();
                     }
                 }
-                // then create new lol
-                self.creating_category = true;
-                self.input_strings = Default::default();
-                // self.active_field = 0;
+            }
+            KeyCode::Char('a') => {
+                self.show_archived = !self.show_archived;
+                self.list_state.select(None);
+                self.fetch_categories().await;
             }
             KeyCode::Up => {
                 let selected = self.list_state.selected().unwrap_or(0);
@@ -278,10 +423,10 @@ impl CategoryMain {
 
     async fn fetch_categories(&mut self) {
         let url = format!(
-            "http://localhost:8000/category_summary?email={}",
-            self.email
+            "http://localhost:8000/category_summary?include_archived={}",
+            self.show_archived
         );
-        match self.client.get(&url).send().await {
+        match self.client.get(&url).bearer_auth(&self.token).send().await {
             Ok(response) => match response.status() {
                 reqwest::StatusCode::OK => {
                     if let Ok(categories) = response.json::<Vec<Category>>().await {
@@ -292,8 +437,8 @@ impl CategoryMain {
                             if let Some(index) = self.categories.iter().position(|c| &c.nickname == nickname) {
                                 self.list_state.select(Some(index));
                             }
-                        } else if !self.categories.is_empty() && self.list_state.selected().is_none() {
-                            self.list_state.select(Some(0));
+                        } else if self.list_state.selected().is_none_or(|i| i >= self.categories.len()) {
+                            self.list_state.select((!self.categories.is_empty()).then_some(0));
                         }
 
                         self.message = format!("Loaded {} categories", self.categories.len());
@@ -313,14 +458,14 @@ impl CategoryMain {
     }
 
     async fn submit_new_category(&mut self) {
-        // Only check the first 4 fields that we actually use
+        // The first 4 fields are required, rollover is optional
         if self.input_strings[..4].iter().any(|s| s.is_empty()) {
             self.message = "Please fill in all fields".to_string();
             return;
         }
 
         // Parse budget value
-        let budget = match self.input_strings[2].parse::<f64>() {
+        let budget = match self.input_strings[2].trim().parse::<Decimal>() {
             Ok(value) => value,
             Err(_) => {
                 self.message = "Invalid budget value".to_string();
@@ -334,16 +479,17 @@ impl CategoryMain {
 
         // Create new category
         let new_category = NewCategory {
-            email: self.email.clone(),
             nickname: self.input_strings[0].clone(),
             category_type: self.input_strings[1].clone(),
             budget,
             budget_freq: self.input_strings[3].clone(),
+            rollover: Some(self.input_strings[4].trim().to_string()).filter(|s| !s.is_empty()),
         };
 
         match self
             .client
             .post("http://localhost:8000/category_create")
+            .bearer_auth(&self.token)
             .json(&new_category)
             .send()
             .await
@@ -375,29 +521,190 @@ impl CategoryMain {
         }
     }
 
-    async fn delete_category(&mut self, nickname: &str) {
+    // A blank `reassign_to` archives the category with its transactions
+    async fn delete_category(&mut self, nickname: &str, reassign_to: &str) {
+        let mut url = format!(
+            "http://localhost:8000/delete_category?category_nickname={}",
+            nickname
+        );
+        if !reassign_to.is_empty() {
+            url.push_str(&format!("&reassign_to={}", reassign_to));
+        }
+
+        match self.client.delete(&url).bearer_auth(&self.token).send().await {
+            Ok(response) => {
+                let status = response.status();
+                let message = response.text().await.unwrap_or_default();
+
+                match status {
+                    reqwest::StatusCode::OK => {
+                        self.fetch_categories().await;
+                        self.message = "Category archived successfully".to_string();
+                    }
+                    _ => {
+                        self.message = format!("Failed to archive category: {}", message);
+                    }
+                }
+            }
+            Err(e) => {
+                self.message = format!("Error archiving category: {}", e);
+            }
+        }
+    }
+
+    async fn merge_category(&mut self, nickname: &str, into: &str) {
+        let url = format!(
+            "http://localhost:8000/merge_category?category_nickname={}&into={}",
+            nickname, into
+        );
+
+        match self.client.post(&url).bearer_auth(&self.token).send().await {
+            Ok(response) => {
+                let status = response.status();
+                let message = response.text().await.unwrap_or_default();
+
+                match status {
+                    reqwest::StatusCode::OK => {
+                        self.last_operation_nickname = Some(into.to_string());
+                        self.fetch_categories().await;
+                        self.message = format!("Merged {} into {}", nickname, into);
+                    }
+                    _ => {
+                        self.message = format!("Failed to merge category: {}", message);
+                    }
+                }
+            }
+            Err(e) => {
+                self.message = format!("Error merging category: {}", e);
+            }
+        }
+    }
+
+    // Sends one category_update per changed field, the nickname last since
+    // the other updates look the category up by its current nickname
+    async fn submit_category_update(&mut self) {
+        let Some(original_nickname) = self.editing_category.clone() else {
+            return;
+        };
+        let Some(original) = self.categories.iter().find(|c| c.nickname == original_nickname) else {
+            return;
+        };
+        if self.input_strings[..4].iter().any(|s| s.trim().is_empty()) {
+            self.message = "Please fill in all fields".to_string();
+            return;
+        }
+        if self.input_strings[2].trim().parse::<Decimal>().is_err() {
+            self.message = "Invalid budget value".to_string();
+            return;
+        }
+
+        let rollover = match self.input_strings[4].trim() {
+            "" => "none".to_string(),
+            policy => policy.to_string(),
+        };
+        let changes: Vec<(&str, String)> = [
+            ("category_type", self.input_strings[1].trim().to_string(), original.category_type.clone()),
+            ("budget", self.input_strings[2].trim().to_string(), original.budget.to_string()),
+            ("budget_freq", self.input_strings[3].trim().to_string(), original.budget_freq.clone()),
+            ("rollover", rollover, original.rollover.clone()),
+            ("nickname", self.input_strings[0].trim().to_string(), original.nickname.clone()),
+        ]
+        .into_iter()
+        .filter(|(_, new_value, old_value)| new_value != old_value)
+        .map(|(field, new_value, _)| (field, new_value))
+        .collect();
+        if changes.is_empty() {
+            self.message = "Nothing to update".to_string();
+            return;
+        }
+
+        for (field, new_value) in changes {
+            let result = self
+                .client
+                .post("http://localhost:8000/category_update")
+                .query(&[
+                    ("field", field),
+                    ("category_nickname", original_nickname.as_str()),
+                    ("new_value", new_value.as_str()),
+                ])
+                .bearer_auth(&self.token)
+                .send()
+                .await;
+            match result {
+                Ok(response) if response.status().is_success() => {}
+                Ok(response) => {
+                    let message = response.text().await.unwrap_or_default();
+                    self.message = format!("Failed to update category: {}", message);
+                    self.fetch_categories().await;
+                    return;
+                }
+                Err(e) => {
+                    self.message = format!("Error updating category: {}", e);
+                    return;
+                }
+            }
+        }
+
+        self.last_operation_nickname = Some(self.input_strings[0].trim().to_string());
+        self.creating_category = false;
+        self.editing_category = None;
+        self.input_strings = Default::default();
+        self.active_field = 0;
+        self.fetch_categories().await;
+        self.message = "Category updated successfully".to_string();
+    }
+
+    async fn unarchive_category(&mut self, nickname: &str) {
+        let url = format!(
+            "http://localhost:8000/unarchive_category?category_nickname={}",
+            nickname
+        );
+
+        match self.client.patch(&url).bearer_auth(&self.token).send().await {
+            Ok(response) => {
+                let status = response.status();
+                let message = response.text().await.unwrap_or_default();
+
+                match status {
+                    reqwest::StatusCode::OK => {
+                        self.fetch_categories().await;
+                        self.message = "Category unarchived successfully".to_string();
+                    }
+                    _ => {
+                        self.message = format!("Failed to unarchive category: {}", message);
+                    }
+                }
+            }
+            Err(e) => {
+                self.message = format!("Error unarchiving category: {}", e);
+            }
+        }
+    }
+
+    // The backend wants the nickname repeated as confirmation
+    async fn purge_category(&mut self, nickname: &str) {
         let url = format!(
-            "http://localhost:8000/delete_category?email={}&category_nickname={}",
-            self.email, nickname
+            "http://localhost:8000/purge_category?category_nickname={0}&confirm={0}",
+            nickname
         );
 
-        match self.client.delete(&url).send().await {
+        match self.client.delete(&url).bearer_auth(&self.token).send().await {
             Ok(response) => {
                 let status = response.status();
                 let message = response.text().await.unwrap_or_default();
 
                 match status {
                     reqwest::StatusCode::OK => {
-                        self.message = "Category deleted successfully".to_string();
                         self.fetch_categories().await;
+                        self.message = "Category purged".to_string();
                     }
                     _ => {
-                        self.message = format!("Failed to delete category: {}", message);
+                        self.message = format!("Failed to purge category: {}", message);
                     }
                 }
             }
             Err(e) => {
-                self.message = format!("Error deleting category: {}", e);
+                self.message = format!("Error purging category: {}", e);
             }
         }
     }
@@ -2,32 +2,39 @@ use crate::ui::report::create_lines;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout},
     style::{Color, Modifier, Style},
+    text::Line,
     widgets::{Block, Borders, Paragraph, Wrap},
     Frame,
 };
 
+// Alerts past this many are summarized in the last banner line
+const MAX_BANNER_LINES: usize = 3;
+
 pub struct Homepage {
     pub username: String,
-    pub email: String,
+    pub token: String,
     pub report_overview: Vec<String>,
     pub category_overview: Vec<String>,
     pub account_overview: Vec<String>,
+    // unread budget alerts, shown as a banner until dismissed with 'r'
+    pub notifications: Vec<String>,
 }
 
 impl Homepage {
     pub fn new(
         username: String,
-        email: String,
+        token: String,
         report_overview: Vec<String>,
         category_overview: Vec<String>,
         account_overview: Vec<String>
     ) -> Self {
         Self {
             username,
-            email,
+            token,
             report_overview,
             category_overview,
             account_overview,
+            notifications: vec![],
         }
     }
 
@@ -36,15 +43,20 @@ impl Homepage {
         let background = Block::default().style(Style::default().bg(Color::White));
         f.render_widget(background, f.area());
 
-        // Split the frame into four vertical chunks
+        // Split the frame into vertical chunks, the alert banner collapses when there is nothing to show
+        let banner_height = match self.notifications.len() {
+            0 => 0,
+            count => count.min(MAX_BANNER_LINES) as u16 + 2,
+        };
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .margin(1)
             .constraints(
                 [
-                    Constraint::Length(3), // Greeting row
-                    Constraint::Min(10),   // Main blocks (Accounts, Categories, Report)
-                    Constraint::Length(5), // Navigation notice (with extra padding)
+                    Constraint::Length(3),             // Greeting row
+                    Constraint::Length(banner_height), // Budget alert banner
+                    Constraint::Min(10),               // Main blocks (Accounts, Categories, Report)
+                    Constraint::Length(5),             // Navigation notice (with extra padding)
                 ]
                 .as_ref(),
             )
@@ -81,6 +93,34 @@ impl Homepage {
         f.render_widget(greeting_paragraph, horizontal_layout[0]);
         f.render_widget(title, horizontal_layout[1]);
 
+        // Budget alert banner, newest alerts first
+        if !self.notifications.is_empty() {
+            let mut banner_lines: Vec<Line> = self
+                .notifications
+                .iter()
+                .take(MAX_BANNER_LINES)
+                .map(|message| Line::raw(format!("! {}", message)))
+                .collect();
+            let hidden = self.notifications.len().saturating_sub(MAX_BANNER_LINES);
+            if hidden > 0 {
+                if let Some(last_line) = banner_lines.last_mut() {
+                    *last_line = Line::raw(format!("... and {} more", hidden + 1));
+                }
+            }
+            let banner_block = Block::default()
+                .title(format!(
+                    "Budget Alerts ({}) - press 'r' to dismiss",
+                    self.notifications.len()
+                ))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Red));
+            let banner = Paragraph::new(banner_lines)
+                .style(Style::default().fg(Color::Red).bg(Color::White))
+                .wrap(Wrap { trim: true })
+                .block(banner_block);
+            f.render_widget(banner, chunks[1]);
+        }
+
         // Main horizontal blocks: Accounts, Categories, Report
         let main_chunks = Layout::default()
             .direction(Direction::Horizontal)
@@ -92,7 +132,7 @@ impl Homepage {
                 ]
                 .as_ref(),
             )
-            .split(chunks[1]);
+            .split(chunks[2]);
 
         // Accounts block (press 1 to jump) with account overview
         let accounts_block = Block::default().title("Accounts").borders(Borders::ALL);
@@ -116,13 +156,13 @@ impl Homepage {
         f.render_widget(report_paragraph, main_chunks[2]);
 
         // Bottom notice for navigation instructions (Esc to quit, etc.)
-        let notice = Paragraph::new("Esc to quit | 1 to Account | 2 to Category | 3 to Report")
+        let notice = Paragraph::new("Esc to quit | 1 to Account | 2 to Category | 3 to Report | 4 to Recurring")
             .style(Style::default().fg(Color::DarkGray).bg(Color::White))
             .alignment(Alignment::Center);
-        f.render_widget(notice, chunks[2]);
+        f.render_widget(notice, chunks[3]);
 
         // Additional notice for transaction creation
-        let create_transaction_notice = Paragraph::new("Press 'n' to create a new transaction")
+        let create_transaction_notice = Paragraph::new("Press 'n' to create a new transaction | 'i' to import a bank statement")
             .style(Style::default().fg(Color::DarkGray).bg(Color::White))
             .alignment(Alignment::Center);
         f.render_widget(
@@ -130,7 +170,7 @@ impl Homepage {
             Layout::default()
                 .direction(Direction::Vertical)
                 .constraints([Constraint::Length(1), Constraint::Length(1)].as_ref())
-                .split(chunks[2])[1],
+                .split(chunks[3])[1],
         ); // Place it right below the first notice
     }
 }
@@ -0,0 +1,517 @@
+use crossterm::event::{KeyCode, KeyModifiers};
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+// Column mapping of a statement, columns are header names or 1-based positions
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ImportMapping {
+    pub category_name: Option<String>,
+    pub inflow_category_name: Option<String>,
+    pub has_header: Option<bool>,
+    pub date_column: String,
+    pub date_format: Option<String>,
+    pub amount_column: Option<String>,
+    pub debit_column: Option<String>,
+    pub credit_column: Option<String>,
+    pub description_column: Option<String>,
+    pub payee_column: Option<String>,
+    pub sign_convention: Option<String>,
+}
+
+impl ImportMapping {
+    // The mapping fields of the form, in the order of FIELD_TITLES after the file and account
+    fn field_values(&self) -> [String; 11] {
+        let text = |value: &Option<String>| value.clone().unwrap_or_default();
+        [
+            text(&self.category_name),
+            text(&self.inflow_category_name),
+            match self.has_header {
+                Some(false) => "n".to_string(),
+                _ => "y".to_string(),
+            },
+            self.date_column.clone(),
+            text(&self.date_format),
+            text(&self.amount_column),
+            text(&self.debit_column),
+            text(&self.credit_column),
+            text(&self.description_column),
+            text(&self.payee_column),
+            text(&self.sign_convention),
+        ]
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct ImportRequest {
+    pub account_name: String,
+    pub csv: String,
+    pub mapping: ImportMapping,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ImportRow {
+    pub line: usize,
+    pub date: Option<String>,
+    pub category_name: Option<String>,
+    pub amount: Option<Decimal>,
+    pub description: Option<String>,
+    pub payee: Option<String>,
+    pub status: String,
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ImportPreview {
+    pub account_name: String,
+    pub new_rows: usize,
+    pub duplicates: usize,
+    pub invalid: usize,
+    pub rows: Vec<ImportRow>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ImportResult {
+    pub imported: usize,
+    pub duplicates: usize,
+    pub invalid: usize,
+}
+
+const FIELD_TITLES: [&str; 13] = [
+    "Statement File (path to a .csv)",
+    "Account",
+    "Category",
+    "Inflow Category (optional, money coming in)",
+    "Header Row (y/n)",
+    "Date Column (name or number)",
+    "Date Format (optional, e.g. %m/%d/%Y)",
+    "Amount Column (signed amounts)",
+    "Debit Column (or debit/credit)",
+    "Credit Column",
+    "Description Column (optional)",
+    "Payee Column (optional)",
+    "Sign (negative_outflow/positive_outflow)",
+];
+// Index of the account among the form fields, leaving it loads the saved mapping
+const ACCOUNT_FIELD: usize = 1;
+// Index of the header row toggle among the form fields
+const HEADER_FIELD: usize = 4;
+
+pub struct ImportMain {
+    token: String,
+    message: String,
+    // the statement has been read, the rows are shown until committed or dismissed
+    preview: Option<ImportPreview>,
+    // what was previewed, committed as is even if the file changed since
+    previewed_request: Option<ImportRequest>,
+    list_state: ListState,
+    active_field: usize,
+    client: Client,
+    input_strings: [String; 13],
+}
+
+impl ImportMain {
+    pub fn new(token: String) -> Self {
+        let mut input_strings: [String; 13] = Default::default();
+        input_strings[HEADER_FIELD] = "y".to_string();
+        Self {
+            token,
+            message: "Map the statement columns, a saved mapping loads with the account"
+                .to_string(),
+            preview: None,
+            previewed_request: None,
+            list_state: ListState::default(),
+            active_field: 0,
+            client: Client::new(),
+            input_strings,
+        }
+    }
+
+    pub fn render(&mut self, f: &mut Frame) {
+        let background = Block::default().style(Style::default().bg(Color::White));
+        f.render_widget(background, f.area());
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(1)
+            .constraints(
+                [
+                    Constraint::Length(3), // Title
+                    Constraint::Min(10),   // Content
+                    Constraint::Length(3), // Message/Status
+                    Constraint::Length(3), // Navigation help
+                ]
+                .as_ref(),
+            )
+            .split(f.area());
+
+        let title = Paragraph::new("IMPORT BANK STATEMENT")
+            .style(
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::White)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .alignment(Alignment::Center);
+        f.render_widget(title, chunks[0]);
+
+        if self.preview.is_some() {
+            self.render_preview(f, chunks[1]);
+        } else {
+            self.render_form(f, chunks[1]);
+        }
+
+        let message_style = if self.message.contains("Error") || self.message.contains("Failed") {
+            Style::default().fg(Color::Red)
+        } else {
+            Style::default().fg(Color::Green)
+        };
+        let message = Paragraph::new(self.message.clone())
+            .style(message_style)
+            .alignment(Alignment::Center);
+        f.render_widget(message, chunks[2]);
+
+        let help_text = if self.preview.is_some() {
+            "ESC: Back to Mapping | C: Import New Rows | ↑↓: Navigate"
+        } else {
+            "ESC: Back | TAB: Next Field | ENTER: Preview"
+        };
+        let help = Paragraph::new(help_text)
+            .style(Style::default().fg(Color::DarkGray))
+            .alignment(Alignment::Center);
+        f.render_widget(help, chunks[3]);
+    }
+
+    // Two columns of fields: the file, account and dates on the left, the amounts on the right
+    fn render_form(&self, f: &mut Frame, area: Rect) {
+        let halves = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+            .split(area);
+        let half_fields = FIELD_TITLES.len().div_ceil(2);
+
+        for (half, half_area) in halves.iter().enumerate() {
+            let form_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .margin(1)
+                .constraints(vec![Constraint::Length(3); half_fields])
+                .split(*half_area);
+            for (row, row_area) in form_chunks.iter().enumerate() {
+                let i = half * half_fields + row;
+                if i >= FIELD_TITLES.len() {
+                    break;
+                }
+                let block = Block::default()
+                    .title(FIELD_TITLES[i])
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(if self.active_field == i {
+                        Color::Yellow
+                    } else {
+                        Color::Black
+                    }));
+                let text = Paragraph::new(self.input_strings[i].clone())
+                    .style(Style::default().fg(Color::Black));
+                f.render_widget(text.block(block), *row_area);
+            }
+        }
+    }
+
+    fn render_preview(&mut self, f: &mut Frame, area: Rect) {
+        let Some(preview) = self.preview.as_ref() else {
+            return;
+        };
+        let items: Vec<ListItem> = preview
+            .rows
+            .iter()
+            .map(|row| {
+                let details = match row.status.as_str() {
+                    "invalid" => row.error.clone().unwrap_or_default(),
+                    _ => {
+                        let payee_str = match row.payee.as_deref() {
+                            Some(payee) => format!(" @ {}", payee),
+                            None => String::new(),
+                        };
+                        format!(
+                            "{} {} {}{} {}",
+                            row.date.clone().unwrap_or_default(),
+                            row.category_name.clone().unwrap_or_default(),
+                            row.amount
+                                .map(|amount| amount.to_string())
+                                .unwrap_or_default(),
+                            payee_str,
+                            row.description.clone().unwrap_or_default()
+                        )
+                    }
+                };
+                let style = match row.status.as_str() {
+                    "new" => Style::default().fg(Color::Black),
+                    "duplicate" => Style::default().fg(Color::DarkGray),
+                    _ => Style::default().fg(Color::Red),
+                };
+                ListItem::new(format!(
+                    "line {:>4}  {:<9}  {}",
+                    row.line, row.status, details
+                ))
+                .style(style)
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(format!(
+                "{}: {} new, {} already recorded, {} invalid",
+                preview.account_name, preview.new_rows, preview.duplicates, preview.invalid
+            )))
+            .style(Style::default().fg(Color::Black))
+            .highlight_style(
+                Style::default()
+                    .add_modifier(Modifier::BOLD)
+                    .bg(Color::Yellow),
+            );
+
+        f.render_stateful_widget(list, area, &mut self.list_state);
+    }
+
+    pub async fn handle_input(&mut self, key: KeyCode, _modifiers: KeyModifiers) -> bool {
+        if key == KeyCode::Esc {
+            if self.preview.is_some() {
+                self.preview = None;
+                self.previewed_request = None;
+                return false;
+            }
+            return true;
+        }
+
+        if self.preview.is_some() {
+            self.handle_preview_input(key).await;
+        } else {
+            self.handle_form_input(key).await;
+        }
+        false
+    }
+
+    async fn handle_form_input(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Tab => {
+                if self.active_field == ACCOUNT_FIELD {
+                    self.load_saved_mapping().await;
+                }
+                self.active_field = (self.active_field + 1) % FIELD_TITLES.len();
+            }
+            KeyCode::BackTab => {
+                if self.active_field == ACCOUNT_FIELD {
+                    self.load_saved_mapping().await;
+                }
+                self.active_field = match self.active_field {
+                    0 => FIELD_TITLES.len() - 1,
+                    field => field - 1,
+                };
+            }
+            KeyCode::Enter => {
+                self.submit_preview().await;
+            }
+            KeyCode::Char(c) => {
+                self.input_strings[self.active_field].push(c);
+            }
+            KeyCode::Backspace => {
+                self.input_strings[self.active_field].pop();
+            }
+            _ => {}
+        }
+    }
+
+    async fn handle_preview_input(&mut self, key: KeyCode) {
+        let row_count = self
+            .preview
+            .as_ref()
+            .map_or(0, |preview| preview.rows.len());
+        match key {
+            KeyCode::Char('c') | KeyCode::Char('C') => {
+                self.submit_commit().await;
+            }
+            KeyCode::Up if row_count > 0 => {
+                let selected = self.list_state.selected().unwrap_or(0);
+                self.list_state.select(Some(if selected == 0 {
+                    row_count - 1
+                } else {
+                    selected - 1
+                }));
+            }
+            KeyCode::Down if row_count > 0 => {
+                let selected = self.list_state.selected().unwrap_or(0);
+                self.list_state.select(Some(if selected >= row_count - 1 {
+                    0
+                } else {
+                    selected + 1
+                }));
+            }
+            _ => {}
+        }
+    }
+
+    // Fills the mapping fields with the account's saved profile, an account without one keeps
+    // what is typed
+    async fn load_saved_mapping(&mut self) {
+        let account_name = self.input_strings[ACCOUNT_FIELD].trim().to_string();
+        if account_name.is_empty() {
+            return;
+        }
+        let response = self
+            .client
+            .get("http://localhost:8000/import_profile")
+            .bearer_auth(&self.token)
+            .query(&[("account_name", account_name.as_str())])
+            .send()
+            .await;
+        match response {
+            Ok(response) if response.status() == reqwest::StatusCode::OK => {
+                match response.json::<ImportMapping>().await {
+                    Ok(mapping) => {
+                        for (i, value) in mapping.field_values().into_iter().enumerate() {
+                            self.input_strings[i + 2] = value;
+                        }
+                        self.message = format!("Loaded the mapping saved for {}", account_name);
+                    }
+                    Err(_) => {
+                        self.message = "Failed to parse the saved mapping".to_string();
+                    }
+                }
+            }
+            Ok(response) if response.status() == reqwest::StatusCode::NOT_FOUND => {
+                self.message = format!("No saved mapping for {} yet", account_name);
+            }
+            Ok(response) => {
+                let message = response.text().await.unwrap_or_default();
+                self.message = format!("Error: {}", message);
+            }
+            Err(e) => {
+                self.message = format!("Error fetching the saved mapping: {}", e);
+            }
+        }
+    }
+
+    // The form as a request, the file is read again for each preview so edits to it are
+    // picked up
+    fn build_request(&self) -> Result<ImportRequest, String> {
+        let path = self.input_strings[0].trim();
+        if path.is_empty() || self.input_strings[ACCOUNT_FIELD].trim().is_empty() {
+            return Err("Please fill in the statement file and the account".to_string());
+        }
+        let csv =
+            std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        let optional = |i: usize| {
+            let value = self.input_strings[i].trim();
+            (!value.is_empty()).then(|| value.to_string())
+        };
+        let has_header = match self.input_strings[HEADER_FIELD]
+            .trim()
+            .to_ascii_lowercase()
+            .as_str()
+        {
+            "" | "y" | "yes" => true,
+            "n" | "no" => false,
+            _ => return Err("Invalid header row, expected y or n".to_string()),
+        };
+        Ok(ImportRequest {
+            account_name: self.input_strings[ACCOUNT_FIELD].trim().to_string(),
+            csv,
+            mapping: ImportMapping {
+                category_name: optional(2),
+                inflow_category_name: optional(3),
+                has_header: Some(has_header),
+                date_column: self.input_strings[5].trim().to_string(),
+                date_format: optional(6),
+                amount_column: optional(7),
+                debit_column: optional(8),
+                credit_column: optional(9),
+                description_column: optional(10),
+                payee_column: optional(11),
+                sign_convention: optional(12),
+            },
+        })
+    }
+
+    async fn submit_preview(&mut self) {
+        let request = match self.build_request() {
+            Ok(request) => request,
+            Err(message) => {
+                self.message = message;
+                return;
+            }
+        };
+        let response = self
+            .client
+            .post("http://localhost:8000/import_preview")
+            .bearer_auth(&self.token)
+            .json(&request)
+            .send()
+            .await;
+        match response {
+            Ok(response) if response.status() == reqwest::StatusCode::OK => {
+                match response.json::<ImportPreview>().await {
+                    Ok(preview) => {
+                        self.message = "Check the rows, C imports the new ones".to_string();
+                        self.list_state
+                            .select((!preview.rows.is_empty()).then_some(0));
+                        self.preview = Some(preview);
+                        self.previewed_request = Some(request);
+                    }
+                    Err(_) => {
+                        self.message = "Failed to parse the preview".to_string();
+                    }
+                }
+            }
+            Ok(response) => {
+                let message = response.text().await.unwrap_or_default();
+                self.message = format!("Error: {}", message);
+            }
+            Err(e) => {
+                self.message = format!("Error previewing the statement: {}", e);
+            }
+        }
+    }
+
+    // Sends the statement exactly as previewed, the file is not read again
+    async fn submit_commit(&mut self) {
+        let Some(request) = self.previewed_request.as_ref() else {
+            self.message = "Preview the statement before importing it".to_string();
+            return;
+        };
+        let response = self
+            .client
+            .post("http://localhost:8000/import_commit")
+            .bearer_auth(&self.token)
+            .json(request)
+            .send()
+            .await;
+        match response {
+            Ok(response) if response.status() == reqwest::StatusCode::OK => {
+                match response.json::<ImportResult>().await {
+                    Ok(result) => {
+                        self.message = format!(
+                            "Imported {} transactions, skipped {} already recorded and {} invalid",
+                            result.imported, result.duplicates, result.invalid
+                        );
+                        self.preview = None;
+                        self.previewed_request = None;
+                    }
+                    Err(_) => {
+                        self.message = "Failed to parse the import result".to_string();
+                    }
+                }
+            }
+            Ok(response) => {
+                let message = response.text().await.unwrap_or_default();
+                self.message = format!("Error: {}", message);
+            }
+            Err(e) => {
+                self.message = format!("Error importing the statement: {}", e);
+            }
+        }
+    }
+}
@@ -14,12 +14,17 @@ use crate::ui::homepage::Homepage;
 use crate::ui::report::*;
 
 #[derive(Serialize)]
-struct SignupData {
-    username: String,
+struct LoginData {
     email: String,
     password: String,
 }
 
+#[derive(Deserialize)]
+struct LoginResponse {
+    token: String,
+    username: String,
+}
+
 pub struct LoginPage {
     pub email: InputField,
     pub password: InputField,
@@ -130,43 +135,44 @@ impl LoginPage {
 
     pub async fn submit(&mut self, homepage: &mut Option<Homepage>) {
         let client = Client::new();
-        let login_data = SignupData {
-            username: "_login".to_string(),
+        let login_data = LoginData {
             email: self.email.content.clone(),
             password: self.password.content.clone(),
         };
 
         match client
-            .post("http://0.0.0.0:8000/signup")
+            .post("http://0.0.0.0:8000/login")
             .json(&login_data)
             .send()
             .await
         {
             Ok(response) => {
                 let status = response.status();
-                let raw_body = response.text().await.unwrap_or_default();
-
-                if status == 200 && raw_body.contains("Login successful") {
-                    self.report_overview = get_report_overview(login_data.email.clone()).await;
-                    let category_overview = get_category_overview(login_data.email.clone()).await;
-                    let account_overview = get_account_overview(login_data.email.clone()).await;
-                    if let Some(username) = raw_body.split_whitespace().next() {
-                        *homepage = Some(Homepage::new(
-                            username.to_string(),
-                            self.email.content.clone(),
-                            self.report_overview.clone(),
-                            category_overview,
-                            account_overview,
-                        ));
-                        self.response_message =
-                            "Login successful! Redirecting to homepage...".to_string();
+
+                if status == 200 {
+                    match response.json::<LoginResponse>().await {
+                        Ok(session) => {
+                            self.report_overview = get_report_overview(session.token.clone()).await;
+                            let category_overview = get_category_overview(session.token.clone()).await;
+                            let account_overview = get_account_overview(session.token.clone()).await;
+                            *homepage = Some(Homepage::new(
+                                session.username,
+                                session.token,
+                                self.report_overview.clone(),
+                                category_overview,
+                                account_overview,
+                            ));
+                            self.response_message =
+                                "Login successful! Redirecting to homepage...".to_string();
+                        }
+                        Err(e) => {
+                            self.response_message = format!("Failed to parse login response: {}", e);
+                        }
                     }
-                } else if status == 400 {
-                    self.response_message = "Invalid password. Please try again.".to_string();
-                } else if status == 201 {
-                    self.response_message =
-                        "Email not registered. Please sign up first.".to_string();
+                } else if status == 401 {
+                    self.response_message = "Invalid email or password. Please try again.".to_string();
                 } else {
+                    let raw_body = response.text().await.unwrap_or_default();
                     self.response_message =
                         format!("ERROR_CODE: {}\nMessage: {}", status, raw_body);
                 }
@@ -177,3 +183,13 @@ impl LoginPage {
         }
     }
 }
+
+// End the session on the server so the token cannot be reused
+pub async fn logout(token: String) {
+    let client = Client::new();
+    let _ = client
+        .delete("http://0.0.0.0:8000/logout")
+        .bearer_auth(token)
+        .send()
+        .await;
+}
@@ -6,5 +6,7 @@ pub mod report;
 pub mod signup;
 pub mod account_main;
 pub mod category_main;
+pub mod import_main;
+pub mod recurring_main;
 pub mod report_main;
 pub mod transaction_create;
\ No newline at end of file
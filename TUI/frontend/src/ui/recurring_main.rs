@@ -0,0 +1,589 @@
+use crossterm::event::{KeyCode, KeyModifiers};
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Recurring {
+    pub recurring_id: i32,
+    pub category_name: String,
+    pub account_name: String,
+    pub amount: Decimal,
+    pub notes: Option<String>,
+    pub currency: Option<String>,
+    pub frequency: String,
+    pub interval_count: i32,
+    pub start_date: String,
+    pub end_date: Option<String>,
+    pub next_date: String,
+    pub paused: bool,
+}
+
+impl Recurring {
+    // "every month", "every 2 weeks"
+    fn schedule_label(&self) -> String {
+        let unit = match self.frequency.as_str() {
+            "daily" => "day",
+            "weekly" => "week",
+            "monthly" => "month",
+            "yearly" => "year",
+            other => other,
+        };
+        match self.interval_count {
+            1 => format!("every {}", unit),
+            count => format!("every {} {}s", count, unit),
+        }
+    }
+
+    // The form fields, in the order of FIELD_TITLES
+    fn field_values(&self) -> [String; 8] {
+        [
+            self.category_name.clone(),
+            self.account_name.clone(),
+            self.amount.to_string(),
+            self.notes.clone().unwrap_or_default(),
+            self.frequency.clone(),
+            self.interval_count.to_string(),
+            self.start_date.clone(),
+            self.end_date.clone().unwrap_or_default(),
+        ]
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct NewRecurring {
+    pub category_name: String,
+    pub account_name: String,
+    pub amount: Decimal,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    pub frequency: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval_count: Option<i32>,
+    pub start_date: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<String>,
+}
+
+// Only the fields that changed are sent, a changed schedule starts over on the backend
+#[derive(Serialize, Debug, Default)]
+pub struct RecurringUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval_count: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paused: Option<bool>,
+}
+
+const FIELD_TITLES: [&str; 8] = [
+    "Category",
+    "Account",
+    "Amount",
+    "Notes (optional)",
+    "Frequency (daily/weekly/monthly/yearly)",
+    "Every (optional, e.g. 2 with weekly is every other week)",
+    "Start Date (YYYY-MM-DD, monthly repeats on this day)",
+    "End Date (optional, YYYY-MM-DD)",
+];
+
+pub struct RecurringMain {
+    recurrences: Vec<Recurring>,
+    list_state: ListState,
+    token: String,
+    message: String,
+    // the form is open, to create a recurrence or to edit `editing`
+    in_form: bool,
+    editing: Option<Recurring>,
+    active_field: usize,
+    client: Client,
+    input_strings: [String; 8],
+}
+
+impl RecurringMain {
+    pub fn new(token: String) -> Self {
+        Self {
+            recurrences: Vec::new(),
+            list_state: ListState::default(),
+            token,
+            message: "Loading recurring transactions...".to_string(),
+            in_form: false,
+            editing: None,
+            active_field: 0,
+            client: Client::new(),
+            input_strings: Default::default(),
+        }
+    }
+
+    pub fn render(&mut self, f: &mut Frame) {
+        let background = Block::default().style(Style::default().bg(Color::White));
+        f.render_widget(background, f.area());
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(1)
+            .constraints(
+                [
+                    Constraint::Length(3), // Title
+                    Constraint::Min(10),   // Content
+                    Constraint::Length(3), // Message/Status
+                    Constraint::Length(3), // Navigation help
+                ]
+                .as_ref(),
+            )
+            .split(f.area());
+
+        let title = Paragraph::new("RECURRING TRANSACTIONS")
+            .style(
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::White)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .alignment(Alignment::Center);
+        f.render_widget(title, chunks[0]);
+
+        if self.in_form {
+            self.render_form(f, chunks[1]);
+        } else {
+            self.render_recurring_list(f, chunks[1]);
+        }
+
+        let message_style = if self.message.contains("Error") || self.message.contains("Failed") {
+            Style::default().fg(Color::Red)
+        } else {
+            Style::default().fg(Color::Green)
+        };
+        let message = Paragraph::new(self.message.clone())
+            .style(message_style)
+            .alignment(Alignment::Center);
+        f.render_widget(message, chunks[2]);
+
+        let help_text = if self.in_form {
+            "ESC: Back | TAB: Next Field | ENTER: Submit"
+        } else {
+            "ESC: Back | N: New | E: Edit | P: Pause/Resume | D: Delete | ↑↓: Navigate"
+        };
+        let help = Paragraph::new(help_text)
+            .style(Style::default().fg(Color::DarkGray))
+            .alignment(Alignment::Center);
+        f.render_widget(help, chunks[3]);
+    }
+
+    fn render_recurring_list(&mut self, f: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self
+            .recurrences
+            .iter()
+            .map(|rec| {
+                let amount_str = match rec.currency {
+                    Some(ref code) => format!("{} {}", rec.amount, code),
+                    None => rec.amount.to_string(),
+                };
+                let status = if rec.paused {
+                    "paused".to_string()
+                } else if rec.end_date.as_ref().is_some_and(|end| *end < rec.next_date) {
+                    "ended".to_string()
+                } else {
+                    format!("next {}", rec.next_date)
+                };
+                let notes_str = match rec.notes.as_deref() {
+                    Some(notes) if !notes.is_empty() => format!(", {}", notes),
+                    _ => String::new(),
+                };
+                ListItem::new(format!(
+                    "{} -> {}: {} {} from {} ({}){}",
+                    rec.account_name,
+                    rec.category_name,
+                    amount_str,
+                    rec.schedule_label(),
+                    rec.start_date,
+                    status,
+                    notes_str
+                ))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL))
+            .style(Style::default().fg(Color::Black))
+            .highlight_style(
+                Style::default()
+                    .add_modifier(Modifier::BOLD)
+                    .fg(Color::Yellow),
+            );
+
+        f.render_stateful_widget(list, area, &mut self.list_state);
+    }
+
+    fn render_form(&self, f: &mut Frame, area: Rect) {
+        let form_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(1)
+            .constraints([Constraint::Length(3); 8].as_ref())
+            .split(area);
+
+        for (i, title) in FIELD_TITLES.iter().enumerate() {
+            let block = Block::default()
+                .title(*title)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(if self.active_field == i {
+                    Color::Yellow
+                } else {
+                    Color::Black
+                }));
+            let text = Paragraph::new(self.input_strings[i].clone())
+                .style(Style::default().fg(Color::Black));
+            f.render_widget(text.block(block), form_chunks[i]);
+        }
+    }
+
+    pub async fn handle_input(&mut self, key: KeyCode, _modifiers: KeyModifiers) -> bool {
+        if key == KeyCode::Esc {
+            if self.in_form {
+                self.in_form = false;
+                self.editing = None;
+                return false;
+            }
+            return true;
+        }
+
+        if self.in_form {
+            self.handle_form_input(key).await;
+        } else {
+            self.handle_list_input(key).await;
+        }
+        false
+    }
+
+    async fn handle_form_input(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Tab => {
+                self.active_field = (self.active_field + 1) % FIELD_TITLES.len();
+            }
+            KeyCode::Enter => {
+                if self.editing.is_some() {
+                    self.submit_update().await;
+                } else {
+                    self.submit_new_recurring().await;
+                }
+            }
+            KeyCode::Char(c) => {
+                self.input_strings[self.active_field].push(c);
+            }
+            KeyCode::Backspace => {
+                self.input_strings[self.active_field].pop();
+            }
+            _ => {}
+        }
+    }
+
+    fn selected(&self) -> Option<Recurring> {
+        self.list_state
+            .selected()
+            .and_then(|selected| self.recurrences.get(selected))
+            .cloned()
+    }
+
+    async fn handle_list_input(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Char('n') => {
+                self.in_form = true;
+                self.editing = None;
+                self.input_strings = Default::default();
+                self.active_field = 0;
+            }
+            KeyCode::Char('e') => {
+                if let Some(rec) = self.selected() {
+                    self.input_strings = rec.field_values();
+                    self.editing = Some(rec);
+                    self.in_form = true;
+                    self.active_field = 0;
+                }
+            }
+            KeyCode::Char('p') => {
+                if let Some(rec) = self.selected() {
+                    let update = RecurringUpdate {
+                        paused: Some(!rec.paused),
+                        ..Default::default()
+                    };
+                    let done = if rec.paused { "resumed" } else { "paused" };
+                    self.send_update(rec.recurring_id, &update, done).await;
+                }
+            }
+            KeyCode::Char('d') => {
+                if let Some(rec) = self.selected() {
+                    self.delete_recurring(rec.recurring_id).await;
+                }
+            }
+            KeyCode::Up => {
+                let selected = self.list_state.selected().unwrap_or(0);
+                if !self.recurrences.is_empty() {
+                    self.list_state.select(Some(if selected == 0 {
+                        self.recurrences.len() - 1
+                    } else {
+                        selected - 1
+                    }));
+                }
+            }
+            KeyCode::Down => {
+                let selected = self.list_state.selected().unwrap_or(0);
+                if !self.recurrences.is_empty() {
+                    self.list_state
+                        .select(Some(if selected >= self.recurrences.len() - 1 {
+                            0
+                        } else {
+                            selected + 1
+                        }));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub async fn initialize(&mut self) {
+        self.fetch_recurrences().await;
+    }
+
+    async fn fetch_recurrences(&mut self) {
+        let url = "http://localhost:8000/recurring_summary";
+        match self.client.get(url).bearer_auth(&self.token).send().await {
+            Ok(response) => match response.status() {
+                reqwest::StatusCode::OK => {
+                    if let Ok(recurrences) = response.json::<Vec<Recurring>>().await {
+                        self.recurrences = recurrences;
+                        match self.list_state.selected() {
+                            _ if self.recurrences.is_empty() => self.list_state.select(None),
+                            Some(selected) if selected >= self.recurrences.len() => {
+                                self.list_state.select(Some(self.recurrences.len() - 1))
+                            }
+                            None => self.list_state.select(Some(0)),
+                            _ => {}
+                        }
+                    } else {
+                        self.message = "Failed to parse recurring transaction data".to_string();
+                    }
+                }
+                _ => {
+                    self.message = "Failed to fetch recurring transactions".to_string();
+                }
+            },
+            Err(e) => {
+                self.message = format!("Error fetching recurring transactions: {}", e);
+            }
+        }
+    }
+
+    // The optional interval field, None when blank
+    fn parse_interval(&self) -> Result<Option<i32>, String> {
+        let raw = self.input_strings[5].trim();
+        if raw.is_empty() {
+            return Ok(None);
+        }
+        raw.parse::<i32>()
+            .map(Some)
+            .map_err(|_| "Invalid interval, expected a whole number".to_string())
+    }
+
+    async fn submit_new_recurring(&mut self) {
+        // Category, account, amount, frequency and start date are required
+        if [0, 1, 2, 4, 6]
+            .iter()
+            .any(|&i| self.input_strings[i].trim().is_empty())
+        {
+            self.message = "Please fill in all required fields".to_string();
+            return;
+        }
+        let amount = match self.input_strings[2].trim().parse::<Decimal>() {
+            Ok(value) => value,
+            Err(_) => {
+                self.message = "Invalid amount".to_string();
+                return;
+            }
+        };
+        let interval_count = match self.parse_interval() {
+            Ok(interval_count) => interval_count,
+            Err(e) => {
+                self.message = e;
+                return;
+            }
+        };
+        let optional = |value: &String| Some(value.trim().to_string()).filter(|s| !s.is_empty());
+
+        let new_recurring = NewRecurring {
+            category_name: self.input_strings[0].trim().to_string(),
+            account_name: self.input_strings[1].trim().to_string(),
+            amount,
+            notes: optional(&self.input_strings[3]),
+            frequency: self.input_strings[4].trim().to_string(),
+            interval_count,
+            start_date: self.input_strings[6].trim().to_string(),
+            end_date: optional(&self.input_strings[7]),
+        };
+
+        match self
+            .client
+            .post("http://localhost:8000/recurring_create")
+            .bearer_auth(&self.token)
+            .json(&new_recurring)
+            .send()
+            .await
+        {
+            Ok(response) => {
+                let status = response.status();
+                let message = response.text().await.unwrap_or_default();
+                match status {
+                    reqwest::StatusCode::CREATED => {
+                        self.message = "Recurring transaction created successfully".to_string();
+                        self.in_form = false;
+                        self.input_strings = Default::default();
+                        self.active_field = 0;
+                        self.fetch_recurrences().await;
+                    }
+                    reqwest::StatusCode::BAD_REQUEST => {
+                        self.message = message;
+                    }
+                    _ => {
+                        self.message = format!("Failed to create recurring transaction: {}", message);
+                    }
+                }
+            }
+            Err(e) => {
+                self.message = format!("Error creating recurring transaction: {}", e);
+            }
+        }
+    }
+
+    async fn submit_update(&mut self) {
+        let Some(original) = self.editing.clone() else {
+            return;
+        };
+        let before = original.field_values();
+        let changed = |i: usize| {
+            let value = self.input_strings[i].trim();
+            (value != before[i]).then(|| value.to_string())
+        };
+
+        let amount = match changed(2) {
+            Some(raw) => match raw.parse::<Decimal>() {
+                Ok(value) => Some(value),
+                Err(_) => {
+                    self.message = "Invalid amount".to_string();
+                    return;
+                }
+            },
+            None => None,
+        };
+        let interval_count = match changed(5) {
+            Some(_) => match self.parse_interval() {
+                Ok(interval_count) => Some(interval_count.unwrap_or(1)),
+                Err(e) => {
+                    self.message = e;
+                    return;
+                }
+            },
+            None => None,
+        };
+        let update = RecurringUpdate {
+            category_name: changed(0),
+            account_name: changed(1),
+            amount,
+            notes: changed(3),
+            frequency: changed(4),
+            interval_count,
+            start_date: changed(6),
+            end_date: changed(7),
+            paused: None,
+        };
+        if self.send_update(original.recurring_id, &update, "updated").await {
+            self.in_form = false;
+            self.editing = None;
+            self.input_strings = Default::default();
+            self.active_field = 0;
+        }
+    }
+
+    async fn send_update(&mut self, recurring_id: i32, update: &RecurringUpdate, done: &str) -> bool {
+        let url = format!(
+            "http://localhost:8000/recurring_update?recurring_id={}",
+            recurring_id
+        );
+        match self
+            .client
+            .patch(&url)
+            .bearer_auth(&self.token)
+            .json(update)
+            .send()
+            .await
+        {
+            Ok(response) => {
+                let status = response.status();
+                let message = response.text().await.unwrap_or_default();
+                match status {
+                    reqwest::StatusCode::OK => {
+                        self.message = format!("Recurring transaction {}", done);
+                        self.fetch_recurrences().await;
+                        true
+                    }
+                    reqwest::StatusCode::BAD_REQUEST => {
+                        self.message = message;
+                        false
+                    }
+                    _ => {
+                        self.message = format!("Failed to update recurring transaction: {}", message);
+                        false
+                    }
+                }
+            }
+            Err(e) => {
+                self.message = format!("Error updating recurring transaction: {}", e);
+                false
+            }
+        }
+    }
+
+    async fn delete_recurring(&mut self, recurring_id: i32) {
+        let url = format!(
+            "http://localhost:8000/delete_recurring?recurring_id={}",
+            recurring_id
+        );
+
+        match self.client.delete(&url).bearer_auth(&self.token).send().await {
+            Ok(response) => {
+                let status = response.status();
+                let message = response.text().await.unwrap_or_default();
+                match status {
+                    reqwest::StatusCode::OK => {
+                        self.message =
+                            "Recurring transaction deleted, its past transactions are kept"
+                                .to_string();
+                        self.fetch_recurrences().await;
+                    }
+                    _ => {
+                        self.message = format!("Failed to delete recurring transaction: {}", message);
+                    }
+                }
+            }
+            Err(e) => {
+                self.message = format!("Error deleting recurring transaction: {}", e);
+            }
+        }
+    }
+}
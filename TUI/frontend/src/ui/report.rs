@@ -1,23 +1,22 @@
 use ratatui::{style::Stylize, text::Line};
 use reqwest::Client;
+use serde::Deserialize;
 
-pub async fn get_account_overview(user_email: String) -> Vec<String> {
+pub async fn get_account_overview(token: String) -> Vec<String> {
     let client = Client::new();
-    let url = format!(
-        "http://localhost:8000/account_summary?email={}",
-        user_email
-    );
+    let url = "http://localhost:8000/account_summary";
 
-    match client.get(&url).send().await {
+    match client.get(url).bearer_auth(token).send().await {
         Ok(response) => {
             match response.status() {
                 reqwest::StatusCode::OK => {
                     if let Ok(accounts) = response.json::<Vec<crate::ui::account_main::Account>>().await {
                         accounts.iter().map(|acc| {
                             format!(
-                                "{}: {}",
+                                "{}: {} ({})",
                                 acc.account_name,
-                                acc.account_type
+                                acc.account_type,
+                                acc.currency
                             )
                         }).collect()
                     } else {
@@ -31,14 +30,11 @@ pub async fn get_account_overview(user_email: String) -> Vec<String> {
     }
 }
 
-pub async fn get_category_overview(user_email: String) -> Vec<String> {
+pub async fn get_category_overview(token: String) -> Vec<String> {
     let client = Client::new();
-    let url = format!(
-        "http://localhost:8000/category_summary?email={}",
-        user_email
-    );
+    let url = "http://localhost:8000/category_summary";
 
-    match client.get(&url).send().await {
+    match client.get(url).bearer_auth(token).send().await {
         Ok(response) => {
             match response.status() {
                 reqwest::StatusCode::OK => {
@@ -63,30 +59,56 @@ pub async fn get_category_overview(user_email: String) -> Vec<String> {
     }
 }
 
-pub async fn get_report_overview(user_email: String) -> Vec<String> {
+pub async fn get_report_overview(token: String) -> Vec<String> {
     let client = Client::new();
-    let overview_url = format!(
-        "http://0.0.0.0:8000/report_overview?email={}",
-        user_email.clone().as_str()
-    );
-    match client.get(overview_url).send().await {
+    let overview_url = "http://0.0.0.0:8000/report_overview";
+    match client.get(overview_url).bearer_auth(token).send().await {
         Ok(response) => {
             let status = response.status();
             let raw_body = response.text().await.unwrap_or_default();
             let body_vec: Vec<String> = serde_json::from_str(raw_body.as_str()).unwrap();
 
             if status.is_success() {
-                return body_vec;
+                body_vec
             } else {
-                return vec!["Error querying report overview!".to_string()];
+                vec!["Error querying report overview!".to_string()]
             }
         }
         Err(_) => {
-            return vec!["Error querying report overview!".to_string()];
+            vec!["Error querying report overview!".to_string()]
         }
     }
 }
 
+// Messages of the budget alerts the user has not dismissed yet, newest first
+pub async fn get_unread_notifications(token: String) -> Vec<String> {
+    let client = Client::new();
+    let url = "http://localhost:8000/notifications?unread=true";
+
+    match client.get(url).bearer_auth(token).send().await {
+        Ok(response) => match response.status() {
+            reqwest::StatusCode::OK => match response.json::<Vec<Notification>>().await {
+                Ok(notifications) => notifications.into_iter().map(|n| n.message).collect(),
+                Err(_) => vec!["Error parsing notifications".to_string()],
+            },
+            _ => vec!["Failed to fetch notifications".to_string()],
+        },
+        Err(_) => vec![],
+    }
+}
+
+// Dismisses every unread budget alert
+pub async fn read_notifications(token: String) {
+    let client = Client::new();
+    let url = "http://localhost:8000/read_notifications";
+    let _ = client.patch(url).bearer_auth(token).send().await;
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Notification {
+    pub message: String,
+}
+
 // helper function to render summary tab
 /// Create some lines to display in the paragraph.
 pub fn create_lines(summary_lines: Vec<String>) -> Vec<Line<'static>> {
@@ -12,39 +12,109 @@ use ratatui::{
     },
     Frame,
 };
+use chrono::{DateTime, Datelike, Duration, Local, Months, NaiveDate};
 use reqwest::Client;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
+use crate::ui::components::InputField;
+
 #[derive(Deserialize, Debug, Serialize)]
 pub struct CategorySummary {
     pub nickname: String,
-    pub budget: f64,
+    // income, expense or transfer
+    pub category_type: String,
+    pub budget: Decimal,
     pub budget_freq: String,
     pub overbudget: bool,
-    pub total: f64,
+    pub target_reached: bool,
+    // current budget period, missing for frequencies without one
+    pub bounds: Option<PeriodBounds>,
+    // left over (positive) or overspent (negative) in the previous period
+    pub carried: Decimal,
+    // budget + carried
+    pub effective_budget: Decimal,
+    pub total: Decimal,
+    // currency of the total and the budget
+    pub base_currency: String,
     // a vector of corresponding transaction ids of the entries below
     pub transaction_idz: Vec<i32>,
     // a vector of all the relevant transactions within budget freq frame
     pub cat_trans: Vec<String>,
 }
 
+// Start (included) and end (excluded) of a budget period, RFC 3339 in the user's time zone
+#[derive(Deserialize, Debug, Serialize)]
+pub struct PeriodBounds {
+    pub start: String,
+    pub end: String,
+}
+
+impl PeriodBounds {
+    // "2026-10-15 to 2026-11-14", both days included
+    fn days_label(&self) -> String {
+        match (
+            DateTime::parse_from_rfc3339(&self.start),
+            DateTime::parse_from_rfc3339(&self.end),
+        ) {
+            (Ok(start), Ok(end)) => format!(
+                "{} to {}",
+                start.date_naive(),
+                (end - Duration::days(1)).date_naive()
+            ),
+            _ => format!("{} to {}", self.start, self.end),
+        }
+    }
+}
+
+// Response of GET /settings, only the part the report pages with
+#[derive(Deserialize, Debug)]
+struct UserSettings {
+    period_start_day: u32,
+}
+
+// Response of GET /report_cashflow, shown under the page title
+#[derive(Deserialize, Debug)]
+pub struct CashFlowReport {
+    pub base_currency: String,
+    pub bounds: PeriodBounds,
+    pub income: Decimal,
+    pub expense: Decimal,
+    pub net_cash_flow: Decimal,
+    pub savings_rate: Option<Decimal>,
+}
+
 // https://ratatui.rs/examples/widgets/block/
 // Create a bordered block with a title.
-fn title_block(
-    cat_name: &str,
-    overbudget: bool,
-    budget: f64,
-    budget_freq: String,
-    spent: f64,
-    is_active: bool,
-) -> Block {
+fn title_block(summary: &CategorySummary, is_active: bool) -> Block<'_> {
+    let cat_name = summary.nickname.as_str();
+    let budget = summary.budget;
+    let budget_freq = summary.budget_freq.clone();
+    let spent = summary.total;
+    let currency = summary.base_currency.clone();
+    // over budget for expenses, target not reached for income
+    let off_track = match summary.category_type.as_str() {
+        "income" => !summary.target_reached,
+        _ => summary.overbudget,
+    };
     // a line on the budget information:
     let budget_str = format!("{:.2}", budget);
     let spent_str = format!("{:.2}", spent);
     let mut budget_freq_str = budget_freq.to_string();
     budget_freq_str.make_ascii_uppercase();
-    let budget_suffix = format!("{} budget: {}", budget_freq_str, budget_str);
-    let new_block = match overbudget {
+    let mut budget_suffix = match summary.category_type.as_str() {
+        "income" => format!("{} target: {} {}", budget_freq_str, budget_str, currency),
+        "transfer" => "transfer".to_string(),
+        _ if !summary.carried.is_zero() => format!(
+            "{} budget: {} {:+.2} carried = {:.2} {}",
+            budget_freq_str, budget_str, summary.carried, summary.effective_budget, currency
+        ),
+        _ => format!("{} budget: {} {}", budget_freq_str, budget_str, currency),
+    };
+    if let Some(bounds) = &summary.bounds {
+        budget_suffix = format!("{} ({})", budget_suffix, bounds.days_label());
+    }
+    let new_block = match off_track {
         false => Block::bordered()
             .title(cat_name.blue().on_white().bold())
             .title(spent_str.green().on_white().bold())
@@ -67,6 +137,30 @@ fn title_block(
     new_block
 }
 
+// Body of PATCH /update_trans, blank fields in the edit form are left out
+#[derive(Serialize, Debug)]
+struct TransactionUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    amount: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    notes: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    category_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    account_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transaction_date: Option<String>,
+}
+
+fn non_empty(field: &InputField) -> Option<String> {
+    let content = field.content.trim();
+    if content.is_empty() {
+        None
+    } else {
+        Some(content.to_string())
+    }
+}
+
 /*fn generate_report_block(budget: f64, budget_freq: String, spent: f64) -> Vec<Line<'static>> {
     let mut all_lines: Vec<Line<'static>> = vec![];
     // a line on the budget information:
@@ -94,9 +188,10 @@ fn title_block(
 }*/
 
 pub struct ReportMain {
-    pub email: String,
+    pub token: String,
     // each element in vector is a block to be rendered
     pub summary_blocks: Vec<CategorySummary>,
+    pub cash_flow: Option<CashFlowReport>,
     pub client: Client,
     // we need multiple list states as we have multiple summary blocks
     pub list_states: Vec<ListState>,
@@ -104,38 +199,108 @@ pub struct ReportMain {
     pub active_cat: usize,
     // trans_mapping[active_cat][list_states[active_cat].selected] = transaction_id
     pub trans_mapping: Vec<Vec<i32>>,
+    // id of the transaction currently open in the edit form
+    pub editing_trans: Option<i32>,
+    // amount, notes, category, account, date
+    pub edit_fields: [InputField; 5],
+    pub active_field: usize,
+    // date shown when the form was opened, only sent back if the user changed it
+    pub original_date: String,
+    pub message: String,
+    // 0 shows every category's current period, 1 the budget month before, and so on
+    pub month_offset: u32,
+    // day of the month budget months start on, from the user's settings
+    pub period_start_day: u32,
 }
 
 impl ReportMain {
-    pub fn new(email: String) -> Self {
-        let instance = Self {
+    pub fn new(token: String) -> Self {
+        Self {
             summary_blocks: Vec::new(),
-            email: email.clone(),
+            cash_flow: None,
+            token,
             client: Client::new(),
             list_states: Vec::new(),
             active_cat: 0,
             trans_mapping: Vec::new(),
+            editing_trans: None,
+            edit_fields: [
+                InputField::new("Amount", false),
+                InputField::new("Notes", false),
+                InputField::new("Category Name", false),
+                InputField::new("Account Name (blank keeps current)", false),
+                InputField::new("Date (YYYY-MM-DD)", false),
+            ],
+            active_field: 0,
+            original_date: String::new(),
+            message: String::new(),
+            month_offset: 0,
+            period_start_day: 1,
+        }
+    }
+
+    // "2026-09" for the budget month `month_offset` months before the current one
+    fn selected_month(&self) -> Option<String> {
+        if self.month_offset == 0 {
+            return None;
+        }
+        let today = Local::now().date_naive();
+        let this_month = NaiveDate::from_ymd_opt(today.year(), today.month(), 1)?;
+        // before the start day we are still in last month's budget
+        let current = if today.day() >= self.period_start_day {
+            this_month
+        } else {
+            this_month.checked_sub_months(Months::new(1))?
         };
-        instance
+        let selected = current.checked_sub_months(Months::new(self.month_offset))?;
+        Some(selected.format("%Y-%m").to_string())
+    }
+
+    async fn get_settings(&mut self) {
+        let url = "http://localhost:8000/settings";
+        match self.client.get(url).bearer_auth(&self.token).send().await {
+            Ok(response) => if response.status() == reqwest::StatusCode::OK {
+                if let Ok(settings) = response.json::<UserSettings>().await {
+                    self.period_start_day = settings.period_start_day;
+                }
+            },
+            Err(_e) => {}
+        }
     }
 
     // mimicking what account_main does: not sure how this works, hopefully just magically.
     pub async fn initialize(&mut self) {
+        self.get_settings().await;
         self.get_categorical_summary().await;
+        self.get_cash_flow().await;
+    }
+
+    async fn get_cash_flow(&mut self) {
+        let url = "http://localhost:8000/report_cashflow";
+        match self.client.get(url).bearer_auth(&self.token).send().await {
+            Ok(response) => if response.status() == reqwest::StatusCode::OK {
+                self.cash_flow = response.json::<CashFlowReport>().await.ok();
+            },
+            Err(_e) => {}
+        }
     }
 
     async fn get_categorical_summary(&mut self) {
-        let url = format!("http://localhost:8000/report_details?email={}", self.email);
-        match self.client.get(&url).send().await {
-            Ok(response) => match response.status() {
-                reqwest::StatusCode::OK => {
-                    if let Ok(mut cat_sum) = response.json::<Vec<CategorySummary>>().await {
-                        // sort the categories by nickname
-                        cat_sum.sort_unstable_by_key(|item| (item.nickname.clone()));
-                        self.summary_blocks = cat_sum;
-                    }
+        let url = match self.selected_month() {
+            Some(month) => format!("http://localhost:8000/report_details?period={}", month),
+            None => "http://localhost:8000/report_details".to_string(),
+        };
+        match self.client.get(&url).bearer_auth(&self.token).send().await {
+            Ok(response) => if response.status() == reqwest::StatusCode::OK {
+                if let Ok(mut cat_sum) = response.json::<Vec<CategorySummary>>().await {
+                    // sort the categories by nickname
+                    cat_sum.sort_unstable_by_key(|item| item.nickname.clone());
+                    self.summary_blocks = cat_sum;
+                    // list states and id mappings are rebuilt for the new blocks on the next render
+                    self.list_states.clear();
+                    self.trans_mapping.clear();
+                    self.active_cat = 0;
                 }
-                _ => {}
             },
             Err(_e) => {}
         }
@@ -159,7 +324,27 @@ impl ReportMain {
             )
             .split(f.area());
 
-        let title = Paragraph::new("REPORT (Category Based)")
+        let mut title_lines = vec![Line::from(match self.selected_month() {
+            Some(month) => format!("REPORT (Category Based) - {}", month),
+            None => "REPORT (Category Based) - Current Periods".to_string(),
+        })];
+        // the cash flow line only covers the current month
+        if let Some(cash_flow) = self.cash_flow.as_ref().filter(|_| self.month_offset == 0) {
+            let savings_str = match cash_flow.savings_rate {
+                Some(rate) => format!("{:.2}%", rate),
+                None => "-".to_string(),
+            };
+            title_lines.push(Line::from(format!(
+                "{}: income {:.2} | expense {:.2} | net {:.2} {} | savings rate {}",
+                cash_flow.bounds.days_label(),
+                cash_flow.income,
+                cash_flow.expense,
+                cash_flow.net_cash_flow,
+                cash_flow.base_currency,
+                savings_str
+            )));
+        }
+        let title = Paragraph::new(title_lines)
             .style(
                 Style::default()
                     .fg(Color::Black)
@@ -170,6 +355,26 @@ impl ReportMain {
 
         f.render_widget(title, chunks[0]);
 
+        if let Some(trans_id) = self.editing_trans {
+            self.render_edit_form(f, chunks[1], trans_id);
+            let notice = Paragraph::new(format!(
+                "{} | Esc: Cancel | Tab: Next Field | Enter: Save",
+                self.message
+            ))
+            .style(Style::default().fg(Color::DarkGray).bg(Color::White))
+            .wrap(Wrap { trim: true })
+            .alignment(Alignment::Center);
+            f.render_widget(notice, chunks[2]);
+            return;
+        }
+
+        if self.summary_blocks.is_empty() {
+            let empty_notice = Paragraph::new("No transactions in this period")
+                .style(Style::default().fg(Color::DarkGray).bg(Color::White))
+                .alignment(Alignment::Center);
+            f.render_widget(empty_notice, chunks[1]);
+        }
+
         // divide the second chunk into one block per category
         //let block_percent = 100 / (self.summary_blocks.len() as u16);
         let mut constraint_vec: Vec<Constraint> = vec![];
@@ -188,7 +393,7 @@ impl ReportMain {
         }
 
         // Bottom notice for navigation instructions (Esc to quit, etc.)
-        let notice = Paragraph::new("Esc: Back | Tab: Switch between Categories | ↑↓: Switch between Transactions | D: Delete Transaction")
+        let notice = Paragraph::new("Esc: Back | ←→: Previous/Next Month | Tab: Switch between Categories | ↑↓: Switch between Transactions | E: Edit Transaction | D: Delete Transaction")
             .style(Style::default().fg(Color::DarkGray).bg(Color::White))
             .wrap(Wrap { trim: true })
             .alignment(Alignment::Center);
@@ -196,24 +401,12 @@ impl ReportMain {
     }
 
     fn render_nested_blocks(&mut self, frame: &mut Frame, area: Rect, i: usize) {
-        let cat_title_str = self.summary_blocks[i].nickname.as_str();
-        let overbudget_status = self.summary_blocks[i].overbudget.clone();
-        let budget = self.summary_blocks[i].budget.clone();
-        let budget_freq = self.summary_blocks[i].budget_freq.clone();
-        let spent = self.summary_blocks[i].total.clone();
         let trans_ids = self.summary_blocks[i].transaction_idz.clone();
         let transactions: Vec<String> = self.summary_blocks[i].cat_trans.clone();
         // divide each summary chunk into budget information line + transaction list
         // outer block is the category information
         // inner block is the transaction details
-        let mut outer_block = title_block(
-            cat_title_str,
-            overbudget_status,
-            budget,
-            budget_freq,
-            spent,
-            self.active_cat == i,
-        );
+        let mut outer_block = title_block(&self.summary_blocks[i], self.active_cat == i);
         let inner_block = Block::default()
             .borders(Borders::NONE)
             .title("Relevant Transactions: ");
@@ -249,7 +442,106 @@ impl ReportMain {
         frame.render_stateful_widget(list, inner, &mut self.list_states[i]);
     }
 
+    fn render_edit_form(&self, f: &mut Frame, area: Rect, trans_id: i32) {
+        let outer_block = Block::bordered()
+            .title(format!("Edit Transaction {}", trans_id))
+            .border_style(Style::default().fg(Color::Black));
+        let inner = outer_block.inner(area);
+        f.render_widget(outer_block, area);
+
+        let field_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(1)
+            .constraints([Constraint::Length(3); 5].as_ref())
+            .split(inner);
+
+        for (i, field) in self.edit_fields.iter().enumerate() {
+            field.render(f, field_chunks[i], self.active_field == i);
+        }
+    }
+
+    // fill the edit form from the selected "date, amount, notes" entry
+    fn open_edit_form(&mut self) {
+        let Some(selected) = self
+            .list_states
+            .get(self.active_cat)
+            .and_then(|list_state| list_state.selected())
+        else {
+            return;
+        };
+        let (Some(&trans_id), Some(summary)) = (
+            self.trans_mapping
+                .get(self.active_cat)
+                .and_then(|id_mapping| id_mapping.get(selected)),
+            self.summary_blocks.get(self.active_cat),
+        ) else {
+            return;
+        };
+        let Some(entry) = summary.cat_trans.get(selected) else {
+            return;
+        };
+
+        let mut parts = entry.splitn(3, ", ");
+        let date_str = parts.next().unwrap_or_default();
+        let amount_str = parts.next().unwrap_or_default();
+        let notes_str = parts.next().unwrap_or_default();
+
+        // amounts in another currency read "12.50 USD"
+        self.edit_fields[0].content = amount_str
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        self.edit_fields[1].content = notes_str.to_string();
+        self.edit_fields[2].content = summary.nickname.clone();
+        self.edit_fields[3].content = String::new();
+        self.edit_fields[4].content = date_str.chars().take(10).collect();
+        self.original_date = self.edit_fields[4].content.clone();
+        self.active_field = 0;
+        self.message = String::new();
+        self.editing_trans = Some(trans_id);
+    }
+
+    async fn handle_edit_input(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Tab => {
+                self.active_field = (self.active_field + 1) % self.edit_fields.len();
+            }
+            KeyCode::BackTab => {
+                self.active_field = if self.active_field == 0 {
+                    self.edit_fields.len() - 1
+                } else {
+                    self.active_field - 1
+                };
+            }
+            KeyCode::Enter => {
+                if let Some(trans_id) = self.editing_trans {
+                    self.update_transaction(trans_id).await;
+                }
+            }
+            _ => self.edit_fields[self.active_field].handle_input(key),
+        }
+    }
+
     async fn handle_list_input(&mut self, key: KeyCode) {
+        match key {
+            // left/right page through budget months, right stops at the current periods
+            KeyCode::Left => {
+                self.month_offset += 1;
+                self.get_categorical_summary().await;
+                return;
+            }
+            KeyCode::Right if self.month_offset > 0 => {
+                self.month_offset -= 1;
+                self.get_categorical_summary().await;
+                return;
+            }
+            _ => {}
+        }
+        // nothing to select on a period without transactions
+        if self.summary_blocks.is_empty() {
+            return;
+        }
         match key {
             // tab switches between category blocks
             KeyCode::Tab => {
@@ -273,9 +565,10 @@ impl ReportMain {
                     },
                 ));
             }
+            KeyCode::Char('e') => self.open_edit_form(),
             KeyCode::Char('d') => {
                 if let Some(selected) = self.list_states[self.active_cat].selected() {
-                    let to_delete_id = self.trans_mapping[self.active_cat][selected].clone();
+                    let to_delete_id = self.trans_mapping[self.active_cat][selected];
                     // pop the transaction from the mapping
                     self.trans_mapping[self.active_cat].remove(selected);
                     // delete the transaction
@@ -288,26 +581,76 @@ impl ReportMain {
 
     pub async fn handle_input(&mut self, key: KeyCode, _modifiers: KeyModifiers) -> bool {
         if key == KeyCode::Esc {
+            if self.editing_trans.is_some() {
+                self.editing_trans = None;
+                return false;
+            }
             return true;
         }
-        self.handle_list_input(key).await;
+        if self.editing_trans.is_some() {
+            self.handle_edit_input(key).await;
+        } else {
+            self.handle_list_input(key).await;
+        }
         false
     }
 
     async fn delete_transaction(&mut self, trans_id: i32) {
         let url = format!("http://localhost:8000/delete_trans?trans_id={}", trans_id);
 
-        match self.client.delete(&url).send().await {
+        match self.client.delete(&url).bearer_auth(&self.token).send().await {
             Ok(response) => {
                 let status = response.status();
-                match status {
-                    reqwest::StatusCode::OK => {
-                        self.get_categorical_summary().await;
-                    }
-                    _ => {}
+                if status == reqwest::StatusCode::OK {
+                    self.initialize().await;
                 }
             }
             Err(_e) => {}
         }
     }
+
+    async fn update_transaction(&mut self, trans_id: i32) {
+        let amount = match non_empty(&self.edit_fields[0]) {
+            Some(amount_str) => match amount_str.parse::<Decimal>() {
+                Ok(value) => Some(value),
+                Err(_) => {
+                    self.message = "Invalid amount".to_string();
+                    return;
+                }
+            },
+            None => None,
+        };
+        let changes = TransactionUpdate {
+            amount,
+            notes: non_empty(&self.edit_fields[1]),
+            category_name: non_empty(&self.edit_fields[2]),
+            account_name: non_empty(&self.edit_fields[3]),
+            transaction_date: non_empty(&self.edit_fields[4])
+                .filter(|date_str| *date_str != self.original_date),
+        };
+
+        let url = format!("http://localhost:8000/update_trans?trans_id={}", trans_id);
+        match self
+            .client
+            .patch(&url)
+            .bearer_auth(&self.token)
+            .json(&changes)
+            .send()
+            .await
+        {
+            Ok(response) => {
+                let status = response.status();
+                let message = response.text().await.unwrap_or_default();
+                if status == reqwest::StatusCode::OK {
+                    self.editing_trans = None;
+                    self.initialize().await;
+                } else {
+                    self.message = format!("Failed to update transaction: {}", message);
+                }
+            }
+            Err(e) => {
+                self.message = format!("Error updating transaction: {}", e);
+            }
+        }
+    }
 }
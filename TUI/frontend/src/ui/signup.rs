@@ -151,7 +151,7 @@ impl SignupPage {
                         self.response_message = "Signup successful! Redirecting to login...".to_string();
                         return true; // Return true to navigate to login
                     }
-                    200 if message.contains("Login successful") => {
+                    409 => {
                         self.response_message = "Email already registered. Please try another one.".to_string();
                     }
                     400 => {
@@ -1,44 +1,75 @@
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout},
     style::{Color, Style},
+    text::{Line, Span},
     widgets::{Block, Paragraph},
     Frame,
 };
+use chrono::Local;
 use crossterm::event::{KeyCode, KeyModifiers};
 use reqwest::Client;
-use serde::Serialize;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
 
 use crate::ui::components::InputField;
 
 #[derive(Serialize)]
 struct TransactionData {
-    email: String,
     category_name: String,
-    amount: f64,
+    amount: Decimal,
     notes: String,
     account_name: String,
+    // left out when the date is today, so the backend keeps the exact time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transaction_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    payee: Option<String>,
 }
 
+#[derive(Deserialize)]
+struct PayeeSummary {
+    name: String,
+}
+
+// How many previous payees are offered while typing one
+const MAX_PAYEE_SUGGESTIONS: usize = 5;
+// Index of the payee among the input fields
+const PAYEE_FIELD: usize = 3;
+
 pub struct TransactionCreate {
     pub category_name: InputField,
     pub amount: InputField,
     pub account_name: InputField,
+    pub payee: InputField,
+    // previous payees starting with what is typed, most used first
+    pub payee_suggestions: Vec<String>,
+    pub selected_suggestion: usize,
     pub notes: InputField,
+    pub date: InputField,
+    pub today: String,
     pub active_field: usize,
     pub response_message: String,
-    pub email: String, // The email is passed from the homepage
+    pub token: String, // The session token is passed from the homepage
 }
 
 impl TransactionCreate {
-    pub fn new(email: String) -> Self {
+    pub fn new(token: String) -> Self {
+        let today = Local::now().format("%Y-%m-%d").to_string();
+        let mut date = InputField::new("Date (YYYY-MM-DD)", false);
+        date.content = today.clone();
         Self {
             category_name: InputField::new("Category Name", false),
             amount: InputField::new("Amount", false),
             account_name: InputField::new("Account Name", false),
+            payee: InputField::new("Payee (optional)", false),
+            payee_suggestions: Vec::new(),
+            selected_suggestion: 0,
             notes: InputField::new("Notes", false),
+            date,
+            today,
             active_field: 0,
             response_message: String::new(),
-            email,
+            token,
         }
     }
 
@@ -55,7 +86,10 @@ impl TransactionCreate {
                     Constraint::Length(3),   // Category Name
                     Constraint::Length(3),   // Amount
                     Constraint::Length(3),   // Account Name
+                    Constraint::Length(3),   // Payee
+                    Constraint::Length(1),   // Payee suggestions
                     Constraint::Length(3),   // Notes (larger)
+                    Constraint::Length(3),   // Date
                     Constraint::Min(3),      // Response message
                     Constraint::Length(3),   // Navigation notice
                 ]
@@ -73,20 +107,46 @@ impl TransactionCreate {
         self.category_name.render(f, chunks[1], self.active_field == 0);
         self.amount.render(f, chunks[2], self.active_field == 1);
         self.account_name.render(f, chunks[3], self.active_field == 2);
-        self.notes.render(f, chunks[4], self.active_field == 3);
+        self.payee.render(f, chunks[4], self.active_field == PAYEE_FIELD);
+        self.notes.render(f, chunks[6], self.active_field == 4);
+        self.date.render(f, chunks[7], self.active_field == 5);
+
+        // Payee suggestions, only while the payee is being typed
+        if self.active_field == PAYEE_FIELD && !self.payee_suggestions.is_empty() {
+            let mut spans = vec![Span::styled(
+                " Previous payees: ",
+                Style::default().fg(Color::DarkGray),
+            )];
+            for (i, suggestion) in self.payee_suggestions.iter().enumerate() {
+                let style = if i == self.selected_suggestion {
+                    Style::default().fg(Color::White).bg(Color::Blue)
+                } else {
+                    Style::default().fg(Color::Black)
+                };
+                spans.push(Span::styled(suggestion.clone(), style));
+                spans.push(Span::raw("  "));
+            }
+            let suggestion_paragraph = Paragraph::new(Line::from(spans))
+                .style(Style::default().bg(Color::White));
+            f.render_widget(suggestion_paragraph, chunks[5]);
+        }
 
         // Response message
         let response_paragraph = Paragraph::new(self.response_message.clone())
             .style(Style::default().fg(Color::DarkGray).bg(Color::White))
             .alignment(Alignment::Center);
-        f.render_widget(response_paragraph, chunks[5]);
+        f.render_widget(response_paragraph, chunks[8]);
 
         // Bottom notice
-        let notice_text = "Esc to quit | Enter to submit";
+        let notice_text = if self.active_field == PAYEE_FIELD && !self.payee_suggestions.is_empty() {
+            "Esc to quit | Enter to submit | ←→: Pick Payee | ↓: Complete Payee"
+        } else {
+            "Esc to quit | Enter to submit"
+        };
         let notice_paragraph = Paragraph::new(notice_text)
             .style(Style::default().fg(Color::DarkGray).bg(Color::White))
             .alignment(Alignment::Center);
-        f.render_widget(notice_paragraph, chunks[6]);
+        f.render_widget(notice_paragraph, chunks[9]);
     }
 
     pub async fn handle_input(&mut self, key: KeyCode, _modifiers: KeyModifiers) -> bool {
@@ -96,20 +156,48 @@ impl TransactionCreate {
 
         match key {
             KeyCode::Tab => {
-                self.active_field = (self.active_field + 1) % 4; // Cycle through input fields
+                self.active_field = (self.active_field + 1) % 6; // Cycle through input fields
+                if self.active_field == PAYEE_FIELD {
+                    self.fetch_payee_suggestions().await;
+                }
             }
             KeyCode::BackTab => {
-                self.active_field = if self.active_field == 0 { 3 } else { self.active_field - 1 };
+                self.active_field = if self.active_field == 0 { 5 } else { self.active_field - 1 };
+                if self.active_field == PAYEE_FIELD {
+                    self.fetch_payee_suggestions().await;
+                }
             }
             KeyCode::Enter => {
                 return self.submit().await;
             }
+            // Pick one of the previous payees
+            KeyCode::Left | KeyCode::Right
+                if self.active_field == PAYEE_FIELD && !self.payee_suggestions.is_empty() =>
+            {
+                let count = self.payee_suggestions.len();
+                self.selected_suggestion = if key == KeyCode::Right {
+                    (self.selected_suggestion + 1) % count
+                } else {
+                    (self.selected_suggestion + count - 1) % count
+                };
+            }
+            KeyCode::Down
+                if self.active_field == PAYEE_FIELD && !self.payee_suggestions.is_empty() =>
+            {
+                self.payee.content = self.payee_suggestions[self.selected_suggestion].clone();
+                self.payee_suggestions.clear();
+            }
             _ => {
                 match self.active_field {
                     0 => self.category_name.handle_input(key),
                     1 => self.amount.handle_input(key),
                     2 => self.account_name.handle_input(key),
-                    3 => self.notes.handle_input(key),
+                    PAYEE_FIELD => {
+                        self.payee.handle_input(key);
+                        self.fetch_payee_suggestions().await;
+                    }
+                    4 => self.notes.handle_input(key),
+                    5 => self.date.handle_input(key),
                     _ => {}
                 }
             }
@@ -117,17 +205,52 @@ impl TransactionCreate {
         false
     }
 
+    // Previous payees starting with what is typed so far, a failed request just offers none
+    async fn fetch_payee_suggestions(&mut self) {
+        let client = Client::new();
+        let typed = self.payee.content.trim().to_string();
+        let response = client
+            .get("http://0.0.0.0:8000/payees")
+            .bearer_auth(&self.token)
+            .query(&[("search", typed.as_str())])
+            .send()
+            .await;
+        let previous = match response {
+            Ok(response) if response.status().is_success() => {
+                response.json::<Vec<PayeeSummary>>().await.unwrap_or_default()
+            }
+            _ => Vec::new(),
+        };
+        self.payee_suggestions = previous
+            .into_iter()
+            .map(|payee| payee.name)
+            // nothing left to complete once the name is typed in full
+            .filter(|name| name != &typed)
+            .take(MAX_PAYEE_SUGGESTIONS)
+            .collect();
+        self.selected_suggestion = 0;
+    }
+
     pub async fn submit(&mut self) -> bool {
         let client = Client::new();
         let transaction_data = TransactionData {
-            email: self.email.clone(),
             category_name: self.category_name.content.clone(),
-            amount: self.amount.content.parse().unwrap_or(0.0),
+            amount: self.amount.content.trim().parse().unwrap_or_default(),
             notes: self.notes.content.clone(),
             account_name: self.account_name.content.clone(),
+            transaction_date: match self.date.content.trim() {
+                "" => None,
+                date_str if date_str == self.today => None,
+                date_str => Some(date_str.to_string()),
+            },
+            payee: match self.payee.content.trim() {
+                "" => None,
+                payee_name => Some(payee_name.to_string()),
+            },
         };
 
         match client.post("http://0.0.0.0:8000/add_trans")
+            .bearer_auth(&self.token)
             .json(&transaction_data)
             .send()
             .await